@@ -1,180 +1,307 @@
 use crate::{
+    archivist::{ArchiveRequest, Archivist},
+    assistant::{assistant_version, delete_assistant, shared_assistant},
+    campaign::CampaignConfig,
     character::{
-        CharacterSheet, CharacterSheetBuilder, CharacterSheetUpdate, CharacterValue, Contact, Item,
-        MatrixAttributes, Quality, Race, Skills, UpdateOperation,
+        CharacterSheet, CharacterSheetBuilder, CharacterSheetUpdate, CharacterValue, Contact,
+        Skills, UpdateOperation,
     },
-    dice::{DiceRollRequest, perform_dice_roll},
-    error::{AIError, AppError, Error, Result, ShadowrunError},
+    combat::{Combat, StatusEffect},
+    dice::{
+        Condition, DiceRollRequest, ExtendedTestRequest, FireWeaponRequest, OpposedTestRequest,
+        dice_roll, fire_weapon, perform_dice_roll, perform_extended_test, perform_opposed_test,
+        soak_damage,
+    },
+    error::{Error, Result, ShadowrunError},
     game_state::GameState,
     imager::generate_and_save_image,
+    lifestyle::DAYS_PER_MONTH,
     message::AIMessage,
     message::UserCompletionRequest,
-    message::{self, Message, MessageType},
-};
-use async_openai::{
-    Client,
-    config::OpenAIConfig,
-    types::{
-        CreateMessageRequestArgs, CreateRunRequestArgs, CreateThreadRequestArgs, MessageContent,
-        MessageRole, RunObject, RunStatus, RunToolCallObject, SubmitToolOutputsRunRequest,
-        ToolsOutputs,
+    message::{self, Message},
+    provider::{
+        Backend, LlmProvider, Provider, ReplayProvider, ReplaySession, SessionEntry, record_entry,
     },
+    retry::{self, EventStream},
+    settings::{Capability, ModelClient, RetryPolicy, SessionMode, Settings},
+    tool_log::ToolCallEntry,
+    tools::*,
+    usage::Usage,
 };
-use serde_json::Value;
-use std::{collections::HashMap, path::PathBuf};
-use tokio::{
-    sync::mpsc,
-    time::{Duration, Instant},
-};
+use async_openai::{Client, config::OpenAIConfig};
+use backoff::ExponentialBackoffBuilder;
+use std::{collections::HashMap, time::Duration};
+use tokio::sync::mpsc::{self, error::SendError};
 
 #[derive(Debug)]
 pub struct GameAI {
     pub client: Client<OpenAIConfig>,
+    pub model: String,
+    pub speech: ModelClient,
+    pub images: ModelClient,
+    pub session: SessionMode,
+    pub replay: ReplaySession,
+    pub retry: RetryPolicy,
     pub ai_sender: mpsc::UnboundedSender<AIMessage>,
-    pub image_sender: mpsc::UnboundedSender<PathBuf>,
+    // The turn the requests are made for, what they send is tagged with it.
+    pub turn_id: Option<u64>,
 }
 
 impl Clone for GameAI {
     fn clone(&self) -> Self {
         GameAI {
             client: self.client.clone(),
+            model: self.model.clone(),
+            speech: self.speech.clone(),
+            images: self.images.clone(),
+            session: self.session,
+            replay: self.replay.clone(),
+            retry: self.retry,
             ai_sender: self.ai_sender.clone(),
-            image_sender: self.image_sender.clone(),
+            turn_id: self.turn_id,
         }
     }
 }
 
 impl GameAI {
-    pub async fn new(
-        api_key: &str,
-        ai_sender: mpsc::UnboundedSender<AIMessage>,
-        image_sender: mpsc::UnboundedSender<PathBuf>,
-    ) -> Result<Self> {
-        let openai_config = OpenAIConfig::new().with_api_key(api_key);
-        let client = Client::with_config(openai_config);
-
-        Ok(Self {
-            client,
+    pub fn new(settings: &Settings, ai_sender: mpsc::UnboundedSender<AIMessage>) -> Self {
+        let narration = settings.model_client(Capability::Narration);
+        // The RetryPolicy retries the GM requests and tells the player, the client would do it
+        // silently for minutes.
+        let no_backoff = ExponentialBackoffBuilder::new()
+            .with_max_elapsed_time(Some(Duration::ZERO))
+            .build();
+
+        Self {
+            client: narration.client.with_backoff(no_backoff),
+            model: narration.model,
+            speech: settings.model_client(Capability::Speech),
+            images: settings.model_client(Capability::Images),
+            session: settings.session,
+            replay: ReplaySession::default(),
+            retry: settings.retry,
             ai_sender,
-            image_sender,
-        })
+            turn_id: None,
+        }
     }
 
-    pub async fn start_new_conversation(
+    // The backend is a property of the save, so old Assistants campaigns keep their thread.
+    pub fn provider(&self, game_state: &GameState) -> Provider {
+        if self.session == SessionMode::Replay {
+            return Provider::Replay(ReplayProvider::new(self.replay.clone()));
+        }
+        Provider::new(
+            &game_state.backend,
+            self.client.clone(),
+            game_state.campaign.model(&self.model),
+        )
+    }
+
+    // Chat turns stop when their task is aborted, Assistants runs go on remotely until cancelled.
+    pub async fn cancel_turn(&self, game_state: &GameState) -> Result<()> {
+        if let Provider::Assistants(provider) = self.provider(game_state) {
+            provider.cancel_active_run(&game_state.thread_id).await?;
+        }
+        Ok(())
+    }
+
+    // Removes an undone turn from the Assistants thread, the Chat history lives in the GameState.
+    pub async fn delete_thread_messages(
         &self,
-        assistant_id: &str,
-        save_name: &str,
-    ) -> Result<GameState> {
-        let thread = self
-            .client
-            .threads()
-            .create(
-                CreateThreadRequestArgs::default()
-                    .build()
-                    .map_err(AIError::OpenAI)?,
-            )
-            .await
-            .map_err(AIError::OpenAI)?;
+        game_state: &GameState,
+        message_ids: &[String],
+    ) -> Result<()> {
+        if let Provider::Assistants(provider) = self.provider(game_state) {
+            provider
+                .delete_messages(&game_state.thread_id, message_ids)
+                .await?;
+        }
+        Ok(())
+    }
 
-        let game_state = GameState::new(
-            assistant_id.to_string(),
-            thread.id.to_string(),
-            save_name.to_string(),
-        );
+    // Writes the exchange to the session file of the save when recording.
+    fn record(&self, game_state: &GameState, entry: SessionEntry) {
+        if self.session == SessionMode::Record
+            && let Err(e) = record_entry(game_state, &entry)
+        {
+            log::error!("Failed to record the session: {e:#?}");
+        }
+    }
 
-        let initial_message = CreateMessageRequestArgs::default()
-            .role(MessageRole::User)
-            .content("Start the game by assisting the player to create a character. Answer in valid json")
-            .build().map_err(AIError::OpenAI)?;
+    // The same AI, playing the turn with this id.
+    pub fn for_turn(&self, turn_id: u64) -> Self {
+        GameAI {
+            turn_id: Some(turn_id),
+            ..self.clone()
+        }
+    }
 
-        self.client
-            .threads()
-            .messages(&thread.id)
-            .create(initial_message)
-            .await
-            .map_err(AIError::OpenAI)?;
+    // Sends what the turn being played produced, tagged with it so that it can be dropped once
+    // the turn is taken back.
+    pub fn send(&self, message: AIMessage) -> std::result::Result<(), SendError<AIMessage>> {
+        self.ai_sender.send(message.for_turn(self.turn_id))
+    }
 
-        Ok(game_state)
+    // Counts what a request cost against the campaign. It stays counted when the turn is taken
+    // back, so it is not tagged.
+    pub fn report_usage(&self, usage: impl Into<Usage>) {
+        if let Err(e) = self.ai_sender.send(AIMessage::Usage(usage.into())) {
+            log::error!("Failed to send the usage: {e:#?}");
+        }
     }
 
-    pub async fn send_message(
-        &self,
-        mut message: UserCompletionRequest,
-        ai_sender: mpsc::UnboundedSender<AIMessage>,
-    ) -> Result<()> {
-        // serialize
-        let formatted = serde_json::to_string(&message.message)?;
+    // Tells the player that a request failed and is about to be sent again.
+    pub fn report_retry(&self, attempt: u32, max_attempts: u32) {
+        if let Err(e) = self.send(AIMessage::Retrying(attempt, max_attempts)) {
+            log::error!("Failed to send the retry: {e:#?}");
+        }
+    }
+
+    pub async fn with_retry<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        retry::with_retry(
+            &self.retry,
+            |attempt, max| self.report_retry(attempt, max),
+            request,
+        )
+        .await
+    }
+
+    pub async fn with_retry_stream<T, F, Fut>(&self, request: F) -> Result<EventStream<T>>
+    where
+        T: Send + 'static,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<EventStream<T>>>,
+    {
+        retry::with_retry_stream(
+            &self.retry,
+            |attempt, max| self.report_retry(attempt, max),
+            request,
+        )
+        .await
+    }
 
-        self.add_message_to_thread(&message.state.thread_id, &formatted)
+    // Moves an Assistants save to the shared assistant of the model at the current version.
+    // Saves from before shared assistants owned theirs, it is deleted once replaced.
+    // Returns whether the save changed.
+    pub async fn migrate_assistant(&self, game_state: &mut GameState) -> Result<bool> {
+        if self.session == SessionMode::Replay || game_state.backend != Backend::Assistants {
+            return Ok(false);
+        }
+        let model = game_state.campaign.model(&self.model).to_string();
+        let version = assistant_version(&model)?;
+        if game_state.assistant_version.as_ref() == Some(&version) {
+            return Ok(false);
+        }
+        let assistant_id = self
+            .with_retry(|| shared_assistant(&self.client, &model))
             .await?;
+        if game_state.assistant_version.is_none() && game_state.assistant_id != assistant_id {
+            delete_assistant(&self.client, &game_state.assistant_id).await;
+        }
+        log::info!(
+            "Moved {} to the assistant {assistant_id}: {version}",
+            game_state.save_name
+        );
+        game_state.assistant_id = assistant_id;
+        game_state.assistant_version = Some(version);
+        Ok(true)
+    }
 
-        let run = self
-            .create_run(&message.state.thread_id, &message.state.assistant_id)
-            .await?; // ① propagate errors instead of unwrap/expect
+    pub async fn start_new_conversation(
+        &self,
+        save_name: &str,
+        campaign: CampaignConfig,
+    ) -> Result<GameState> {
+        let mut game_state = GameState::new(Backend::ChatCompletions, save_name.to_string());
+        game_state.campaign = campaign;
+        self.provider(&game_state)
+            .start_conversation(&mut game_state)
+            .await?;
+        Ok(game_state)
+    }
 
-        let thread_id = message.state.thread_id.clone();
+    pub async fn send_message(&self, message: UserCompletionRequest) -> Result<()> {
+        self.play_turn(message, false).await
+    }
 
-        loop {
-            match self.wait_for_run_completion(&thread_id, &run.id).await? {
-                Some(run) => {
-                    self.handle_required_action(&run, message.state.clone())
-                        .await?;
-                }
-                None => {
-                    let response = self.get_latest_message(&thread_id).await?;
-                    let game_msg = self.update_game_state(&mut message.state, &response)?;
-                    ai_sender
-                        .send(AIMessage::Response(game_msg))
-                        .map_err(Error::AISend)?; // ② convert SendError
-                    break;
-                }
-            }
+    // Asks the GM for a new reply to the last player turn, the previous reply is discarded.
+    pub async fn reroll_message(&self, message: UserCompletionRequest) -> Result<()> {
+        self.play_turn(message, true).await
+    }
+
+    async fn play_turn(&self, mut message: UserCompletionRequest, reroll: bool) -> Result<()> {
+        // serialize
+        let formatted = serde_json::to_string(&message.message)?;
+        self.record(
+            &message.state,
+            SessionEntry::Request {
+                content: formatted.clone(),
+            },
+        );
+
+        let provider = self.provider(&message.state);
+        let response = if reroll {
+            provider.reroll_turn(self, &mut message.state).await?
+        } else {
+            provider
+                .send_turn(self, &mut message.state, &formatted)
+                .await?
+        };
+        self.record(
+            &message.state,
+            SessionEntry::Response {
+                content: response.clone(),
+            },
+        );
+        let game_msg = self.update_game_state(&mut message.state, &response)?;
+
+        if message.state.backend == Backend::ChatCompletions {
+            self.send(AIMessage::UpdateHistory(message.state.history))
+                .map_err(Error::AISend)?;
         }
+        self.send(AIMessage::Response(game_msg))
+            .map_err(Error::AISend)?;
         Ok(())
     }
 
-    //
-    async fn wait_for_run_completion(
-        &self,
-        thread_id: &str,
-        run_id: &str,
-    ) -> Result<Option<RunObject>> {
-        let timeout_duration = Duration::from_secs(60 * 3);
-        let start_time = Instant::now();
-
-        loop {
-            if start_time.elapsed() > timeout_duration {
-                self.cancel_run(thread_id, run_id).await?;
-                return Err(AppError::Timeout.into());
-            }
-
-            let run = self
-                .client
-                .threads()
-                .runs(thread_id)
-                .retrieve(run_id)
-                .await
-                .map_err(AIError::OpenAI)?;
-
-            match run.status {
-                RunStatus::Completed => {
-                    return Ok(None);
-                }
-                RunStatus::RequiresAction => return Ok(Some(run)),
-                RunStatus::Failed
-                | RunStatus::Incomplete
-                | RunStatus::Cancelling
-                | RunStatus::Cancelled
-                | RunStatus::Expired => {
-                    let _ = self.cancel_run(thread_id, run_id).await;
-                    return Err(format!("Run failed with status: {:#?}", run.status).into());
+    // Lets the Archivist fold a turn into the campaign memory of the game without delaying the
+    // next turn. It always answers with an UpdateMemory, None when it failed, and returns false
+    // when it does not run at all.
+    pub fn archive_turn(&self, request: ArchiveRequest, game_state: &GameState) -> bool {
+        if self.session == SessionMode::Replay {
+            return false;
+        }
+        let archivist = Archivist::new(self.client.clone(), game_state.campaign.model(&self.model));
+        let memory = game_state.memory.clone();
+        let ai = self.clone();
+        tokio::spawn(async move {
+            let update = retry::with_retry(
+                &ai.retry,
+                |_, _| {},
+                || archivist.update(&memory, &request.player_action, &request.reply),
+            );
+            let memory = match update.await {
+                Ok((memory, usage)) => {
+                    ai.report_usage(usage);
+                    Some(memory)
                 }
-                RunStatus::InProgress | RunStatus::Queued => {
-                    tokio::time::sleep(Duration::from_millis(200)).await;
-                    continue;
+                Err(e) => {
+                    log::error!("The Archivist failed to update the memory: {e:#?}");
+                    None
                 }
             };
-        }
+            if let Err(e) = ai
+                .ai_sender
+                .send(AIMessage::UpdateMemory(request.turn_id, memory))
+            {
+                log::error!("Failed to send the campaign memory: {e:#?}");
+            }
+        });
+        true
     }
 
     fn update_game_state(
@@ -215,92 +342,219 @@ impl GameAI {
 
         Ok(())
     }
-    //
-    pub async fn cancel_run(&self, thread_id: &str, run_id: &str) -> Result<()> {
-        self.client
-            .threads()
-            .runs(thread_id)
-            .cancel(run_id)
-            .await
-            .map_err(|e| ShadowrunError::OpenAI(e.to_string()))
-            .map_err(AppError::Shadowrun)?;
-        Ok(())
-    }
-    //
-    async fn handle_required_action(&self, run: &RunObject, game_state: GameState) -> Result<()> {
-        if let Some(required_action) = &run.required_action {
-            match required_action.r#type.as_str() {
-                "submit_tool_outputs" => self.handle_tool_outputs(run, game_state).await,
-                _ => Err(ShadowrunError::Game(format!(
-                    "Unknown required action type: {}",
-                    required_action.r#type
-                ))
-                .into()),
+
+    // Runs a single tool call requested by the model and returns its output.
+    // Invalid arguments are reported back to the model instead of failing the turn.
+    pub fn handle_tool_call(
+        &self,
+        name: &str,
+        arguments: &str,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let output = match self.run_tool(name, arguments, game_state) {
+            Ok(output) => output,
+            Err(Error::Tool(e)) => {
+                log::warn!("{e}");
+                e.to_output()
             }
-        } else {
-            Err(ShadowrunError::Game("No required action found".to_string()).into())
+            Err(e) => return Err(e),
+        };
+        self.record(
+            game_state,
+            SessionEntry::ToolCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+                output: output.clone(),
+            },
+        );
+        // A replayed call was logged when it was recorded.
+        if self.session != SessionMode::Replay {
+            self.send(AIMessage::ToolCall(ToolCallEntry::new(
+                name, arguments, &output,
+            )))?;
         }
+        Ok(output)
     }
-    //
-    async fn handle_tool_outputs(&self, run: &RunObject, game_state: GameState) -> Result<()> {
-        let mut tool_outputs = Vec::new();
-        let required_action = run.required_action.clone().unwrap();
-
-        for tool_call in required_action.submit_tool_outputs.tool_calls {
-            let output = match tool_call.function.name.as_str() {
-                "create_character_sheet" => self.handle_create_character_sheet(&tool_call)?,
-                "perform_dice_roll" => self.handle_perform_dice_roll(&tool_call, &game_state)?,
-                "generate_character_image" => self.handle_generate_character_image(&tool_call)?,
-                "update_basic_attributes" => self.handle_update_basic_attributes(&tool_call)?,
-                "update_skills" => self.handle_update_skills(&tool_call)?,
-                "update_inventory" => self.handle_update_inventory(&tool_call)?,
-                "update_qualities" => self.handle_update_qualities(&tool_call)?,
-                "update_matrix_attributes" => self.handle_update_matrix_attributes(&tool_call)?,
-                "update_contacts" => self.handle_update_contacts(&tool_call)?,
-                "update_augmentations" => self.handle_update_augmentations(&tool_call)?,
-                _ => {
-                    return Err(ShadowrunError::Game(format!(
-                        "Unknown function: {}",
-                        tool_call.function.name
-                    ))
-                    .into());
-                }
-            };
 
-            tool_outputs.push(ToolsOutputs {
-                tool_call_id: Some(tool_call.id.clone()),
-                output: Some(output),
-            });
+    // Changes to the combat, the damage and the Edge are applied to the state of the turn as
+    // well, so that the following calls of the same reply see them.
+    fn run_tool(&self, name: &str, arguments: &str, game_state: &mut GameState) -> Result<String> {
+        match name {
+            "create_character_sheet" => {
+                self.handle_create_character_sheet(parse_arguments(name, arguments)?)
+            }
+            "perform_dice_roll" => {
+                self.handle_perform_dice_roll(parse_arguments(name, arguments)?, game_state)
+            }
+            "perform_opposed_test" => {
+                self.handle_perform_opposed_test(parse_arguments(name, arguments)?, game_state)
+            }
+            "perform_extended_test" => {
+                self.handle_perform_extended_test(parse_arguments(name, arguments)?, game_state)
+            }
+            "fire_weapon" => self.handle_fire_weapon(parse_arguments(name, arguments)?, game_state),
+            "generate_character_image" => {
+                self.handle_generate_character_image(parse_arguments(name, arguments)?)
+            }
+            "update_basic_attributes" => {
+                self.handle_update_basic_attributes(parse_arguments(name, arguments)?)
+            }
+            "apply_damage" => {
+                self.handle_apply_damage(parse_arguments(name, arguments)?, game_state)
+            }
+            "heal" => self.handle_heal(parse_arguments(name, arguments)?, game_state),
+            "start_combat" => {
+                self.handle_start_combat(parse_arguments(name, arguments)?, game_state)
+            }
+            "update_combat" => {
+                self.handle_update_combat(parse_arguments(name, arguments)?, game_state)
+            }
+            "dead_mans_trigger" => {
+                self.handle_dead_mans_trigger(parse_arguments(name, arguments)?, game_state)
+            }
+            "refresh_edge" => {
+                self.handle_refresh_edge(parse_arguments(name, arguments)?, game_state)
+            }
+            "update_skills" => self.handle_update_skills(parse_arguments(name, arguments)?),
+            "update_inventory" => self.handle_update_inventory(parse_arguments(name, arguments)?),
+            "update_gear" => self.handle_update_gear(parse_arguments(name, arguments)?, game_state),
+            "update_qualities" => self.handle_update_qualities(parse_arguments(name, arguments)?),
+            "update_matrix_attributes" => {
+                self.handle_update_matrix_attributes(parse_arguments(name, arguments)?)
+            }
+            "update_contacts" => self.handle_update_contacts(parse_arguments(name, arguments)?),
+            "update_augmentations" => {
+                self.handle_update_augmentations(parse_arguments(name, arguments)?, game_state)
+            }
+            "update_lifestyle" => {
+                self.handle_update_lifestyle(parse_arguments(name, arguments)?, game_state)
+            }
+            "pass_time" => self.handle_pass_time(parse_arguments(name, arguments)?, game_state),
+            _ => Err(ToolError {
+                function: name.to_string(),
+                details: vec![format!("Unknown function: {name}")],
+            }
+            .into()),
         }
+    }
 
-        self.submit_tool_outputs(&run.thread_id, &run.id, tool_outputs)
-            .await
+    fn request_update(
+        &self,
+        character_name: &str,
+        attribute: &str,
+        operation: UpdateOperation<CharacterValue>,
+    ) -> Result<()> {
+        self.send(AIMessage::RequestCharacterUpdate(
+            CharacterSheetUpdate::Attribute {
+                attribute: attribute.to_string(),
+                operation,
+            },
+            character_name.to_string(),
+        ))?;
+        Ok(())
+    }
+
+    // For the updates that later calls of the turn depend on.
+    fn update_character(
+        &self,
+        game_state: &mut GameState,
+        character_name: &str,
+        update: CharacterSheetUpdate,
+    ) -> Result<()> {
+        if let Some(character) = game_state
+            .characters
+            .iter_mut()
+            .find(|c| c.name == character_name)
+        {
+            character.apply_update(&update)?;
+        }
+        self.send(AIMessage::RequestCharacterUpdate(
+            update,
+            character_name.to_string(),
+        ))?;
+        Ok(())
     }
+
     //
-    fn handle_create_character_sheet(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_sheet = match self.create_character(&args) {
-            Ok(sheet) => sheet,
+    fn handle_create_character_sheet(&self, args: CreateCharacterSheetArgs) -> Result<String> {
+        let character_sheet = self.create_character(args);
+        self.send(AIMessage::AddCharacter(character_sheet.clone()))?;
+        Ok(serde_json::to_string(&character_sheet)?)
+    }
+
+    fn handle_perform_dice_roll(
+        &self,
+        args: DiceRollRequest,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let character_name = args.character_name.clone();
+        let response = match perform_dice_roll(args, game_state) {
+            Ok(response) => {
+                if response.edge_spent {
+                    self.update_character(
+                        game_state,
+                        &character_name,
+                        CharacterSheetUpdate::SpendEdge,
+                    )?;
+                }
+                serde_json::to_string(&response)?
+            }
             Err(e) => {
-                log::error!("Could not create character: {:#?}", e);
-                self.create_dummy_character()
+                let err = format!("Failed to perform_dice_roll: {e:#?}");
+                log::error!("{err}");
+                err
             }
         };
-        self.ai_sender
-            .send(AIMessage::AddCharacter(character_sheet.clone()))?;
-        Ok(serde_json::to_string(&character_sheet)?)
+
+        Ok(response)
     }
 
-    fn handle_perform_dice_roll(
+    fn handle_perform_opposed_test(
+        &self,
+        args: OpposedTestRequest,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let attacker = args.attacker.character_name.clone();
+        let defender = args.defender.character_name.clone();
+        let response = match perform_opposed_test(args, game_state) {
+            Ok(response) => {
+                let defender_edge_spent = response
+                    .opposed
+                    .as_ref()
+                    .is_some_and(|opposed| opposed.defender_edge_spent);
+                for (character_name, edge_spent) in [
+                    (&attacker, response.edge_spent),
+                    (&defender, defender_edge_spent),
+                ] {
+                    if edge_spent {
+                        self.update_character(
+                            game_state,
+                            character_name,
+                            CharacterSheetUpdate::SpendEdge,
+                        )?;
+                    }
+                }
+                serde_json::to_string(&response)?
+            }
+            Err(e) => {
+                let err = format!("Failed to perform_opposed_test: {e:#?}");
+                log::error!("{err}");
+                err
+            }
+        };
+
+        Ok(response)
+    }
+
+    fn handle_perform_extended_test(
         &self,
-        tool_call: &RunToolCallObject,
+        args: ExtendedTestRequest,
         game_state: &GameState,
     ) -> Result<String> {
-        let args: DiceRollRequest = serde_json::from_str(&tool_call.function.arguments)?;
-        let response = match perform_dice_roll(args, game_state) {
+        let response = match perform_extended_test(args, game_state) {
             Ok(response) => serde_json::to_string(&response)?,
             Err(e) => {
-                let err = format!("Failed to perform_dice_roll: {e:#?}");
+                let err = format!("Failed to perform_extended_test: {e:#?}");
                 log::error!("{err}");
                 err
             }
@@ -309,19 +563,257 @@ impl GameAI {
         Ok(response)
     }
 
-    fn handle_generate_character_image(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: Value = serde_json::from_str(&tool_call.function.arguments)
-            .map_err(|e| Error::Shadowrun(ShadowrunError::Serialization(e.to_string())))?;
+    // The rounds fired leave the clip, whatever the result of the attack.
+    fn handle_fire_weapon(
+        &self,
+        args: FireWeaponRequest,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let attacker = args.character_name.clone();
+        let defender = args
+            .defender
+            .as_ref()
+            .map(|defender| defender.character_name.clone());
+        let response = match fire_weapon(args, game_state) {
+            Ok(response) => {
+                if let Some(attack) = &response.attack {
+                    self.update_character(
+                        game_state,
+                        &attacker,
+                        CharacterSheetUpdate::FireWeapon {
+                            item: attack.weapon.clone(),
+                            rounds: attack.rounds,
+                        },
+                    )?;
+                }
+                let defender_edge_spent = response
+                    .opposed
+                    .as_ref()
+                    .is_some_and(|opposed| opposed.defender_edge_spent);
+                for (character_name, edge_spent) in [
+                    (Some(&attacker), response.edge_spent),
+                    (defender.as_ref(), defender_edge_spent),
+                ] {
+                    if let Some(character_name) = character_name
+                        && edge_spent
+                    {
+                        self.update_character(
+                            game_state,
+                            character_name,
+                            CharacterSheetUpdate::SpendEdge,
+                        )?;
+                    }
+                }
+                serde_json::to_string(&response)?
+            }
+            Err(e) => {
+                let err = format!("Failed to fire_weapon: {e:#?}");
+                log::error!("{err}");
+                err
+            }
+        };
 
-        let image_sender = self.image_sender.clone();
-        let client = self.client.clone();
-        log::info!("handle_generate_character_image: {tool_call:#?}");
+        Ok(response)
+    }
+
+    fn handle_apply_damage(
+        &self,
+        args: ApplyDamageArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let character =
+            find_character("apply_damage", &args.character_name, &game_state.characters)?;
+        let response = soak_damage(
+            character.clone(),
+            args.damage_type,
+            args.damage_value,
+            args.armor_penetration,
+            args.soak,
+        );
+        self.update_character(
+            game_state,
+            &args.character_name,
+            CharacterSheetUpdate::Damage {
+                damage_type: response.damage_type,
+                boxes: response.boxes,
+            },
+        )?;
+        Ok(serde_json::to_string(&response)?)
+    }
+
+    fn handle_heal(&self, args: HealArgs, game_state: &mut GameState) -> Result<String> {
+        find_character("heal", &args.character_name, &game_state.characters)?;
+        self.update_character(
+            game_state,
+            &args.character_name,
+            CharacterSheetUpdate::Heal {
+                damage_type: args.damage_type,
+                boxes: args.boxes,
+            },
+        )?;
+        let character = find_character("heal", &args.character_name, &game_state.characters)?;
+        Ok(serde_json::to_string(&Condition::from(character))?)
+    }
+
+    fn handle_dead_mans_trigger(
+        &self,
+        args: DeadMansTriggerArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let character = find_character(
+            "dead_mans_trigger",
+            &args.character_name,
+            &game_state.characters,
+        )?;
+        let edge = character.attributes.edge;
+        check_edge_left("dead_mans_trigger", character)?;
+        self.update_character(
+            game_state,
+            &args.character_name,
+            CharacterSheetUpdate::SpendEdge,
+        )?;
+        let roll = dice_roll(edge, None, Some(3), None, 0);
+        let character = find_character(
+            "dead_mans_trigger",
+            &args.character_name,
+            &game_state.characters,
+        )?;
+        Ok(serde_json::json!({
+            "hits": roll.hits,
+            "dice_results": roll.dice_results,
+            "success": roll.success,
+            "edge_points_left": character.derived_attributes.edge_points,
+        })
+        .to_string())
+    }
+
+    fn handle_refresh_edge(
+        &self,
+        args: RefreshEdgeArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        find_character("refresh_edge", &args.character_name, &game_state.characters)?;
+        self.update_character(
+            game_state,
+            &args.character_name,
+            CharacterSheetUpdate::RefreshEdge {
+                points: args.points,
+            },
+        )?;
+        let character =
+            find_character("refresh_edge", &args.character_name, &game_state.characters)?;
+        Ok(format!(
+            "{} has {}/{} Edge points",
+            character.name, character.derived_attributes.edge_points, character.attributes.edge
+        ))
+    }
+
+    fn handle_start_combat(
+        &self,
+        args: StartCombatArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let combatants = if args.combatants.is_empty() {
+            game_state.characters.iter().collect()
+        } else {
+            args.combatants
+                .iter()
+                .map(|name| find_character("start_combat", name, &game_state.characters))
+                .collect::<Result<Vec<_>>>()?
+        };
+        if combatants.is_empty() {
+            return Err(ToolError {
+                function: "start_combat".to_string(),
+                details: vec!["No character to fight, create them first".to_string()],
+            }
+            .into());
+        }
+        let combat = Combat::start(&combatants);
+        let summary = combat.summary().to_string();
+        game_state.combat = Some(combat);
+        self.send(AIMessage::UpdateCombat(game_state.combat.clone()))?;
+        Ok(summary)
+    }
+
+    fn handle_update_combat(
+        &self,
+        args: UpdateCombatArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        let tool_error = |details: String| ToolError {
+            function: "update_combat".to_string(),
+            details: vec![details],
+        };
+        let Some(combat) = game_state.combat.as_mut() else {
+            return Err(
+                tool_error("No combat is going on, call start_combat first".to_string()).into(),
+            );
+        };
+        let edge_action = match &args {
+            UpdateCombatArgs::SeizeTheInitiative { character_name }
+            | UpdateCombatArgs::Blitz { character_name } => Some(character_name.clone()),
+            _ => None,
+        };
+        let result = match args {
+            UpdateCombatArgs::NextTurn => {
+                combat.next_turn(&game_state.characters);
+                Ok(())
+            }
+            UpdateCombatArgs::Interrupt {
+                character_name,
+                initiative_cost,
+            } => combat.interrupt(&character_name, initiative_cost),
+            UpdateCombatArgs::AddStatusEffect {
+                character_name,
+                status_effect,
+                rounds,
+            } => combat.add_status_effect(
+                &character_name,
+                StatusEffect {
+                    name: status_effect,
+                    rounds,
+                },
+            ),
+            UpdateCombatArgs::RemoveStatusEffect {
+                character_name,
+                status_effect,
+            } => combat.remove_status_effect(&character_name, &status_effect),
+            UpdateCombatArgs::SeizeTheInitiative { character_name } => {
+                let character =
+                    find_character("update_combat", &character_name, &game_state.characters)?;
+                check_edge_left("update_combat", character)?;
+                combat.seize_the_initiative(&character_name)
+            }
+            UpdateCombatArgs::Blitz { character_name } => {
+                let character =
+                    find_character("update_combat", &character_name, &game_state.characters)?;
+                check_edge_left("update_combat", character)?;
+                combat.blitz(character)
+            }
+            UpdateCombatArgs::EndCombat => {
+                game_state.combat = None;
+                Ok(())
+            }
+        };
+        result.map_err(tool_error)?;
+        if let Some(character_name) = edge_action {
+            self.update_character(game_state, &character_name, CharacterSheetUpdate::SpendEdge)?;
+        }
+        self.send(AIMessage::UpdateCombat(game_state.combat.clone()))?;
+        Ok(match &game_state.combat {
+            Some(combat) => combat.summary().to_string(),
+            None => "Combat ended".to_string(),
+        })
+    }
+
+    fn handle_generate_character_image(&self, args: GenerateCharacterImageArgs) -> Result<String> {
+        let ai = self.clone();
+        log::info!("handle_generate_character_image: {args:#?}");
         tokio::spawn(async move {
-            match generate_and_save_image(client, &args["image_generation_prompt"].to_string())
-                .await
-            {
+            match generate_and_save_image(ai.images.clone(), &args.image_generation_prompt).await {
                 Ok(path) => {
-                    if let Err(e) = image_sender.send(path) {
+                    ai.report_usage(Usage::image());
+                    if let Err(e) = ai.send(AIMessage::Image(path)) {
                         log::error!("Failed to send the Image path: {e:#?}");
                     }
                 }
@@ -334,806 +826,447 @@ impl GameAI {
         Ok("Generating image...".to_string())
     }
 
-    fn vec_to_map(&self, vec: &serde_json::Value) -> HashMap<String, u8> {
-        vec.as_array()
-            .unwrap_or(&vec![])
-            .iter()
-            .filter_map(|entry| {
-                Some((
-                    entry.get("name")?.as_str()?.to_string(),
-                    entry.get("rating")?.as_u64()? as u8,
-                ))
-            })
-            .collect()
-    }
-    fn map_skills(&self, value: &serde_json::Value) -> Result<Skills> {
-        Ok(Skills {
-            combat: self.vec_to_map(&value["combat"]),
-            physical: self.vec_to_map(&value["physical"]),
-            social: self.vec_to_map(&value["social"]),
-            technical: self.vec_to_map(&value["technical"]),
-        })
-    }
-
-    fn handle_update_basic_attributes(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?
-            .to_string();
-        let updates = &args["updates"];
-
-        for (attr, value) in updates.as_object().expect("Value should be an object") {
-            let update = CharacterSheetUpdate::Attribute {
-                attribute: attr.to_string(),
-                operation: UpdateOperation::Modify(self.parse_value(attr, value)?),
-            };
-            self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-                update,
-                character_name.to_string(),
-            ))?;
+    fn handle_update_basic_attributes(&self, args: UpdateBasicAttributesArgs) -> Result<String> {
+        let BasicAttributeUpdates {
+            name,
+            race,
+            gender,
+            backstory,
+            body,
+            agility,
+            reaction,
+            strength,
+            willpower,
+            logic,
+            intuition,
+            charisma,
+            edge,
+            magic,
+            resonance,
+            nuyen,
+        } = args.updates;
+
+        let strings = [("name", name), ("gender", gender), ("backstory", backstory)]
+            .into_iter()
+            .filter_map(|(attribute, value)| Some((attribute, CharacterValue::String(value?))));
+        let ratings = [
+            ("body", body),
+            ("agility", agility),
+            ("reaction", reaction),
+            ("strength", strength),
+            ("willpower", willpower),
+            ("logic", logic),
+            ("intuition", intuition),
+            ("charisma", charisma),
+            ("edge", edge),
+        ]
+        .into_iter()
+        .filter_map(|(attribute, value)| Some((attribute, CharacterValue::U8(value?))));
+        let special = [("magic", magic), ("resonance", resonance)]
+            .into_iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(attribute, value)| (attribute, CharacterValue::OptionU8(value)));
+        let others = [
+            race.map(|race| ("race", CharacterValue::Race(race))),
+            nuyen.map(|nuyen| ("nuyen", CharacterValue::Nuyen(nuyen))),
+        ]
+        .into_iter()
+        .flatten();
+
+        for (attribute, value) in strings.chain(ratings).chain(special).chain(others) {
+            self.request_update(
+                &args.character_name,
+                attribute,
+                UpdateOperation::Modify(value),
+            )?;
         }
 
         Ok(format!(
             "Updated basic attributes for character: {}",
-            character_name
+            args.character_name
         ))
     }
-    fn handle_update_skills(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let updates = &args["updates"]["skills"];
 
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?
-            .to_string();
+    fn handle_update_lifestyle(
+        &self,
+        args: UpdateLifestyleArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        const FUNCTION: &str = "update_lifestyle";
+        let update = CharacterSheetUpdate::ChangeLifestyle {
+            tier: args.tier,
+            options: args.options,
+            prepay_months: args.prepay_months,
+        };
+        find_character(FUNCTION, &args.character_name, &game_state.characters)?
+            .clone()
+            .apply_update(&update)
+            .map_err(|e| ToolError {
+                function: FUNCTION.to_string(),
+                details: vec![e.to_string()],
+            })?;
+        self.update_character(game_state, &args.character_name, update)?;
+        let character = find_character(FUNCTION, &args.character_name, &game_state.characters)?;
+        Ok(serde_json::json!({
+            "lifestyle": character.lifestyle,
+            "monthly_cost": character.lifestyle.monthly_cost(),
+            "healing_modifier": character.lifestyle.healing_modifier(),
+            "nuyen": character.nuyen,
+        })
+        .to_string())
+    }
 
-        let skills_update: Skills = self.map_skills(updates)?;
+    // Rent is due for every character each time a month of in-game time ends.
+    fn handle_pass_time(&self, args: PassTimeArgs, game_state: &mut GameState) -> Result<String> {
+        let days = game_state.days.saturating_add(args.days);
+        let months = days / DAYS_PER_MONTH - game_state.days / DAYS_PER_MONTH;
+        game_state.days = days;
+        self.send(AIMessage::UpdateDays(days))?;
+
+        let mut upkeep = Vec::new();
+        if months > 0 {
+            let names: Vec<String> = game_state
+                .characters
+                .iter()
+                .filter(|character| character.lifestyle.monthly_cost() > 0)
+                .map(|character| character.name.clone())
+                .collect();
+            for name in names {
+                let character = find_character("pass_time", &name, &game_state.characters)?;
+                upkeep.push(character.clone().pay_upkeep(months));
+                self.update_character(
+                    game_state,
+                    &name,
+                    CharacterSheetUpdate::PayUpkeep { months },
+                )?;
+            }
+        }
+        Ok(serde_json::json!({
+            "day": days,
+            "days_until_rent": DAYS_PER_MONTH - days % DAYS_PER_MONTH,
+            "upkeep": upkeep,
+        })
+        .to_string())
+    }
 
-        let skills_update = CharacterSheetUpdate::Attribute {
-            attribute: "skills".to_string(),
-            operation: UpdateOperation::Modify(crate::character::CharacterValue::Skills(
-                skills_update,
-            )),
+    fn handle_update_skills(&self, args: UpdateSkillsArgs) -> Result<String> {
+        let SkillUpdates {
+            skills,
+            knowledge_skills,
+            specializations,
+            skill_groups,
+            knowledge_categories,
+            languages,
+            native_language,
+        } = args.updates;
+
+        let skills = Skills {
+            combat: skills.combat,
+            physical: skills.physical,
+            social: skills.social,
+            technical: skills.technical,
         };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            skills_update,
-            character_name.to_string(),
-        ))?;
+        self.request_update(
+            &args.character_name,
+            "skills",
+            UpdateOperation::Modify(CharacterValue::Skills(skills)),
+        )?;
+
+        if !knowledge_skills.is_empty() {
+            self.request_update(
+                &args.character_name,
+                "knowledge_skills",
+                UpdateOperation::Modify(CharacterValue::HashMapStringU8(knowledge_skills)),
+            )?;
+        }
 
-        // Update knowledge skills
-        if let Some(knowledge_skills_value) = updates.get("knowledge") {
-            let knowledge_skills = self.vec_to_map(knowledge_skills_value);
-            let knowledge_update = CharacterSheetUpdate::Attribute {
-                attribute: "knowledge_skills".to_string(),
-                operation: UpdateOperation::Modify(CharacterValue::HashMapStringU8(
-                    knowledge_skills,
-                )),
-            };
-            self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-                knowledge_update,
-                character_name.to_string(),
-            ))?;
+        let updates = [
+            (!specializations.is_empty()).then_some((
+                "specializations",
+                CharacterValue::HashMapStringString(specializations),
+            )),
+            (!skill_groups.is_empty()).then_some((
+                "skill_groups",
+                CharacterValue::HashMapStringU8(skill_groups),
+            )),
+            (!knowledge_categories.is_empty()).then_some((
+                "knowledge_categories",
+                CharacterValue::HashMapStringKnowledgeCategory(knowledge_categories),
+            )),
+            (!languages.is_empty())
+                .then_some(("languages", CharacterValue::HashMapStringU8(languages))),
+            native_language.map(|language| ("native_language", CharacterValue::String(language))),
+        ];
+        for (attribute, value) in updates.into_iter().flatten() {
+            self.request_update(
+                &args.character_name,
+                attribute,
+                UpdateOperation::Modify(value),
+            )?;
         }
 
-        Ok(format!("Updated skills for character: {}", &character_name))
+        Ok(format!(
+            "Updated skills for character: {}",
+            args.character_name
+        ))
     }
 
-    fn handle_update_inventory(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or(ShadowrunError::Game("Missing character_name".to_string()))?;
-        let operation = args["operation"]
-            .as_str()
-            .ok_or(ShadowrunError::Game("Missing operation".to_string()))?;
-        let items = args
-            .get("items")
-            .or_else(|| args.get("item"))
-            .ok_or(ShadowrunError::Game(
-                "No items or item for add/modify".to_string(),
-            ))?;
-
-        let mut changed_items: HashMap<String, Item> = HashMap::new();
-
-        match operation {
-            "Remove" => {
-                // For removal, we only need the item names
-                let item_names: Vec<String> = match items {
-                    items if items.is_array() => serde_json::from_value(items.clone())?,
-                    items if items.is_object() && items.get("name").is_some() => {
-                        vec![items["name"].as_str().expect("Not a String").to_string()]
-                    }
-                    items if items.is_object() && items.get("name").is_none() => items
-                        .as_object()
-                        .expect("Value should be an object")
-                        .keys()
-                        .cloned()
-                        .collect(),
-                    _ => {
-                        return Err(ShadowrunError::Game(
-                            "Invalid items format for removal".to_string(),
-                        )
-                        .into());
-                    }
-                };
-                item_names.iter().for_each(|name| {
-                    changed_items.insert(
-                        name.clone(),
-                        Item {
-                            name: name.clone(),
-                            quantity: 1,
-                            description: String::new(),
-                        },
-                    );
-                });
-            }
-            "Add" | "Modify" => {
-                match items {
-                    items if items.is_object() && items.get("name").is_some() => {
-                        // Single item
-                        let item: Item = serde_json::from_value(items.clone())?;
-                        changed_items.insert(item.name.clone(), item);
-                    }
-                    items if items.is_object() && items.get("name").is_none() => {
-                        // Multiple items
-                        for (key, value) in items.as_object().expect("Value should be an object") {
-                            if !value.is_object() {
-                                return Err(ShadowrunError::Game(
-                                    "Invalid item format".to_string(),
-                                )
-                                .into());
-                            } else {
-                                let item = Item {
-                                    name: key.clone(),
-                                    quantity: value["quantity"].as_u64().unwrap_or(1) as u32,
-                                    description: value["description"]
-                                        .as_str()
-                                        .unwrap_or("")
-                                        .to_string(),
-                                };
-                                changed_items.insert(key.clone(), item);
-                            }
-                        }
-                    }
-                    _ => {
-                        return Err(ShadowrunError::Game(
-                            "Invalid inventory operation".to_string(),
-                        )
-                        .into());
-                    }
-                };
-            }
-            _ => {
-                return Err(ShadowrunError::Game("Invalid inventory operation".to_string()).into());
-            }
-        };
-
-        let update = CharacterSheetUpdate::Attribute {
-            attribute: "inventory".to_string(),
-            operation: match operation {
-                "Remove" => {
-                    UpdateOperation::Remove(CharacterValue::HashMapStringItem(changed_items))
-                }
-                "Add" => UpdateOperation::Add(CharacterValue::HashMapStringItem(changed_items)),
-                "Modify" => {
-                    UpdateOperation::Modify(CharacterValue::HashMapStringItem(changed_items))
-                }
-                _ => unreachable!(),
-            },
-        };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            update,
-            character_name.to_string(),
-        ))?;
+    fn handle_update_inventory(&self, args: UpdateInventoryArgs) -> Result<String> {
+        let items = HashMap::from([(args.item.name.clone(), args.item)]);
+        self.request_update(
+            &args.character_name,
+            "inventory",
+            args.operation
+                .update(CharacterValue::HashMapStringItem(items)),
+        )?;
 
         Ok(format!(
             "Updated inventory for character: {}",
-            character_name
+            args.character_name
         ))
     }
 
-    fn handle_update_qualities(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?;
-        let operation = args["operation"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing operation".to_string()))?;
-        let qualities = &args["qualities"];
-
-        let new_qualities: Vec<Quality> = serde_json::from_value(qualities.clone())?;
-
-        let update = CharacterSheetUpdate::Attribute {
-            attribute: "qualities".to_string(),
-            operation: match operation {
-                "Add" => UpdateOperation::Add(crate::character::CharacterValue::VecQuality(
-                    new_qualities,
-                )),
-                "Remove" => UpdateOperation::Remove(crate::character::CharacterValue::VecQuality(
-                    new_qualities,
-                )),
-                _ => {
-                    return Err(
-                        ShadowrunError::Game("Invalid qualities operation".to_string()).into(),
-                    );
-                }
+    fn handle_update_gear(
+        &self,
+        args: UpdateGearArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        const FUNCTION: &str = "update_gear";
+        let update = match args.action {
+            GearAction::Equip | GearAction::Unequip => CharacterSheetUpdate::EquipItem {
+                item: args.item_name.clone(),
+                equipped: args.action == GearAction::Equip,
+            },
+            GearAction::Reload => CharacterSheetUpdate::ReloadWeapon {
+                item: args.item_name.clone(),
             },
         };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            update,
-            character_name.to_string(),
-        ))?;
+        find_character(FUNCTION, &args.character_name, &game_state.characters)?
+            .clone()
+            .apply_update(&update)
+            .map_err(|e| ToolError {
+                function: FUNCTION.to_string(),
+                details: vec![e.to_string()],
+            })?;
+        self.update_character(game_state, &args.character_name, update)?;
+        let character = find_character(FUNCTION, &args.character_name, &game_state.characters)?;
+        let equipped: Vec<String> = character
+            .inventory
+            .values()
+            .filter(|item| item.equipped)
+            .map(|item| match &item.weapon {
+                Some(weapon) => format!("{} ({})", item.name, weapon.stats()),
+                None => item.name.clone(),
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "equipped": equipped,
+            "armor": character.derived_attributes.armor,
+        })
+        .to_string())
+    }
+
+    fn handle_update_qualities(&self, args: UpdateQualitiesArgs) -> Result<String> {
+        self.request_update(
+            &args.character_name,
+            "qualities",
+            args.operation
+                .update(CharacterValue::VecQuality(args.qualities)),
+        )?;
 
         Ok(format!(
             "Updated qualities for character: {}",
-            character_name
+            args.character_name
         ))
     }
 
-    fn handle_update_matrix_attributes(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?;
-        let matrix_attributes = &args["matrix_attributes"];
-
-        let new_matrix_attributes: MatrixAttributes =
-            serde_json::from_value(matrix_attributes.clone())?;
-
-        let update = CharacterSheetUpdate::Attribute {
-            attribute: "matrix_attributes".to_string(),
-            operation: UpdateOperation::Modify(
-                crate::character::CharacterValue::OptionMatrixAttributes(Some(
-                    new_matrix_attributes,
-                )),
-            ),
-        };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            update,
-            character_name.to_string(),
-        ))?;
+    fn handle_update_matrix_attributes(&self, args: UpdateMatrixAttributesArgs) -> Result<String> {
+        self.request_update(
+            &args.character_name,
+            "matrix_attributes",
+            UpdateOperation::Modify(CharacterValue::OptionMatrixAttributes(Some(
+                args.matrix_attributes,
+            ))),
+        )?;
 
         Ok(format!(
             "Updated matrix attributes for character: {}",
-            character_name
+            args.character_name
         ))
     }
 
-    fn handle_update_contacts(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?;
-        let operation = args["operation"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing operation".to_string()))?;
-        let contacts = &args["contacts"];
-
-        // Deserialize into Vec<Contact>
-        let new_contacts_vec: Vec<Contact> = serde_json::from_value(contacts.clone())?;
-
-        // Convert Vec<Contact> into HashMap<String, Contact>
-        let new_contacts: HashMap<String, Contact> = new_contacts_vec
+    fn handle_update_contacts(&self, args: UpdateContactsArgs) -> Result<String> {
+        let contacts: HashMap<String, Contact> = args
+            .contacts
             .into_iter()
             .map(|contact| (contact.name.clone(), contact))
             .collect();
+        self.request_update(
+            &args.character_name,
+            "contacts",
+            args.operation
+                .update(CharacterValue::HashMapStringContact(contacts)),
+        )?;
 
-        let update = CharacterSheetUpdate::Attribute {
-            attribute: "contacts".to_string(),
-            operation: match operation {
-                "Add" => UpdateOperation::Add(
-                    crate::character::CharacterValue::HashMapStringContact(new_contacts),
-                ),
-                "Remove" => UpdateOperation::Remove(
-                    crate::character::CharacterValue::HashMapStringContact(new_contacts),
-                ),
-                "Modify" => UpdateOperation::Modify(
-                    crate::character::CharacterValue::HashMapStringContact(new_contacts),
-                ),
-                _ => {
-                    return Err(
-                        ShadowrunError::Game("Invalid contacts operation".to_string()).into(),
-                    );
-                }
-            },
-        };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            update,
-            character_name.to_string(),
-        ))?;
         Ok(format!(
             "Updated contacts for character: {}",
-            character_name
-        ))
-    }
-
-    fn handle_update_augmentations(&self, tool_call: &RunToolCallObject) -> Result<String> {
-        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
-        let character_name = args["character_name"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing character_name".to_string()))?;
-        let operation = args["operation"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing operation".to_string()))?;
-        let augmentation_type = args["augmentation_type"]
-            .as_str()
-            .ok_or_else(|| ShadowrunError::Game("Missing augmentation_type".to_string()))?;
-        let augmentations = &args["augmentations"];
-
-        let new_augmentations: Vec<String> = serde_json::from_value(augmentations.clone())?;
-
-        let update = CharacterSheetUpdate::Attribute {
-            attribute: augmentation_type.to_string(),
-            operation: match operation {
-                "Add" => UpdateOperation::Add(crate::character::CharacterValue::VecString(
-                    new_augmentations,
-                )),
-                "Remove" => UpdateOperation::Remove(crate::character::CharacterValue::VecString(
-                    new_augmentations,
-                )),
-                _ => {
-                    return Err(ShadowrunError::Game(
-                        "Invalid augmentations operation".to_string(),
-                    )
-                    .into());
-                }
-            },
-        };
-        self.ai_sender.send(AIMessage::RequestCharacterUpdate(
-            update,
-            character_name.to_string(),
-        ))?;
-        Ok(format!(
-            "{} updated for character '{}'. Operation: {}",
-            augmentation_type, character_name, operation
+            args.character_name
         ))
     }
 
-    // Helper method to parse values based on attribute type
-    fn parse_value(
+    // Each augmentation is checked on a copy of the sheet first, so that a missing one or the
+    // lack of essence goes back to the model.
+    fn handle_update_augmentations(
         &self,
-        attribute: &str,
-        value: &Value,
-    ) -> Result<crate::character::CharacterValue> {
-        match attribute {
-            "name" | "gender" | "backstory" | "lifestyle" => {
-                Ok(crate::character::CharacterValue::String(
-                    value
-                        .as_str()
-                        .ok_or_else(|| ShadowrunError::Game("Invalid string value".to_string()))?
-                        .to_string(),
-                ))
-            }
-            "race" => Ok(crate::character::CharacterValue::Race(
-                match value
-                    .as_str()
-                    .ok_or_else(|| ShadowrunError::Game("Invalid race value".to_string()))?
-                {
-                    "Human" => Race::Human,
-                    "Elf" => Race::Elf,
-                    "Dwarf" => Race::Dwarf,
-                    "Ork" => Race::Ork,
-                    "Troll" => Race::Troll,
-                    _ => return Err(ShadowrunError::Game("Invalid race".to_string()).into()),
+        args: UpdateAugmentationsArgs,
+        game_state: &mut GameState,
+    ) -> Result<String> {
+        const FUNCTION: &str = "update_augmentations";
+        for augmentation in args.augmentations {
+            let update = match args.operation {
+                Operation::Add => CharacterSheetUpdate::InstallAugmentation {
+                    augmentation_type: args.augmentation_type,
+                    augmentation,
                 },
-            )),
-            "body" | "agility" | "reaction" | "strength" | "willpower" | "logic" | "intuition"
-            | "charisma" | "edge" => Ok(crate::character::CharacterValue::U8(
-                value
-                    .as_u64()
-                    .ok_or_else(|| ShadowrunError::Game("Invalid u8 value".to_string()))?
-                    as u8,
-            )),
-            "magic" | "resonance" => Ok(crate::character::CharacterValue::OptionU8(
-                value.as_u64().map(|v| v as u8),
-            )),
-            "nuyen" => Ok(crate::character::CharacterValue::Nuyen(
-                value.as_u64().ok_or_else(|| {
-                    ShadowrunError::Game("Invalid u32 value for nuyen".to_string())
-                })? as u32,
-            )),
-            "skills" => Ok(crate::character::CharacterValue::Skills(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "knowledge_skills" => Ok(crate::character::CharacterValue::HashMapStringU8(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "contacts" => Ok(crate::character::CharacterValue::HashMapStringContact(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "qualities" => Ok(crate::character::CharacterValue::VecQuality(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "cyberware" | "bioware" => Ok(crate::character::CharacterValue::VecString(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "inventory" => Ok(crate::character::CharacterValue::HashMapStringItem(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            "matrix_attributes" => Ok(crate::character::CharacterValue::OptionMatrixAttributes(
-                serde_json::from_value(value.clone())
-                    .map_err(|e| ShadowrunError::Serialization(e.to_string()))?,
-            )),
-            _ => Err(ShadowrunError::Game(format!("Unsupported attribute: {}", attribute)).into()),
-        }
-    }
-    //
-    //     // Asynchronous method to fetch all messages from a thread, ordered and formatted appropriately.
-    pub async fn fetch_all_messages(&self, thread_id: &str) -> Result<Vec<Message>> {
-        let mut all_messages = Vec::new();
-        let mut before: Option<String> = None;
-        loop {
-            let mut params = vec![("order", "desc"), ("limit", "100")];
-            if let Some(before_id) = &before {
-                params.push(("before", before_id));
-            }
-            let messages = self
-                .client
-                .threads()
-                .messages(thread_id)
-                .list(&params)
-                .await
-                .map_err(|e| Error::from(AIError::OpenAI(e)))?;
-
-            for message in messages.data.into_iter().rev() {
-                if let Some(MessageContent::Text(text_content)) = message.content.first() {
-                    let message_type = match message.role {
-                        MessageRole::User => MessageType::User,
-                        MessageRole::Assistant => MessageType::Game,
-                    };
-                    all_messages.push(Message::new(message_type, text_content.text.value.clone()));
+                Operation::Remove => CharacterSheetUpdate::RemoveAugmentation {
+                    augmentation_type: args.augmentation_type,
+                    name: augmentation.name,
+                },
+                Operation::Modify => {
+                    return Err(ToolError {
+                        function: FUNCTION.to_string(),
+                        details: vec!["Augmentations can only be added or removed".to_string()],
+                    }
+                    .into());
                 }
-            }
-
-            if messages.has_more {
-                before = messages.first_id;
-            } else {
-                break;
-            }
-        }
-        Ok(all_messages)
-    }
-
-    // Asynchronous method to retrieve the latest message from a conversation thread.
-    async fn get_latest_message(&self, thread_id: &str) -> Result<String> {
-        let messages = self
-            .client
-            .threads()
-            .messages(thread_id)
-            .list(&[("limit", "1")])
-            .await
-            .map_err(|e| Error::from(AIError::OpenAI(e)))?;
-
-        if let Some(latest_message) = messages.data.first() {
-            if let Some(MessageContent::Text(text_content)) = latest_message.content.first() {
-                return Ok(text_content.text.value.clone());
-            }
+            };
+            find_character(FUNCTION, &args.character_name, &game_state.characters)?
+                .clone()
+                .apply_update(&update)
+                .map_err(|e| ToolError {
+                    function: FUNCTION.to_string(),
+                    details: vec![e.to_string()],
+                })?;
+            self.update_character(game_state, &args.character_name, update)?;
         }
-        Err(AIError::NoMessageFound.into())
-    }
 
-    //
-    async fn add_message_to_thread(&self, thread_id: &str, message: &str) -> Result<()> {
-        let message_request = CreateMessageRequestArgs::default()
-            .role(MessageRole::User)
-            .content(message)
-            .build()
-            .map_err(AIError::OpenAI)?;
-        self.client
-            .threads()
-            .messages(thread_id)
-            .create(message_request)
-            .await
-            .map_err(AIError::OpenAI)?;
-        Ok(())
-    }
-    //
-    async fn create_run(&self, thread_id: &str, assistant_id: &str) -> Result<RunObject> {
-        let run_request = CreateRunRequestArgs::default()
-            .assistant_id(assistant_id)
-            .build()
-            .map_err(AIError::OpenAI)?;
-        Ok(self
-            .client
-            .threads()
-            .runs(thread_id)
-            .create(run_request)
-            .await
-            .map_err(AIError::OpenAI)?)
+        let character = find_character(FUNCTION, &args.character_name, &game_state.characters)?;
+        Ok(format!(
+            "{} updated for character '{}'. Operation: {}. Essence: {:.2}, Magic: {}, Resonance: {}, Initiative: {}+{}d6",
+            args.augmentation_type,
+            args.character_name,
+            args.operation,
+            character.derived_attributes.essence.current,
+            character.magic.magic.unwrap_or(0),
+            character.resonance.resonance.unwrap_or(0),
+            character.derived_attributes.initiative.0,
+            character.derived_attributes.initiative.1,
+        ))
     }
 
-    //     // Asynchronous method to submit output from a tool during a run.
-    async fn submit_tool_outputs(
-        &self,
-        thread_id: &str,
-        run_id: &str,
-        tool_outputs: Vec<ToolsOutputs>,
-    ) -> Result<()> {
-        let submit_request = SubmitToolOutputsRunRequest {
-            tool_outputs,
-            stream: None,
-        };
-
-        self.client
-            .threads()
-            .runs(thread_id)
-            .submit_tool_outputs(run_id, submit_request)
-            .await
-            .map_err(AIError::OpenAI)?;
-
-        Ok(())
+    // Asynchronous method to fetch all messages of the conversation, ordered and formatted appropriately.
+    pub async fn fetch_all_messages(&self, game_state: &GameState) -> Result<Vec<Message>> {
+        let messages = self.provider(game_state).fetch_messages(game_state).await?;
+        self.record(
+            game_state,
+            SessionEntry::Messages {
+                messages: messages.clone(),
+            },
+        );
+        Ok(messages)
     }
 
-    // Asynchronous method to create a character based on provided arguments, handling attributes and skills.
-    pub fn create_character(&self, args: &Value) -> Result<CharacterSheet> {
-        // Helper function to extract a string
-        fn extract_str(args: &Value, field: &str) -> Result<String> {
-            let args_string = args
-                .get(field)
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| {
-                    AIError::GameStateParseError(format!("Missing or invalid {}", field))
-                })?
-                .to_string();
-            Ok(args_string)
-        }
-
-        // Helper function to extract a u8
-        fn extract_u8(args: &Value, field: &str) -> Result<u8> {
-            let args_u8 = args
-                .get(field)
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| {
-                    AIError::GameStateParseError(format!("Missing or invalid {}", field))
-                })
-                .and_then(|v| {
-                    u8::try_from(v).map_err(|_| {
-                        AIError::GameStateParseError(format!("{} out of range", field))
-                    })
-                })?;
-            Ok(args_u8)
-        }
-
-        // Helper function to extract an optional u8
-        fn extract_u8_opt(args: &Value, field: &str) -> Option<u8> {
-            args.get(field)
-                .and_then(|v| v.as_u64())
-                .and_then(|v| u8::try_from(v).ok())
-        }
-
-        // Extract basic information
-        let name = extract_str(args, "name")?;
-        let race_str = extract_str(args, "race")?;
-        let gender = extract_str(args, "gender")?;
-        let backstory = extract_str(args, "backstory")?;
-        let main = args.get("main").and_then(|v| v.as_bool()).unwrap_or(false);
-
-        // Parse race
-        let race = match race_str.as_str() {
-            "Human" => Race::Human,
-            "Elf" => Race::Elf,
-            "Dwarf" => Race::Dwarf,
-            "Ork" => Race::Ork,
-            "Troll" => Race::Troll,
-            _ => return Err(AIError::GameStateParseError("Invalid race".to_string()).into()),
-        };
-
-        // Extract attributes
-        let attributes = args
-            .get("attributes")
-            .ok_or_else(|| AIError::GameStateParseError("Missing attributes".to_string()))?;
-        let body = extract_u8(attributes, "body")?;
-        let agility = extract_u8(attributes, "agility")?;
-        let reaction = extract_u8(attributes, "reaction")?;
-        let strength = extract_u8(attributes, "strength")?;
-        let willpower = extract_u8(attributes, "willpower")?;
-        let logic = extract_u8(attributes, "logic")?;
-        let intuition = extract_u8(attributes, "intuition")?;
-        let charisma = extract_u8(attributes, "charisma")?;
-        let edge = extract_u8(attributes, "edge")?;
-        let magic = extract_u8_opt(attributes, "magic");
-        let resonance = extract_u8_opt(attributes, "resonance");
-
-        // Extract skills
-        let skills_obj = args
-            .get("skills")
-            .ok_or_else(|| AIError::GameStateParseError("Missing skills".to_string()))?;
-        let mut skills = Skills {
-            combat: HashMap::new(),
-            physical: HashMap::new(),
-            social: HashMap::new(),
-            technical: HashMap::new(),
-        };
-
-        for (category, skills_map) in [
-            ("combat", &mut skills.combat),
-            ("physical", &mut skills.physical),
-            ("social", &mut skills.social),
-            ("technical", &mut skills.technical),
-        ] {
-            if let Some(category_array) = skills_obj.get(category).and_then(|v| v.as_array()) {
-                for skill in category_array {
-                    let name = extract_str(skill, "name")?;
-                    let rating = extract_u8(skill, "rating")?;
-                    skills_map.insert(name, rating);
-                }
-            }
-        }
-
-        let mut knowledge_skills = HashMap::new();
-        if let Some(knowledge_skills_array) = skills_obj.get("knowledge").and_then(|v| v.as_array())
-        {
-            for skill in knowledge_skills_array {
-                let name = extract_str(skill, "name")?;
-                let rating = extract_u8(skill, "rating")?;
-                knowledge_skills.insert(name, rating);
-            }
-        }
+    // Builds the character sheet requested by the model.
+    pub fn create_character(&self, args: CreateCharacterSheetArgs) -> CharacterSheet {
+        let CreateCharacterSheetArgs {
+            name,
+            race,
+            gender,
+            backstory,
+            main,
+            attributes,
+            skills,
+            qualities,
+            nuyen,
+            contacts,
+        } = args;
+
+        let contacts = contacts
+            .into_iter()
+            .map(|contact| (contact.name.clone(), contact))
+            .collect();
 
-        // Extract qualities
-        let qualities = args
-            .get("qualities")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| AIError::GameStateParseError("Missing qualities".to_string()))?
-            .iter()
-            .map(|quality| {
-                let name = extract_str(quality, "name")?;
-                let positive = quality
-                    .get("positive")
-                    .and_then(|v| v.as_bool())
-                    .ok_or_else(|| {
-                        AIError::GameStateParseError(
-                            "Missing or invalid 'positive' in quality".to_string(),
-                        )
-                    })?;
-                Ok(Quality { name, positive })
+        CharacterSheetBuilder::new(name, race, gender, backstory, main)
+            .body(attributes.body)
+            .agility(attributes.agility)
+            .reaction(attributes.reaction)
+            .strength(attributes.strength)
+            .willpower(attributes.willpower)
+            .logic(attributes.logic)
+            .intuition(attributes.intuition)
+            .charisma(attributes.charisma)
+            .edge(attributes.edge)
+            .magic(attributes.magic)
+            .resonance(attributes.resonance)
+            .skills(Skills {
+                combat: skill_map(&skills.combat),
+                physical: skill_map(&skills.physical),
+                social: skill_map(&skills.social),
+                technical: skill_map(&skills.technical),
             })
-            .collect::<Result<Vec<Quality>>>()?;
-
-        // Extract nuyen
-        let nuyen = args.get("nuyen").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
-
-        // Extract inventory
-        let inventory = args
-            .get("inventory")
-            .and_then(|v| v.get("items"))
-            .and_then(|items| items.as_array())
-            .map(|items_array| {
-                items_array
+            .knowledge_skills(skill_map(&skills.knowledge))
+            .specializations(specialization_map(
+                skills
+                    .combat
                     .iter()
-                    .filter_map(|item| {
-                        let name = item.get("name")?.as_str()?;
-                        let quantity = item.get("quantity")?.as_u64()? as u32;
-                        let description = item.get("description")?.as_str()?.to_string();
-
-                        Some((
-                            name.to_string(),
-                            Item {
-                                name: name.to_string(),
-                                quantity,
-                                description,
-                            },
-                        ))
-                    })
-                    .collect::<HashMap<String, Item>>()
-            })
-            .unwrap_or_default();
-        // Extract contacts
-        let contacts = args
-            .get("contacts")
-            .and_then(|v| v.as_array())
-            .map(|contacts_array| {
-                contacts_array
+                    .chain(&skills.physical)
+                    .chain(&skills.social)
+                    .chain(&skills.technical),
+            ))
+            .knowledge_categories(
+                skills
+                    .knowledge
                     .iter()
-                    .filter_map(|contact| {
-                        let name = contact.get("name")?.as_str()?.to_string();
-                        let description = contact.get("description")?.as_str()?.to_string();
-                        let loyalty = contact.get("loyalty")?.as_u64()? as u8;
-                        let connection = contact.get("connection")?.as_u64()? as u8;
-
-                        Some((
-                            name.clone(),
-                            Contact {
-                                name,
-                                description,
-                                loyalty,
-                                connection,
-                            },
-                        ))
-                    })
-                    .collect::<HashMap<String, Contact>>()
-            })
-            .unwrap_or_default();
-
-        // Create base character sheet using the builder pattern
-        let character = CharacterSheetBuilder::new(name, race, gender, backstory, main)
-            .body(body)
-            .agility(agility)
-            .reaction(reaction)
-            .strength(strength)
-            .willpower(willpower)
-            .logic(logic)
-            .intuition(intuition)
-            .charisma(charisma)
-            .edge(edge)
-            .magic(magic.unwrap_or(0))
-            .resonance(resonance.unwrap_or(0))
-            .skills(skills)
-            .knowledge_skills(knowledge_skills)
+                    .filter_map(|skill| Some((skill.name.clone(), skill.category?)))
+                    .collect(),
+            )
+            .skill_groups(skill_map(&skills.groups))
+            .languages(skill_map(&skills.languages))
+            .native_language(skills.native_language)
             .qualities(qualities)
             .nuyen(nuyen)
-            .inventory(inventory)
+            .inventory(HashMap::new())
             .contacts(contacts)
-            .build();
-
-        Ok(character)
-    }
-
-    //     // Method to create a dummy character as a fallback during error handling.
-    fn create_dummy_character(&self) -> CharacterSheet {
-        // self.add_debug_message("Creating dummy character.".to_string());
-        let dummy_skills = Skills {
-            combat: [
-                ("Unarmed Combat".to_string(), 1),
-                ("Pistols".to_string(), 1),
-            ]
-            .iter()
-            .cloned()
-            .collect(),
-            physical: [("Running".to_string(), 1), ("Sneaking".to_string(), 1)]
-                .iter()
-                .cloned()
-                .collect(),
-            social: [("Etiquette".to_string(), 1), ("Negotiation".to_string(), 1)]
-                .iter()
-                .cloned()
-                .collect(),
-            technical: [("Computer".to_string(), 1), ("First Aid".to_string(), 1)]
-                .iter()
-                .cloned()
-                .collect(),
-        };
-        let dummy_knowledge = HashMap::new();
-
-        CharacterSheetBuilder::new(
-            "Dummy Character".to_string(),
-            Race::Human,
-            "Unspecified".to_string(),
-            "This is a dummy character created as a fallback.".to_string(),
-            false, // main
-        )
-        .body(3)
-        .agility(3)
-        .reaction(3)
-        .strength(3)
-        .willpower(3)
-        .logic(3)
-        .intuition(3)
-        .charisma(3)
-        .edge(3)
-        .magic(0)
-        .resonance(0)
-        .skills(dummy_skills)
-        .knowledge_skills(dummy_knowledge)
-        .qualities(vec![])
-        .nuyen(5000)
-        .inventory(HashMap::new())
-        .contacts(HashMap::new())
-        .build()
+            .build()
     }
 }
 
-#[cfg(test)]
-mod tests {
+// An unknown name goes back to the model, like any other invalid argument.
+fn find_character<'a>(
+    function: &str,
+    character_name: &str,
+    characters: &'a [CharacterSheet],
+) -> Result<&'a CharacterSheet> {
+    characters
+        .iter()
+        .find(|c| c.name == character_name)
+        .ok_or_else(|| {
+            ToolError {
+                function: function.to_string(),
+                details: vec![format!("Character '{character_name}' not found")],
+            }
+            .into()
+        })
+}
 
-    #[test]
-    fn test_update_skills() {}
+// Edge actions are refused to characters without points left.
+fn check_edge_left(function: &str, character: &CharacterSheet) -> Result<()> {
+    if character.derived_attributes.edge_points == 0 {
+        return Err(ToolError {
+            function: function.to_string(),
+            details: vec![format!("{} has no Edge points left", character.name)],
+        }
+        .into());
+    }
+    Ok(())
 }
@@ -1,19 +1,22 @@
 use crate::{
     ai::GameAI,
-    assistant::create_assistant,
     audio::{self, AudioNarration, Transcription},
+    campaign::CampaignConfig,
     character::{CharacterSheet, CharacterSheetUpdate},
     context::Context,
     error::Result,
     game_state::GameState,
     imager::load_image_from_file,
     message::{
-        AIMessage, GameMessage, Message, MessageType, UserCompletionRequest, create_user_message,
+        AIMessage, Fluff, GameMessage, Message, MessageType, UserCompletionRequest,
+        create_user_message,
     },
     save::{SaveManager, get_save_base_dir},
-    settings::Settings,
+    settings::{Capability, Settings},
+    tool_log::{SheetChange, append_tool_call, sheet_diff},
     tui::{Tui, TuiEvent},
     ui::{Component, ComponentEnum, api_key_input::ApiKeyInput, game::InGame, main_menu::MainMenu},
+    usage::Usage,
 };
 
 use async_openai::{Client, config::OpenAIConfig};
@@ -30,11 +33,14 @@ use tokio::sync::mpsc;
 pub enum Action {
     Quit,
     LoadSave(PathBuf),
-    CreateNewGame(String),
+    CreateNewGame(String, CampaignConfig),
     SwitchComponent(ComponentEnum),
     SwitchInputMode(InputMode),
     EndRecording,
     AudioNarration(AudioNarration),
+    XCard,
+    // The turn in flight was cancelled or the last one undone.
+    TurnTakenBack,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -54,6 +60,10 @@ pub struct App {
     save_manager: SaveManager,
     input_mode: InputMode,
     audio_narration: AudioNarration,
+    // Dialogue lines of the streamed response already sent to the narration.
+    narrated_lines: usize,
+    // Sheet changes applied since the last tool call was logged, they belong to the next one.
+    sheet_changes: Vec<SheetChange>,
 
     // --- Global information
     game_ai: Option<GameAI>,
@@ -83,17 +93,11 @@ impl App {
         load_game_menu_state.select(Some(0));
 
         let settings = Settings::try_load();
-        let ai_client;
-        let mut game_ai: Option<GameAI> = None;
-        if let Some(api_key) = &settings.openai_api_key {
-            ai_client = Settings::validate_ai_client(api_key).await;
-            game_ai = match GameAI::new(api_key, ai_sender.clone(), image_sender.clone()).await {
-                Ok(game_ai) => Some(game_ai),
-                Err(_) => None,
-            }
-        } else {
-            ai_client = None
+        let ai_client = match &settings.openai_api_key {
+            Some(api_key) => Settings::validate_ai_client(api_key).await,
+            None => None,
         };
+        let game_ai = Self::build_game_ai(&settings, &ai_sender);
 
         Self {
             running: true,
@@ -116,8 +120,21 @@ impl App {
             settings,
             save_manager: SaveManager::new(),
             audio_narration: AudioNarration::Stopped,
+            narrated_lines: 0,
+            sheet_changes: Vec::new(),
         }
     }
+    // The narration endpoint only needs a key when it points at OpenAI.
+    fn build_game_ai(
+        settings: &Settings,
+        ai_sender: &mpsc::UnboundedSender<AIMessage>,
+    ) -> Option<GameAI> {
+        if !settings.is_configured(Capability::Narration) {
+            return None;
+        }
+        Some(GameAI::new(settings, ai_sender.clone()))
+    }
+
     // Asynchronous function to continuously run and update the application.
     pub async fn run(&mut self) -> Result<()> {
         log::info!("Started the app");
@@ -187,7 +204,11 @@ impl App {
     fn handle_action(&mut self, action: Action) -> Result<()> {
         match action {
             Action::SwitchComponent(component) => {
-                self.settings = Settings::try_load();
+                let settings = Settings::try_load();
+                if settings != self.settings {
+                    self.game_ai = Self::build_game_ai(&settings, &self.ai_sender);
+                    self.settings = settings;
+                }
                 self.component = component
             }
             Action::SwitchInputMode(input_mode) => {
@@ -198,24 +219,46 @@ impl App {
                 log::info!("Action::LoadSave: {save_path:#?}");
                 self.ai_sender.send(AIMessage::Load(save_path))?;
             }
-            Action::CreateNewGame(save_name) => {
-                log::info!("Action::CreateNewGame: {save_name:#?}");
-                self.ai_sender.send(AIMessage::StartGame(save_name))?;
+            Action::CreateNewGame(save_name, campaign) => {
+                log::info!("Action::CreateNewGame: {save_name:#?}, {campaign:#?}");
+                self.ai_sender
+                    .send(AIMessage::StartGame(save_name, campaign))?;
             }
             // Action::ProcessMessage(message) => {
             //     todo!("Need to ProcessMessage: {}", message)
             // }
             Action::AudioNarration(audio_narration) => {
                 log::info!("Action::AudioNarration: {audio_narration:#?}");
+                // A cancelled turn never sends the Response that ends its narration.
+                if matches!(audio_narration, AudioNarration::Stopped) {
+                    self.narrated_lines = 0;
+                }
                 self.audio_narration = audio_narration;
                 self.audio_narration.handle_audio(self.ai_sender.clone())?;
             }
+            Action::XCard => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    let action = game.play_x_card(&self.save_manager, &self.settings.language);
+                    self.sheet_changes.clear();
+                    self.handle_action(action)?;
+                }
+            }
+            Action::TurnTakenBack => {
+                self.sheet_changes.clear();
+                self.handle_action(Action::AudioNarration(AudioNarration::Stopped))?;
+            }
             Action::EndRecording => {
                 if let InputMode::Recording(transcription) =
                     mem::replace(&mut self.input_mode, InputMode::Normal)
                 {
+                    let ai_sender = self.ai_sender.clone();
                     tokio::spawn(async move {
-                        transcription.input().await;
+                        if let Some(seconds) = transcription.input().await
+                            && let Err(e) =
+                                ai_sender.send(AIMessage::Usage(Usage::transcription(seconds)))
+                        {
+                            log::error!("Failed to send the transcription usage: {e:#?}");
+                        }
                     });
                 }
                 log::debug!("Replaced self.input_mode: {:#?}", self.input_mode);
@@ -242,20 +285,23 @@ impl App {
                 self.get_messages(game_state)?;
                 None
             }
+            AIMessage::Response(game_message) if self.crosses_line(&game_message.fluff) => {
+                self.take_back_crossed_reply()?
+            }
+            AIMessage::StreamFluff(fluff) if self.crosses_line(&fluff) => {
+                self.take_back_crossed_reply()?
+            }
             AIMessage::Response(game_message) => {
                 self.append_ai_response(&game_message);
-                if self.settings.audio_output_enabled {
-                    Some(Action::AudioNarration(AudioNarration::Generating(
-                        self.game_ai.clone().unwrap().clone(),
-                        game_message.fluff.clone(),
-                        self.component
-                            .get_ingame_save_path()
-                            .expect("Expected a valid save_path")
-                            .clone(),
-                    )))
-                } else {
-                    None
+                let narration = self.narrate(game_message.fluff);
+                self.narrated_lines = 0;
+                narration
+            }
+            AIMessage::StreamFluff(fluff) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.stream_fluff(fluff.clone());
                 }
+                self.narrate(fluff)
             }
             AIMessage::AudioNarration(audio_narration) => {
                 self.audio_narration = audio_narration;
@@ -270,14 +316,77 @@ impl App {
                 self.save(&game_state)?;
                 None
             }
-            AIMessage::StartGame(save_name) => {
-                self.start_new_game(save_name)?;
+            AIMessage::StartGame(save_name, campaign) => {
+                self.start_new_game(save_name, campaign)?;
                 None
             }
             AIMessage::AddCharacter(character_sheet) => {
                 self.add_character(character_sheet);
                 None
             }
+            AIMessage::UpdateHistory(history) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.state.history = history;
+                    self.ai_sender.send(game.save_message())?;
+                }
+                None
+            }
+            AIMessage::ThreadMessage(message_type, message_id) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.track_thread_message(message_type, message_id);
+                }
+                None
+            }
+            AIMessage::ToolCall(mut entry) => {
+                entry.changes = std::mem::take(&mut self.sheet_changes);
+                if let ComponentEnum::InGame(game) = &self.component
+                    && let Err(e) = append_tool_call(&game.state, &entry)
+                {
+                    log::error!("Failed to log the tool call: {e:#?}");
+                }
+                None
+            }
+            // Usage outside of a game, like dictating a save name, is not counted.
+            AIMessage::Usage(usage) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.state.usage.record(usage);
+                    self.ai_sender.send(game.save_message())?;
+                }
+                None
+            }
+            AIMessage::Retrying(attempt, max_attempts) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.retrying(attempt, max_attempts);
+                }
+                None
+            }
+            AIMessage::UpdateMemory(turn_id, memory) => {
+                if let ComponentEnum::InGame(game) = &mut self.component
+                    && game.memory_archived(turn_id, memory)
+                {
+                    self.ai_sender.send(game.save_message())?;
+                }
+                None
+            }
+            AIMessage::UpdateCombat(combat) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.state.combat = combat;
+                    self.ai_sender.send(game.save_message())?;
+                }
+                None
+            }
+            AIMessage::Image(path) => {
+                self.handle_image(path)?;
+                None
+            }
+            AIMessage::Turn(turn_id, message) => self.handle_turn_message(turn_id, *message)?,
+            AIMessage::UpdateDays(days) => {
+                if let ComponentEnum::InGame(game) = &mut self.component {
+                    game.state.days = days;
+                    self.ai_sender.send(game.save_message())?;
+                }
+                None
+            }
         };
         Ok(result)
     }
@@ -334,7 +443,7 @@ impl App {
                             fs::copy(&path, &img_path)?;
                             game.image = Some(load_image_from_file(&picker, &img_path)?);
                             game.state.image_path = Some(img_path);
-                            self.ai_sender.send(AIMessage::Save(game.state.clone()))?;
+                            self.ai_sender.send(game.save_message())?;
                         }
                     }
                 }
@@ -354,23 +463,32 @@ impl App {
         Ok(())
     }
 
-    fn get_messages(&mut self, game_state: GameState) -> Result<()> {
-        let thread_id = game_state.thread_id.clone();
+    fn get_messages(&mut self, mut game_state: GameState) -> Result<()> {
         let ai = self.game_ai.clone().expect("Expected GameAI");
         let sender = self.ai_sender.clone();
         tokio::spawn(async move {
+            let migrated = ai
+                .migrate_assistant(&mut game_state)
+                .await
+                .unwrap_or_else(|e| {
+                    log::error!("Failed to move the save to the shared assistant: {e:#?}");
+                    false
+                });
             let all_messages: Vec<Message> = ai
-                .fetch_all_messages(&thread_id)
+                .fetch_all_messages(&game_state)
                 .await
                 .expect("Expected the return of vec messages");
             let messages = all_messages[1..].to_vec();
 
-            match sender.send(AIMessage::Game((messages, ai, game_state))) {
+            match sender.send(AIMessage::Game((messages, ai, game_state.clone()))) {
                 Ok(_) => {}
                 Err(e) => {
                     log::error!("Couldn't send the AIMessage: {:#?}", e)
                 }
             };
+            if migrated && let Err(e) = sender.send(AIMessage::Save(game_state)) {
+                log::error!("Failed to save the migrated game: {e:#?}");
+            }
         });
 
         Ok(())
@@ -387,13 +505,70 @@ impl App {
 
     // TODO: Make unified and dynamic setting for all settings. cf the Ratatui examples
 
+    fn crosses_line(&self, fluff: &Fluff) -> bool {
+        let ComponentEnum::InGame(game) = &self.component else {
+            return false;
+        };
+        let crossed = game.state.campaign.boundaries.crossed_line(&fluff.render());
+        if let Some(line) = crossed {
+            log::warn!("The GM reply crossed the line: {line}");
+        }
+        crossed.is_some()
+    }
+
+    fn take_back_crossed_reply(&mut self) -> Result<Option<Action>> {
+        let ComponentEnum::InGame(game) = &mut self.component else {
+            return Ok(None);
+        };
+        let action = game.line_crossed(&self.save_manager, &self.settings.language);
+        self.sheet_changes.clear();
+        self.narrated_lines = 0;
+        Ok(Some(action))
+    }
+
+    // What a turn sends only reaches the game while the game holds the turn. Once it is taken
+    // back, its thread messages are deleted and the rest is dropped.
+    fn handle_turn_message(&mut self, turn_id: u64, message: AIMessage) -> Result<Option<Action>> {
+        let ComponentEnum::InGame(game) = &self.component else {
+            log::info!("Dropped a message of a turn outside of the game: {message:?}");
+            return Ok(None);
+        };
+        if game.holds_turn(turn_id) {
+            return self.handle_ai_message(message);
+        }
+        match message {
+            AIMessage::ThreadMessage(_, message_id) => {
+                game.delete_thread_messages(vec![message_id])
+            }
+            message => log::info!("Dropped a message of the turn taken back: {message:?}"),
+        }
+        Ok(None)
+    }
+
     pub fn append_ai_response(&mut self, message: &GameMessage) {
         if let ComponentEnum::InGame(game) = &mut self.component {
             let game_message_json = serde_json::to_string(&message).unwrap();
             game.new_message(&Message::new(MessageType::Game, game_message_json.clone()));
             game.spinner_active = false;
+            game.archive_turn(message);
+            game.end_turn();
+        }
+    }
+    // Narrates the dialogue lines that were not narrated yet while the response streamed.
+    fn narrate(&mut self, mut fluff: Fluff) -> Option<Action> {
+        if !self.settings.audio_output_enabled || fluff.dialogue.len() <= self.narrated_lines {
+            return None;
         }
+        let save_path = self.component.get_ingame_save_path()?.clone();
+        fluff.dialogue.drain(..self.narrated_lines);
+        self.narrated_lines += fluff.dialogue.len();
+        Some(Action::AudioNarration(AudioNarration::Generating(
+            self.game_ai.clone()?,
+            fluff,
+            save_path,
+        )))
     }
+
     pub fn apply_update(
         &mut self,
         update: &CharacterSheetUpdate,
@@ -406,11 +581,14 @@ impl App {
                 .iter_mut()
                 .find(|c| c.name == character_name)
             {
+                let before = character.clone();
                 character.apply_update(update)?;
+                self.sheet_changes
+                    .extend(sheet_diff(Some(&before), character));
                 if character.main {
                     game.state.main_character_sheet = Some(character.clone());
                 }
-                self.ai_sender.send(AIMessage::Save(game.state.clone()))?;
+                self.ai_sender.send(game.save_message())?;
             }
         }
         Ok(())
@@ -424,31 +602,19 @@ impl App {
         Ok(())
     }
 
-    pub fn start_new_game(&mut self, save_name: String) -> Result<()> {
-        if self.ai_client.is_none() {
+    pub fn start_new_game(&mut self, save_name: String, campaign: CampaignConfig) -> Result<()> {
+        if self.game_ai.is_none() {
             self.component = ComponentEnum::ApiKeyInput(ApiKeyInput::new(&None));
             return Ok(());
         }
-        let ai_client = self.ai_client.clone().unwrap();
         let settings = self.settings.clone();
         let game_ai = self.game_ai.clone();
         let ai_sender = self.ai_sender.clone();
         let save_manager = self.save_manager.clone();
 
         tokio::spawn(async move {
-            let assistant = match create_assistant(&ai_client, &settings.model, &save_name).await {
-                Ok(assistant) => assistant,
-                Err(e) => {
-                    log::error!("Failed to create assistant: {:?}", e);
-                    return;
-                }
-            };
-
-            let assistant_id = &assistant.id;
-
             if let Some(ai) = game_ai {
-                let mut game_state = match ai.start_new_conversation(assistant_id, &save_name).await
-                {
+                let mut game_state = match ai.start_new_conversation(&save_name, campaign).await {
                     Ok(game_state) => game_state,
                     Err(e) => {
                         log::error!(
@@ -474,18 +640,17 @@ impl App {
                     log::error!("Failed to send StartGame message: {:?}", e)
                 }
 
+                let language = game_state.campaign.language(&settings.language);
                 if let Err(e) = ai
-                    .send_message(
-                        UserCompletionRequest {
-                            language: settings.language.to_string(),
-                            message: create_user_message(
-                                &settings.language.to_string(),
-                                "Start the Game",
-                            ),
-                            state: game_state.clone(),
-                        },
-                        ai_sender.clone(),
-                    )
+                    .send_message(UserCompletionRequest {
+                        message: create_user_message(
+                            &game_state.campaign,
+                            &language,
+                            "Start the Game",
+                        ),
+                        language,
+                        state: game_state.clone(),
+                    })
                     .await
                 {
                     log::error!("Failed to send initial game message: {:?}", e)
@@ -510,8 +675,12 @@ impl App {
                 .iter_mut()
                 .find(|char| char.name == character_sheet.name)
             {
+                self.sheet_changes
+                    .extend(sheet_diff(Some(existing), &character_sheet));
                 *existing = character_sheet;
             } else {
+                self.sheet_changes
+                    .extend(sheet_diff(None, &character_sheet));
                 game.state.characters.push(character_sheet);
             }
         }
@@ -0,0 +1,176 @@
+// /archivist.rs
+use async_openai::{
+    Client,
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionRequestSystemMessage, ChatCompletionRequestUserMessage,
+        CreateChatCompletionRequestArgs,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::{
+    assistant::{ASSETS_DIR, load_schema},
+    error::{AIError, Result},
+    usage::Usage,
+};
+
+// Long-term memory of a campaign, kept in the save by the Archivist.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CampaignMemory {
+    pub summary: String,
+    pub npcs: Vec<NpcMemory>,
+    pub plot_threads: Vec<String>,
+    pub facts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct NpcMemory {
+    pub name: String,
+    pub motivation: String,
+    pub notes: String,
+}
+
+impl CampaignMemory {
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty()
+            && self.npcs.is_empty()
+            && self.plot_threads.is_empty()
+            && self.facts.is_empty()
+    }
+
+    // The memory as given to the Game Master with the next turn.
+    pub fn to_context(&self) -> String {
+        let mut context = format!(
+            "Campaign memory kept by the Archivist.\n\nSummary:\n{}\n",
+            self.summary
+        );
+        if !self.npcs.is_empty() {
+            context.push_str("\nNPCs:\n");
+            for npc in &self.npcs {
+                context.push_str(&format!(
+                    "- {}: {} {}\n",
+                    npc.name, npc.motivation, npc.notes
+                ));
+            }
+        }
+        for (title, entries) in [
+            ("Open plot threads", &self.plot_threads),
+            ("Facts", &self.facts),
+        ] {
+            if !entries.is_empty() {
+                context.push_str(&format!("\n{title}:\n"));
+                for entry in entries {
+                    context.push_str(&format!("- {entry}\n"));
+                }
+            }
+        }
+        context
+    }
+}
+
+// A turn waiting for the Archivist.
+#[derive(Debug, Clone)]
+pub struct ArchiveRequest {
+    // None for the opening of the game.
+    pub turn_id: Option<u64>,
+    pub player_action: String,
+    pub reply: String,
+}
+
+// Turns waiting for the Archivist. It sums them up one at a time, each from the memory the
+// previous one left, so that no update is lost.
+#[derive(Debug, Default)]
+pub struct ArchiveQueue {
+    requests: VecDeque<ArchiveRequest>,
+    running: bool,
+}
+
+impl ArchiveQueue {
+    pub fn push(&mut self, request: ArchiveRequest) {
+        self.requests.push_back(request);
+    }
+
+    // The next turn to archive, unless one is being archived. Turns taken back are skipped.
+    pub fn next(&mut self, stands: impl Fn(u64) -> bool) -> Option<ArchiveRequest> {
+        if self.running {
+            return None;
+        }
+        while let Some(request) = self.requests.pop_front() {
+            if request.turn_id.is_none_or(&stands) {
+                self.running = true;
+                return Some(request);
+            }
+        }
+        None
+    }
+
+    pub fn done(&mut self) {
+        self.running = false;
+    }
+}
+
+// The Visionary Archivist of ai_structure.md: a background pass after each turn
+// that folds the turn into the campaign memory.
+#[derive(Debug, Clone)]
+pub struct Archivist {
+    client: Client<OpenAIConfig>,
+    model: String,
+}
+
+impl Archivist {
+    pub fn new(client: Client<OpenAIConfig>, model: &str) -> Self {
+        Self {
+            client,
+            model: model.to_string(),
+        }
+    }
+
+    pub async fn update(
+        &self,
+        memory: &CampaignMemory,
+        player_action: &str,
+        game_master_reply: &str,
+    ) -> Result<(CampaignMemory, Usage)> {
+        let turn = format!(
+            "Current campaign memory:\n{}\n\nPlayer action:\n{}\n\nGame Master reply:\n{}",
+            serde_json::to_string(memory)?,
+            player_action,
+            game_master_reply
+        );
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .temperature(0.2)
+            .messages([
+                ChatCompletionRequestSystemMessage::from(load_archivist_instructions()).into(),
+                ChatCompletionRequestUserMessage::from(turn).into(),
+            ])
+            .response_format(load_schema("archivist/schema.json")?)
+            .build()
+            .map_err(AIError::OpenAI)?;
+
+        let response = self
+            .client
+            .chat()
+            .create(request)
+            .await
+            .map_err(AIError::OpenAI)?;
+        let usage = response.usage.map(Usage::from).unwrap_or_default();
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or(AIError::NoMessageFound)?;
+        Ok((serde_json::from_str(&content)?, usage))
+    }
+}
+
+fn load_archivist_instructions() -> &'static str {
+    ASSETS_DIR
+        .get_file("archivist/instructions.json")
+        .expect("Failed to get archivist instructions file")
+        .contents_utf8()
+        .expect("Failed to read archivist instructions file")
+}
@@ -2,20 +2,22 @@
 use crate::error::{AIError, Result};
 use include_dir::{Dir, DirEntry, include_dir};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 
 use async_openai::{
     Client,
     config::OpenAIConfig,
     types::{
         AssistantObject, AssistantTools, AssistantsApiResponseFormatOption,
-        CreateAssistantRequestArgs, FunctionObject, ResponseFormat, ResponseFormatJsonSchema,
+        CreateAssistantRequestArgs, FunctionObject, ModifyAssistantRequestArgs, ResponseFormat,
+        ResponseFormatJsonSchema,
     },
 };
 
 // TODO: Make sure the model is formating properly the dialogue responses in French and english.
 pub static ASSETS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/assets");
 
-fn load_function_objects() -> Result<Vec<FunctionObject>> {
+pub fn load_function_objects() -> Result<Vec<FunctionObject>> {
     let folder_dir = ASSETS_DIR
         .get_dir("assistant_functions")
         .expect("Failed to get assistant_functions directory");
@@ -62,12 +64,17 @@ fn load_function_objects() -> Result<Vec<FunctionObject>> {
     Ok(function_objects)
 }
 
-fn define_schema() -> Result<ResponseFormat> {
+pub fn define_schema() -> Result<ResponseFormat> {
+    load_schema("assistant_instructions/schema.json")
+}
+
+// Loads a structured output schema from the assets.
+pub fn load_schema(path: &str) -> Result<ResponseFormat> {
     let schema_file = ASSETS_DIR
-        .get_file("assistant_instructions/schema.json")
-        .expect("Failed to get assistant schema file")
+        .get_file(path)
+        .expect("Failed to get schema file")
         .contents_utf8()
-        .expect("Failed to read assistant schema file");
+        .expect("Failed to read schema file");
 
     let json_schema: Value = serde_json::from_str(schema_file)?;
     let name = json_schema["name"].as_str().expect("Expected a String");
@@ -84,48 +91,160 @@ fn define_schema() -> Result<ResponseFormat> {
     Ok(response_format)
 }
 
-// Function to create the assistant with multiple function objects
-pub async fn create_assistant(
-    client: &Client<OpenAIConfig>,
-    model: &str,
-    name: &str,
-) -> Result<AssistantObject> {
-    // Load all FunctionObjects from the specified folder
-    let function_objects = load_function_objects()?;
-    let instructions = ASSETS_DIR
+pub fn load_instructions() -> &'static str {
+    ASSETS_DIR
         .get_file("assistant_instructions/instructions.json")
         .expect("Failed to get assistant instructions file")
         .contents_utf8()
-        .expect("Failed to read assistant instructions file");
+        .expect("Failed to read assistant instructions file")
+}
 
-    // Convert FunctionObjects to AssistantTools using the Into trait
-    let assistant_tools = function_objects
-        .into_iter()
-        .map(Into::into) // Use the Into trait for conversion
-        .collect::<Vec<AssistantTools>>();
+// Metadata keys marking the assistant shared by the Assistants saves of a model.
+const MODEL_KEY: &str = "sharad_model";
+const VERSION_KEY: &str = "sharad_version";
+
+// Identifies what an assistant was built from: the model and hashes of the instructions, response
+// schema and functions. It changes when a release changes the assets.
+pub fn assistant_version(model: &str) -> Result<String> {
+    let mut functions = load_function_objects()?;
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(format!(
+        "{model}:{:016x}:{:016x}:{:016x}",
+        fnv1a(load_instructions()),
+        fnv1a(&serde_json::to_string(&define_schema()?)?),
+        fnv1a(&serde_json::to_string(&functions)?)
+    ))
+}
+
+// Stable across builds, unlike the std hasher.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
 
-    let response_format = match define_schema() {
-        Ok(schema) => schema,
-        Err(err) => return Err(err),
+// The assistant shared by the saves playing on the model, created on first use and updated in
+// place when the assets changed since. Returns its id.
+pub async fn shared_assistant(client: &Client<OpenAIConfig>, model: &str) -> Result<String> {
+    let version = assistant_version(model)?;
+    let metadata = HashMap::from([
+        (MODEL_KEY.to_string(), model.to_string()),
+        (VERSION_KEY.to_string(), version.clone()),
+    ]);
+    let tools = assistant_tools()?;
+    let response_format = AssistantsApiResponseFormatOption::Format(define_schema()?);
+
+    let Some(assistant) = find_shared_assistant(client, model).await? else {
+        let request = CreateAssistantRequestArgs::default()
+            .name(format!("Sharad Game Master ({model})"))
+            .temperature(0.7)
+            .instructions(load_instructions())
+            .model(model)
+            .response_format(response_format)
+            .tools(tools)
+            .metadata(metadata)
+            .build()
+            .map_err(AIError::OpenAI)?;
+        let assistant = client
+            .assistants()
+            .create(request)
+            .await
+            .map_err(AIError::OpenAI)?;
+        log::info!("Created the shared assistant {}: {version}", assistant.id);
+        return Ok(assistant.id);
     };
-    // Build the CreateAssistantRequestArgs with all the function objects
-    let create_assistant_request = CreateAssistantRequestArgs::default()
-        .name(name)
-        .temperature(0.7)
-        .instructions(instructions)
-        .model(model)
-        .response_format(AssistantsApiResponseFormatOption::Format(response_format))
-        .tools(assistant_tools) // Pass the vector of AssistantTools
-        .build()
-        .map_err(AIError::OpenAI)?;
-
-    // Create the assistant
-    let assistant = client
-        .assistants()
-        .create(create_assistant_request)
-        .await
-        .map_err(AIError::OpenAI)?;
-    Ok(assistant)
+
+    if assistant_metadata(&assistant, VERSION_KEY) != Some(version.as_str()) {
+        let request = ModifyAssistantRequestArgs::default()
+            .instructions(load_instructions())
+            .response_format(response_format)
+            .tools(tools)
+            .metadata(metadata)
+            .build()
+            .map_err(AIError::OpenAI)?;
+        client
+            .assistants()
+            .update(&assistant.id, request)
+            .await
+            .map_err(AIError::OpenAI)?;
+        log::info!("Updated the shared assistant {}: {version}", assistant.id);
+    }
+    Ok(assistant.id)
+}
+
+async fn find_shared_assistant(
+    client: &Client<OpenAIConfig>,
+    model: &str,
+) -> Result<Option<AssistantObject>> {
+    Ok(list_assistants(client)
+        .await?
+        .into_iter()
+        .find(|assistant| assistant_metadata(assistant, MODEL_KEY) == Some(model)))
+}
+
+async fn list_assistants(client: &Client<OpenAIConfig>) -> Result<Vec<AssistantObject>> {
+    let mut assistants = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let mut query = vec![("limit", "100".to_string())];
+        if let Some(after) = &after {
+            query.push(("after", after.clone()));
+        }
+        let page = client
+            .assistants()
+            .list(&query)
+            .await
+            .map_err(AIError::OpenAI)?;
+        assistants.extend(page.data);
+        if !page.has_more || page.last_id.is_none() {
+            return Ok(assistants);
+        }
+        after = page.last_id;
+    }
+}
+
+// The assistants on the account that Sharad created.
+pub async fn list_sharad_assistants(client: &Client<OpenAIConfig>) -> Result<Vec<AssistantObject>> {
+    Ok(list_assistants(client)
+        .await?
+        .into_iter()
+        .filter(is_sharad_assistant)
+        .collect())
+}
+
+// Shared assistants carry their model in the metadata. Those owned by older saves are only named
+// after the save, but they have the game functions.
+pub fn is_sharad_assistant(assistant: &AssistantObject) -> bool {
+    shared_assistant_model(assistant).is_some()
+        || assistant.tools.iter().any(|tool| {
+            matches!(tool, AssistantTools::Function(tool) if tool.function.name == "create_character_sheet")
+        })
+}
+
+pub fn shared_assistant_model(assistant: &AssistantObject) -> Option<&str> {
+    assistant_metadata(assistant, MODEL_KEY)
+}
+
+// The Sharad assistants that none of the saves refer to anymore.
+pub fn orphaned_assistants(
+    assistants: Vec<AssistantObject>,
+    referenced: &HashSet<String>,
+) -> Vec<AssistantObject> {
+    assistants
+        .into_iter()
+        .filter(|assistant| is_sharad_assistant(assistant) && !referenced.contains(&assistant.id))
+        .collect()
+}
+
+fn assistant_metadata<'a>(assistant: &'a AssistantObject, key: &str) -> Option<&'a str> {
+    assistant.metadata.as_ref()?.get(key).map(String::as_str)
+}
+
+fn assistant_tools() -> Result<Vec<AssistantTools>> {
+    Ok(load_function_objects()?
+        .into_iter()
+        .map(Into::into)
+        .collect())
 }
 
 pub async fn delete_assistant(client: &Client<OpenAIConfig>, assistant_id: &str) {
@@ -4,10 +4,11 @@ use crate::{
     error::{AIError, AudioError, Error, Result},
     message::{AIMessage, Fluff},
     save::get_game_data_dir,
+    settings::ModelClient,
+    usage::Usage,
 };
 use async_openai::{
-    Audio, Client,
-    config::OpenAIConfig,
+    Audio,
     types::{CreateSpeechRequestArgs, CreateTranscriptionRequestArgs, SpeechModel, Voice},
 };
 use chrono::Local;
@@ -23,18 +24,38 @@ use std::{
     io::{BufReader, BufWriter, Cursor},
     path::{Path, PathBuf},
     sync::{
-        Arc, Mutex,
+        Arc, LazyLock, Mutex,
         atomic::{AtomicBool, Ordering},
+        mpsc,
     },
     thread,
     time::Duration,
 };
 use tokio::{
-    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+        oneshot,
+    },
     time::sleep,
 };
 use uuid::Uuid;
 
+// Plays the narrated lines one after the other, in the order they were queued.
+// Each line is queued before its audio is generated, so it waits for the lines before it.
+static NARRATOR: LazyLock<mpsc::Sender<oneshot::Receiver<Option<PathBuf>>>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::channel::<oneshot::Receiver<Option<PathBuf>>>();
+    thread::spawn(move || {
+        for line in receiver {
+            if let Ok(Some(audio_path)) = line.blocking_recv()
+                && let Err(e) = play_audio(audio_path)
+            {
+                log::error!("Failed to read audio: {e:#?}");
+            }
+        }
+    });
+    sender
+});
+
 #[derive(Debug, Clone)]
 pub enum AudioNarration {
     Generating(GameAI, Fluff, PathBuf),
@@ -52,24 +73,15 @@ impl AudioNarration {
             AudioNarration::Generating(game_ai, fluff, save_path) => {
                 log::info!("AudioNarration::Generating: {fluff:#?}");
                 self.generate_narration(
-                    game_ai.client.clone(),
+                    game_ai.speech.clone(),
                     fluff.clone(),
                     save_path.clone(),
                     ai_sender,
                 )?;
             }
+            // The NARRATOR plays each line as soon as its audio is ready.
             AudioNarration::Playing(fluff) => {
                 log::info!("AudioNarration::Playing: {fluff:#?}");
-                let fluff = fluff.clone();
-                tokio::spawn(async move {
-                    for file in fluff.dialogue.iter() {
-                        if let Some(audio_path) = &file.audio {
-                            if let Err(e) = play_audio(audio_path.clone()) {
-                                log::error!("Failed to read audio: {e:#?}");
-                            };
-                        }
-                    }
-                });
             }
             // AudioNarration::Paused => todo!("Need to handle the Paused AudioNarration"),
             AudioNarration::Stopped => {}
@@ -79,48 +91,63 @@ impl AudioNarration {
 
     fn generate_narration(
         &mut self,
-        client: async_openai::Client<OpenAIConfig>,
+        speech: ModelClient,
         mut fluff: Fluff,
         save_path: PathBuf,
         ai_sender: tokio::sync::mpsc::UnboundedSender<AIMessage>,
     ) -> Result<()> {
-        tokio::spawn(async move {
-            fluff
+        fluff
+            .speakers
+            .iter_mut()
+            .for_each(|speaker| speaker.assign_voice());
+
+        log::info!("Before audio generation: {fluff:#?}");
+
+        let mut audio_futures = FuturesOrdered::new();
+
+        for (index, fluff_line) in fluff.dialogue.iter().enumerate() {
+            let Some(voice) = fluff
                 .speakers
-                .iter_mut()
-                .for_each(|speaker| speaker.assign_voice());
-
-            log::info!("Before audio generation: {fluff:#?}");
-
-            let mut audio_futures = FuturesOrdered::new();
-
-            for (index, fluff_line) in fluff.dialogue.iter_mut().enumerate() {
-                let voice = fluff
-                    .speakers
-                    .iter()
-                    .find(|s| s.index == fluff_line.speaker_index)
-                    .and_then(|s| s.voice.clone())
-                    .expect("Voice not found for speaker");
-
-                let text = fluff_line.text.clone();
-                let save_path = save_path.clone();
-                let client = client.clone();
-
-                // Generate the audio concurrently, keeping track of the index
-                audio_futures.push_back(async move {
-                    let result = generate_audio(&client, &save_path, &text, voice).await;
-                    (result, index)
-                });
+                .iter()
+                .find(|s| s.index == fluff_line.speaker_index)
+                .and_then(|s| s.voice.clone())
+            else {
+                log::error!("Voice not found for speaker: {fluff_line:#?}");
+                continue;
+            };
+
+            let text = fluff_line.text.clone();
+            let save_path = save_path.clone();
+            let speech = speech.clone();
+            let (line_sender, line_receiver) = oneshot::channel();
+            if let Err(e) = NARRATOR.send(line_receiver) {
+                log::error!("Failed to queue the narration: {e:#?}");
             }
 
+            // Generate the audio concurrently, keeping track of the index
+            audio_futures.push_back(async move {
+                let result = generate_audio(&speech, &save_path, &text, voice).await;
+                let _ = line_sender.send(result.as_ref().ok().cloned());
+                (result, index)
+            });
+        }
+
+        tokio::spawn(async move {
             log::info!("generate_audio done");
             // Process the results in order
+            let mut usage = Usage::default();
             while let Some((result, index)) = audio_futures.next().await {
                 log::info!("generate_audio results: {result:#?}");
                 if let Ok(path) = result {
+                    usage += Usage::tts(&fluff.dialogue[index].text);
                     fluff.dialogue[index].audio = Some(path);
                 }
             }
+            if usage.tts_characters > 0
+                && let Err(e) = ai_sender.send(AIMessage::Usage(usage))
+            {
+                log::error!("Failed to send the narration usage: {e:#?}");
+            }
 
             log::info!("After audio generation: {fluff:#?}");
             if let Err(e) =
@@ -134,19 +161,19 @@ impl AudioNarration {
 }
 
 pub async fn generate_audio(
-    client: &async_openai::Client<OpenAIConfig>,
+    speech: &ModelClient,
     save_path: &Path,
     text: &str,
     voice: Voice,
 ) -> Result<PathBuf> {
-    let audio = Audio::new(client);
+    let audio = Audio::new(&speech.client);
 
     let response = audio
         .speech(
             CreateSpeechRequestArgs::default()
                 .input(text)
                 .voice(voice)
-                .model(SpeechModel::Tts1)
+                .model(SpeechModel::Other(speech.model.clone()))
                 .speed(1.3)
                 .build()
                 .map_err(AIError::OpenAI)?,
@@ -219,7 +246,7 @@ pub fn play_audio(file_path: PathBuf) -> Result<()> {
         let source = Decoder::new(BufReader::new(file)).expect("Failed to decode audio");
         sink.append(source);
         sink.sleep_until_end();
-        sleep(Duration::from_millis(100));
+        thread::sleep(Duration::from_millis(100));
         log::info!("End of play_audio");
     };
 
@@ -255,7 +282,7 @@ impl TryFrom<Option<PathBuf>> for AudioDir {
 #[derive(Debug)]
 pub struct Transcription {
     is_recording: Arc<AtomicBool>,
-    client: Client<OpenAIConfig>,
+    transcriber: ModelClient,
     dir: AudioDir,
     recording_path: Option<PathBuf>,
     sender: UnboundedSender<String>,
@@ -267,7 +294,7 @@ impl Clone for Transcription {
     fn clone(&self) -> Self {
         Self {
             is_recording: self.is_recording.clone(),
-            client: self.client.clone(),
+            transcriber: self.transcriber.clone(),
             dir: self.dir.clone(),
             recording_path: self.recording_path.clone(),
             sender: self.sender.clone(),
@@ -280,12 +307,12 @@ impl Clone for Transcription {
 impl Transcription {
     pub fn new(
         path: Option<PathBuf>,
-        client: Client<OpenAIConfig>,
+        transcriber: ModelClient,
     ) -> Result<(UnboundedReceiver<String>, Transcription)> {
         let (t_sender, t_receiver) = unbounded_channel();
         let mut transcription = Self {
             is_recording: Arc::new(AtomicBool::new(true)),
-            client,
+            transcriber,
             dir: AudioDir::try_from(path)?,
             recording_path: None,
             sender: t_sender,
@@ -314,19 +341,21 @@ impl Transcription {
         self.is_recording.fetch_not(Ordering::SeqCst);
     }
 
-    pub async fn transcribe_audio(&mut self) {
-        let audio = Audio::new(&self.client);
+    // Returns the length in seconds of the recording that was sent, None when it failed.
+    pub async fn transcribe_audio(&mut self) -> Option<f64> {
+        let audio = Audio::new(&self.transcriber.client);
 
         let recording_path = self
             .recording_path
             .clone()
             .expect("Expected a recording path");
 
+        let seconds = recording_seconds(&recording_path);
         match audio
             .transcribe(
                 CreateTranscriptionRequestArgs::default()
                     .file(recording_path)
-                    .model("whisper-1")
+                    .model(&self.transcriber.model)
                     .build()
                     .map_err(|e| {
                         log::error!("Failed to build the CreateTranscriptionRequestArgs: {e:#?}");
@@ -335,12 +364,19 @@ impl Transcription {
             )
             .await
         {
-            Ok(transcription) => self.transcription = transcription.text,
-            Err(e) => log::error!("Failed to transcribe: {e:#?}"),
-        };
+            Ok(transcription) => {
+                self.transcription = transcription.text;
+                seconds
+            }
+            Err(e) => {
+                log::error!("Failed to transcribe: {e:#?}");
+                None
+            }
+        }
     }
 
-    pub async fn input(mut self) {
+    // Stops the recording and sends its transcription. Returns the length of the recording.
+    pub async fn input(mut self) -> Option<f64> {
         self.stop();
 
         let maybe_path = loop {
@@ -359,15 +395,23 @@ impl Transcription {
         }
 
         log::debug!("Transcription.recording_path: {:#?}", self.recording_path);
-        self.transcribe_audio().await;
+        let seconds = self.transcribe_audio().await;
 
         if let Err(e) = self.sender.send(self.transcription) {
             log::error!("Failed to send the transcription: {e:#?}");
         }
         crate::save::clean_recording_temp_dir();
+        seconds
     }
 }
 
+fn recording_seconds(path: &Path) -> Option<f64> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| log::error!("Failed to read the recording: {e:#?}"))
+        .ok()?;
+    Some(reader.duration() as f64 / reader.spec().sample_rate as f64)
+}
+
 pub fn record_audio(dir: AudioDir, is_recording: Arc<AtomicBool>) -> Result<PathBuf> {
     let host = cpal::default_host();
     let device = host
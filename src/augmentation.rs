@@ -0,0 +1,189 @@
+// /augmentation.rs
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+// Augmentations raise an attribute by 4 at most.
+pub const AUGMENTED_MAXIMUM: u8 = 4;
+// Initiative dice added by augmentations, on top of the natural one.
+pub const MAX_EXTRA_INITIATIVE_DICE: u8 = 4;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AugmentationType {
+    Cyberware,
+    Bioware,
+}
+
+// The grade multiplies the essence cost of an augmentation.
+#[derive(
+    Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Default, strum_macros::Display,
+)]
+pub enum Grade {
+    Used,
+    #[default]
+    Standard,
+    Alphaware,
+    Betaware,
+    Deltaware,
+}
+
+impl Grade {
+    pub fn essence_multiplier(&self) -> f32 {
+        match self {
+            Grade::Used => 1.25,
+            Grade::Standard => 1.0,
+            Grade::Alphaware => 0.8,
+            Grade::Betaware => 0.7,
+            Grade::Deltaware => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Augmentation {
+    pub name: String,
+    #[serde(default)]
+    pub grade: Grade,
+    #[serde(default)]
+    pub rating: Option<u8>,
+    // Essence cost of the standard grade.
+    #[serde(default)]
+    pub essence_cost: f32,
+    // Added to the attributes, by attribute name.
+    #[serde(default)]
+    pub attribute_bonuses: HashMap<String, u8>,
+    // Dice added to the pools of skills, by skill name, like a smartlink on firearms.
+    #[serde(default)]
+    pub skill_bonuses: HashMap<String, u8>,
+    #[serde(default)]
+    pub initiative_dice: u8,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Augmentation {
+    pub fn essence(&self) -> f32 {
+        self.essence_cost * self.grade.essence_multiplier()
+    }
+
+    pub fn attribute_bonus(&self, attribute: &str) -> u8 {
+        self.attribute_bonuses
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(attribute.trim()))
+            .map_or(0, |(_, bonus)| *bonus)
+    }
+
+    pub fn skill_bonus(&self, skill: &str) -> u8 {
+        self.skill_bonuses
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(skill.trim()))
+            .map_or(0, |(_, bonus)| *bonus)
+    }
+
+    // Like "+2 reaction, +2 initiative dice".
+    pub fn bonuses(&self) -> String {
+        let mut bonuses: Vec<String> = self
+            .attribute_bonuses
+            .iter()
+            .chain(&self.skill_bonuses)
+            .map(|(name, bonus)| format!("+{bonus} {name}"))
+            .collect();
+        bonuses.sort();
+        if self.initiative_dice > 0 {
+            bonuses.push(format!("+{} initiative dice", self.initiative_dice));
+        }
+        bonuses.join(", ")
+    }
+}
+
+// The firearms a smartlink helps with.
+const SMARTGUN_SKILLS: [&str; 5] = [
+    "Pistols",
+    "Automatics",
+    "Longarms",
+    "Heavy Weapons",
+    "Exotic Ranged Weapon",
+];
+
+// Saves from before structured augmentations only have names, like "Muscle Replacement 2".
+// Known augmentations get their bonuses and essence cost back from the name and its rating.
+impl From<String> for Augmentation {
+    fn from(name: String) -> Self {
+        let rating = name
+            .split_whitespace()
+            .last()
+            .and_then(|word| word.trim_start_matches(['R', 'r']).parse::<u8>().ok());
+        let level = rating.unwrap_or(1);
+        let attributes = |names: &[&str]| -> HashMap<String, u8> {
+            names
+                .iter()
+                .map(|attribute| (attribute.to_string(), level))
+                .collect()
+        };
+        let mut augmentation = Augmentation {
+            name: name.clone(),
+            grade: Grade::Standard,
+            rating,
+            essence_cost: 0.0,
+            attribute_bonuses: HashMap::new(),
+            skill_bonuses: HashMap::new(),
+            initiative_dice: 0,
+            description: String::new(),
+        };
+        let lowercase = name.to_lowercase();
+        let per_rating = f32::from(level);
+        if lowercase.contains("smartlink") {
+            augmentation.essence_cost = 0.2;
+            augmentation.skill_bonuses = SMARTGUN_SKILLS
+                .iter()
+                .map(|skill| (skill.to_string(), 2))
+                .collect();
+        } else if lowercase.contains("wired reflexes") {
+            augmentation.essence_cost = [2.0, 3.0, 5.0][usize::from(level.clamp(1, 3)) - 1];
+            augmentation.attribute_bonuses = attributes(&["reaction"]);
+            augmentation.initiative_dice = level;
+        } else if lowercase.contains("synaptic booster") {
+            augmentation.essence_cost = 0.5 * per_rating;
+            augmentation.attribute_bonuses = attributes(&["reaction"]);
+            augmentation.initiative_dice = level;
+        } else if lowercase.contains("muscle replacement") {
+            augmentation.essence_cost = per_rating;
+            augmentation.attribute_bonuses = attributes(&["agility", "strength"]);
+        } else if let Some((cost, attribute)) = [
+            ("muscle toner", (0.2, "agility")),
+            ("muscle augmentation", (0.2, "strength")),
+            ("reaction enhancers", (0.3, "reaction")),
+            ("cerebral booster", (0.2, "logic")),
+            ("tailored pheromones", (0.2, "charisma")),
+        ]
+        .into_iter()
+        .find(|(known, _)| lowercase.contains(known))
+        .map(|(_, bonus)| bonus)
+        {
+            augmentation.essence_cost = cost * per_rating;
+            augmentation.attribute_bonuses = attributes(&[attribute]);
+        }
+        augmentation
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedAugmentation {
+    Augmentation(Augmentation),
+    Name(String),
+}
+
+// Reads the augmentations of a save, names included.
+pub fn deserialize_augmentations<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Augmentation>, D::Error> {
+    Ok(Vec::<SavedAugmentation>::deserialize(deserializer)?
+        .into_iter()
+        .map(|saved| match saved {
+            SavedAugmentation::Augmentation(augmentation) => augmentation,
+            SavedAugmentation::Name(name) => Augmentation::from(name),
+        })
+        .collect())
+}
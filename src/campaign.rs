@@ -0,0 +1,167 @@
+// /campaign.rs
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use strum_macros::Display;
+
+use crate::{
+    assistant::load_instructions, safety::Boundaries, save::get_game_data_dir, settings::Language,
+};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Tone {
+    // The tone of instructions.json, nothing is added.
+    #[default]
+    Classic,
+    Noir,
+    Pulp,
+    Horror,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 4] = [Tone::Classic, Tone::Noir, Tone::Pulp, Tone::Horror];
+
+    fn instructions(&self) -> Option<&'static str> {
+        match self {
+            Tone::Classic => None,
+            Tone::Noir => Some(
+                "Tone: noir. Rain-soaked streets, moral compromise and betrayal. Narrate in a weary, \
+                 hard-boiled voice, let every job cost the runner something and keep the victories bittersweet.",
+            ),
+            Tone::Pulp => Some(
+                "Tone: pulp. Fast, loud and larger than life. Favor daring stunts, colorful villains, \
+                 narrow escapes and cliffhangers, and reward bold plans with spectacular results.",
+            ),
+            Tone::Horror => Some(
+                "Tone: horror. Dread over action. Toxic spirits, insect shamans, bug cities and things \
+                 the corps want buried. Reveal threats slowly, keep the runner unsure of what is real and \
+                 let fear weigh on their choices.",
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Difficulty {
+    Forgiving,
+    // The difficulty of instructions.json, nothing is added.
+    #[default]
+    Standard,
+    Lethal,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Forgiving,
+        Difficulty::Standard,
+        Difficulty::Lethal,
+    ];
+
+    fn instructions(&self) -> Option<&'static str> {
+        match self {
+            Difficulty::Forgiving => Some(
+                "Difficulty: forgiving. Use low thresholds, telegraph danger before it strikes and give \
+                 the runner a way out of lost fights. A failed roll complicates the story but never kills \
+                 the character outright.",
+            ),
+            Difficulty::Standard => None,
+            Difficulty::Lethal => Some(
+                "Difficulty: lethal. Use the full thresholds and opposition of the rules, enemies fight \
+                 smart and focus fire, wounds linger and bad plans get the runner killed. Do not fudge \
+                 the dice to save the character.",
+            ),
+        }
+    }
+}
+
+// How the Game Master runs a campaign, chosen when it starts and kept in its save.
+// The default adds nothing to the instructions, saves from before keep playing as they did.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CampaignConfig {
+    pub tone: Tone,
+    pub difficulty: Difficulty,
+    // Read at each turn, so that editing it changes the campaign as it goes.
+    pub instructions_file: Option<PathBuf>,
+    // Overrides of the settings.
+    pub model: Option<String>,
+    pub language: Option<Language>,
+    pub boundaries: Boundaries,
+}
+
+impl CampaignConfig {
+    pub fn model<'a>(&'a self, default: &'a str) -> &'a str {
+        self.model.as_deref().unwrap_or(default)
+    }
+
+    pub fn language(&self, default: &Language) -> String {
+        self.language.as_ref().unwrap_or(default).to_string()
+    }
+
+    // What the campaign adds to instructions.json, None when it adds nothing.
+    pub fn instructions(&self) -> Option<String> {
+        let custom = self.instructions_file.as_ref().and_then(|path| {
+            fs::read_to_string(path)
+                .map_err(|e| log::error!("Failed to read {}: {e:#?}", path.display()))
+                .ok()
+        });
+        let boundaries = self.boundaries.instructions();
+        let layers: Vec<&str> = [self.tone.instructions(), self.difficulty.instructions()]
+            .into_iter()
+            .flatten()
+            .chain(
+                custom
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|text| !text.is_empty()),
+            )
+            .chain(boundaries.as_deref())
+            .collect();
+        (!layers.is_empty()).then(|| format!("Campaign settings:\n{}", layers.join("\n\n")))
+    }
+
+    // instructions.json with the campaign layered on top, for backends that send them.
+    pub fn system_instructions(&self) -> String {
+        match self.instructions() {
+            Some(campaign) => format!("{}\n\n{campaign}", load_instructions()),
+            None => load_instructions().to_string(),
+        }
+    }
+
+    // A reminder sent with each player turn, so that long campaigns don't drift.
+    // The boundaries are sent in full every time.
+    pub fn turn_instructions(&self) -> Option<String> {
+        let mut reminders = Vec::new();
+        if self.tone != Tone::default() {
+            reminders.push(format!(
+                "Keep the {} tone.",
+                self.tone.to_string().to_lowercase()
+            ));
+        }
+        if self.difficulty != Difficulty::default() {
+            reminders.push(format!(
+                "Keep the {} difficulty.",
+                self.difficulty.to_string().to_lowercase()
+            ));
+        }
+        reminders.extend(self.boundaries.instructions());
+        (!reminders.is_empty()).then(|| reminders.join(" "))
+    }
+}
+
+// Players drop their own instruction files here to pick them for a new campaign.
+pub fn get_instructions_dir() -> PathBuf {
+    get_game_data_dir().join("instructions")
+}
+
+pub fn list_instruction_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(get_instructions_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
@@ -5,7 +5,15 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
+    augmentation::{
+        AUGMENTED_MAXIMUM, Augmentation, AugmentationType, MAX_EXTRA_INITIATIVE_DICE,
+        deserialize_augmentations,
+    },
+    dice_pool::DicePool,
     error::{Error, Result},
+    gear::{ItemCategory, Weapon},
+    lifestyle::{Lifestyle, LifestyleOption, LifestyleTier, Upkeep, deserialize_lifestyle, months},
+    skills::{KnowledgeCategory, LANGUAGE_ATTRIBUTE, find_skill},
     ui::descriptions::*,
 };
 
@@ -71,10 +79,27 @@ pub struct Limits {
     pub social: u8,
 }
 
+// The boxes of the condition monitors and the damage marked on them.
+// Saves from before damage tracking start unharmed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Monitors {
     pub physical: u8,
     pub stun: u8,
+    #[serde(default)]
+    pub physical_damage: u8,
+    #[serde(default)]
+    pub stun_damage: u8,
+    // Physical damage past a full track. The character dies when it goes past their body.
+    #[serde(default)]
+    pub overflow: u8,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DamageType {
+    Physical,
+    Stun,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,16 +128,32 @@ pub struct CharacterSheet {
     // Skills and Knowledge
     pub skills: Skills,
     pub knowledge_skills: Skill,
+    // The specialization of each skill that has one, worth 2 dice on the actions it covers.
+    #[serde(default)]
+    pub specializations: HashMap<String, String>,
+    // Groups of related skills bought together, each of their skills rolls at the group rating.
+    #[serde(default)]
+    pub skill_groups: Skill,
+    // Knowledge skills without a category are street knowledge.
+    #[serde(default)]
+    pub knowledge_categories: HashMap<String, KnowledgeCategory>,
+    #[serde(default)]
+    pub languages: Skill,
+    #[serde(default)]
+    pub native_language: Option<String>,
 
     // Economic and Social Information
     #[serde(default)]
     pub nuyen: u32,
-    pub lifestyle: String, // TODO: Make this into a struct cf assets/lifestyle.md
+    #[serde(deserialize_with = "deserialize_lifestyle")]
+    pub lifestyle: Lifestyle,
     #[serde(default)]
     pub contacts: HashMap<String, Contact>,
     pub qualities: Vec<Quality>,
-    pub cyberware: Vec<String>, // TODO: Make this a struct Cyberware
-    pub bioware: Vec<String>,   // TODO: Make this a struct Bioware
+    #[serde(deserialize_with = "deserialize_augmentations")]
+    pub cyberware: Vec<Augmentation>,
+    #[serde(deserialize_with = "deserialize_augmentations")]
+    pub bioware: Vec<Augmentation>,
     #[serde(default)]
     pub inventory: HashMap<String, Item>, // TODO: simplify this data structure to a simple HashMap
     pub matrix_attributes: Option<MatrixAttributes>,
@@ -134,6 +175,15 @@ pub struct Item {
     pub name: String,
     pub quantity: u32,
     pub description: String,
+    #[serde(default)]
+    pub category: ItemCategory,
+    #[serde(default)]
+    pub weapon: Option<Weapon>,
+    #[serde(default)]
+    pub armor_rating: Option<u8>,
+    // Worn armor protects the character, only a weapon in hand can attack.
+    #[serde(default)]
+    pub equipped: bool,
 }
 
 // Define a structure for contacts within the game, representing relationships and connections.
@@ -163,6 +213,15 @@ pub struct MatrixAttributes {
     pub firewall: u8,
 }
 
+// The rating of a skill of the map whatever the case of its name.
+fn find_rating(skills: &Skill, skill: &str) -> Option<u8> {
+    let skill = skill.trim();
+    skills
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(skill))
+        .map(|(_, rating)| *rating)
+}
+
 // Implementation of methods for the CharacterSheet struct.
 impl CharacterSheet {
     // Constructor for creating a new character sheet.
@@ -200,6 +259,9 @@ impl CharacterSheet {
                 monitors: Monitors {
                     physical: 9,
                     stun: 9,
+                    physical_damage: 0,
+                    stun_damage: 0,
+                    overflow: 0,
                 },
                 essence: Essence {
                     current: 6.0,
@@ -210,8 +272,13 @@ impl CharacterSheet {
             },
             skills: builder.skills,
             knowledge_skills: builder.knowledge_skills,
+            specializations: builder.specializations,
+            skill_groups: builder.skill_groups,
+            knowledge_categories: builder.knowledge_categories,
+            languages: builder.languages,
+            native_language: builder.native_language,
             nuyen: builder.nuyen,
-            lifestyle: "Street".to_string(),
+            lifestyle: Lifestyle::default(),
             contacts: builder.contacts,
             qualities: builder.qualities,
             cyberware: Vec::new(),
@@ -222,6 +289,8 @@ impl CharacterSheet {
 
         // Apply race-specific attribute modifiers and update derived attributes.
         sheet.apply_race_modifiers(sheet.race);
+        // Characters start with all their Edge.
+        sheet.derived_attributes.edge_points = sheet.attributes.edge;
         sheet.update_derived_attributes();
         sheet
     }
@@ -260,28 +329,135 @@ impl CharacterSheet {
         }
     }
 
-    // Update derived attributes based on basic and secondary attributes.
+    // Update derived attributes based on basic and secondary attributes, augmented ones
+    // included.
     pub fn update_derived_attributes(&mut self) {
-        self.derived_attributes.initiative =
-            (self.attributes.reaction + self.attributes.intuition, 1);
-        self.derived_attributes.monitors.physical = 8 + (self.attributes.body + 1) / 2;
-        self.derived_attributes.monitors.stun = 8 + (self.attributes.willpower + 1) / 2;
-        self.derived_attributes.limits.physical = ((self.attributes.strength * 2
-            + self.attributes.body
-            + self.attributes.reaction) as f32
-            / 3.0)
-            .ceil() as u8;
-        self.derived_attributes.limits.mental = ((self.attributes.logic * 2
-            + self.attributes.intuition
-            + self.attributes.willpower) as f32
-            / 3.0)
-            .ceil() as u8;
-        self.derived_attributes.limits.social = ((self.attributes.charisma * 2
-            + self.attributes.willpower
-            + self.derived_attributes.essence.current as u8)
-            as f32
-            / 3.0)
-            .ceil() as u8;
+        let [
+            body,
+            reaction,
+            strength,
+            willpower,
+            logic,
+            intuition,
+            charisma,
+        ] = [
+            "body",
+            "reaction",
+            "strength",
+            "willpower",
+            "logic",
+            "intuition",
+            "charisma",
+        ]
+        .map(|attribute| self.augmented_attribute(attribute));
+        let initiative_dice = self
+            .augmentations()
+            .map(|augmentation| augmentation.initiative_dice)
+            .fold(0, u8::saturating_add)
+            .min(MAX_EXTRA_INITIATIVE_DICE);
+        self.derived_attributes.initiative = (reaction + intuition, 1 + initiative_dice);
+        self.derived_attributes.monitors.physical = 8 + (body + 1) / 2;
+        self.derived_attributes.monitors.stun = 8 + (willpower + 1) / 2;
+        self.derived_attributes.edge_points = self
+            .derived_attributes
+            .edge_points
+            .min(self.attributes.edge);
+        self.derived_attributes.limits.physical =
+            ((strength * 2 + body + reaction) as f32 / 3.0).ceil() as u8;
+        self.derived_attributes.limits.mental =
+            ((logic * 2 + intuition + willpower) as f32 / 3.0).ceil() as u8;
+        self.derived_attributes.limits.social =
+            (((charisma * 2 + willpower) as f32 + self.derived_attributes.essence.current) / 3.0)
+                .ceil() as u8;
+        // Armor does not stack, the best armor worn counts.
+        self.derived_attributes.armor = self
+            .inventory
+            .values()
+            .filter(|item| item.equipped)
+            .filter_map(|item| item.armor_rating)
+            .max()
+            .unwrap_or(0);
+    }
+
+    pub fn augmentations(&self) -> impl Iterator<Item = &Augmentation> {
+        self.cyberware.iter().chain(&self.bioware)
+    }
+
+    // What the augmentations add to an attribute, 4 at most.
+    pub fn augmentation_bonus(&self, attribute: &str) -> u8 {
+        self.augmentations()
+            .map(|augmentation| augmentation.attribute_bonus(attribute))
+            .fold(0, u8::saturating_add)
+            .min(AUGMENTED_MAXIMUM)
+    }
+
+    pub fn augmented_attribute(&self, attribute: &str) -> u8 {
+        self.get_attribute(attribute)
+            .saturating_add(self.augmentation_bonus(attribute))
+    }
+
+    // Installing an augmentation costs essence for good: removing it later leaves an essence
+    // hole. Each point of essence lost, rounded up, costs a point of Magic and Resonance.
+    pub fn install_augmentation(
+        &mut self,
+        augmentation_type: AugmentationType,
+        augmentation: Augmentation,
+    ) -> Result<()> {
+        if self
+            .augmentations()
+            .any(|installed| installed.name.eq_ignore_ascii_case(&augmentation.name))
+        {
+            return Err(format!("{} already has {}", self.name, augmentation.name).into());
+        }
+        let essence = &mut self.derived_attributes.essence;
+        let left = essence.current - augmentation.essence();
+        if left <= 0.0 {
+            return Err(format!(
+                "{} has {:.2} essence left, not enough for {} costing {:.2}",
+                self.name,
+                essence.current,
+                augmentation.name,
+                augmentation.essence()
+            )
+            .into());
+        }
+        let max = essence.max;
+        let lost = |current: f32| (max - current - 0.001).ceil().max(0.0) as u8;
+        let points = lost(left).saturating_sub(lost(essence.current));
+        essence.current = left;
+        for rating in [&mut self.magic.magic, &mut self.resonance.resonance]
+            .into_iter()
+            .flatten()
+        {
+            *rating = rating.saturating_sub(points);
+        }
+
+        match augmentation_type {
+            AugmentationType::Cyberware => self.cyberware.push(augmentation),
+            AugmentationType::Bioware => self.bioware.push(augmentation),
+        }
+        self.update_derived_attributes();
+        Ok(())
+    }
+
+    pub fn remove_augmentation(
+        &mut self,
+        augmentation_type: AugmentationType,
+        name: &str,
+    ) -> Result<()> {
+        let augmentations = match augmentation_type {
+            AugmentationType::Cyberware => &mut self.cyberware,
+            AugmentationType::Bioware => &mut self.bioware,
+        };
+        let Some(index) = augmentations
+            .iter()
+            .position(|augmentation| augmentation.name.eq_ignore_ascii_case(name.trim()))
+        else {
+            return Err(format!("{} has no {augmentation_type} named {name}", self.name).into());
+        };
+        augmentations.remove(index);
+        self.update_derived_attributes();
+        Ok(())
     }
 
     // Retrieve all active skills combined from different skill categories.
@@ -294,9 +470,9 @@ impl CharacterSheet {
         all_skills
     }
 
-    // Calculate the dice pool for an action based on attribute and skill levels.
-    pub fn get_dice_pool(&self, attribute: &str, skill: &str) -> u8 {
-        let attribute_value = match attribute.to_lowercase().as_str() {
+    // The rating of an attribute by name, 0 when unknown.
+    pub fn get_attribute(&self, attribute: &str) -> u8 {
+        match attribute.to_lowercase().as_str() {
             "body" => self.attributes.body,
             "agility" => self.attributes.agility,
             "reaction" => self.attributes.reaction,
@@ -305,16 +481,272 @@ impl CharacterSheet {
             "logic" => self.attributes.logic,
             "intuition" => self.attributes.intuition,
             "charisma" => self.attributes.charisma,
+            "edge" => self.attributes.edge,
+            "magic" => self.magic.magic.unwrap_or(0),
+            "resonance" => self.resonance.resonance.unwrap_or(0),
             _ => 0,
+        }
+    }
+
+    // The dice pool of a skill with its linked attribute and no situational modifier, 0 when
+    // the attribute of the skill is unknown.
+    pub fn get_dice_pool(&self, skill: &str) -> u8 {
+        DicePool::new(self, None, skill, None, &[]).map_or(0, |pool| pool.total)
+    }
+
+    // The rating of a skill whatever the case of its name: active skills at the rating of their
+    // group when it is higher, then knowledge skills and languages. 0 when the character does
+    // not have it.
+    pub fn get_skill_rating(&self, skill: &str) -> u8 {
+        let active = find_rating(&self.get_all_active_skills(), skill);
+        let group = find_skill(skill)
+            .and_then(|info| info.group)
+            .and_then(|group| find_rating(&self.skill_groups, group));
+        active
+            .max(group)
+            .or_else(|| find_rating(&self.knowledge_skills, skill))
+            .or_else(|| find_rating(&self.languages, skill))
+            .unwrap_or(0)
+    }
+
+    // The attribute rolled with a skill: from the catalog for active skills, from the category
+    // for knowledge skills, intuition for languages.
+    pub fn linked_attribute(&self, skill: &str) -> Option<&'static str> {
+        if let Some(info) = find_skill(skill) {
+            return Some(info.attribute);
+        }
+        if find_rating(&self.knowledge_skills, skill).is_some() {
+            let category = self
+                .knowledge_categories
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(skill.trim()))
+                .map(|(_, category)| *category)
+                .unwrap_or_default();
+            return Some(category.attribute());
+        }
+        (find_rating(&self.languages, skill).is_some() || self.is_native_language(skill))
+            .then_some(LANGUAGE_ATTRIBUTE)
+    }
+
+    pub fn is_native_language(&self, skill: &str) -> bool {
+        self.native_language
+            .as_ref()
+            .is_some_and(|language| language.eq_ignore_ascii_case(skill.trim()))
+    }
+
+    pub fn get_specialization(&self, skill: &str) -> Option<&str> {
+        let skill = skill.trim();
+        self.specializations
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(skill))
+            .map(|(_, specialization)| specialization.as_str())
+    }
+
+    // Dice lost to wounds: one per 3 boxes of damage on each track.
+    pub fn wound_modifier(&self) -> u8 {
+        let monitors = &self.derived_attributes.monitors;
+        monitors.physical_damage / 3 + monitors.stun_damage / 3
+    }
+
+    // Marks damage on a track. Stun past a full track carries into the physical one at one box
+    // per two, physical past a full track goes into the overflow.
+    pub fn take_damage(&mut self, damage_type: DamageType, boxes: u8) {
+        let monitors = &mut self.derived_attributes.monitors;
+        match damage_type {
+            DamageType::Stun => {
+                let total = monitors.stun_damage.saturating_add(boxes);
+                monitors.stun_damage = total.min(monitors.stun);
+                let excess = total.saturating_sub(monitors.stun);
+                if excess >= 2 {
+                    self.take_damage(DamageType::Physical, excess / 2);
+                }
+            }
+            DamageType::Physical => {
+                let total = monitors.physical_damage.saturating_add(boxes);
+                monitors.physical_damage = total.min(monitors.physical);
+                monitors.overflow = monitors
+                    .overflow
+                    .saturating_add(total.saturating_sub(monitors.physical));
+            }
+        }
+    }
+
+    // Physical healing closes the overflow first.
+    pub fn heal(&mut self, damage_type: DamageType, boxes: u8) {
+        let monitors = &mut self.derived_attributes.monitors;
+        match damage_type {
+            DamageType::Stun => monitors.stun_damage = monitors.stun_damage.saturating_sub(boxes),
+            DamageType::Physical => {
+                let left = boxes.saturating_sub(monitors.overflow);
+                monitors.overflow = monitors.overflow.saturating_sub(boxes);
+                monitors.physical_damage = monitors.physical_damage.saturating_sub(left);
+            }
+        }
+    }
+
+    fn item_mut(&mut self, name: &str) -> Result<&mut Item> {
+        let character = self.name.clone();
+        self.inventory
+            .values_mut()
+            .find(|item| item.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("{character} has no {name} in their inventory").into())
+    }
+
+    // Only weapons and armor can be equipped.
+    pub fn equip_item(&mut self, name: &str, equipped: bool) -> Result<()> {
+        let item = self.item_mut(name)?;
+        if item.weapon.is_none() && item.armor_rating.is_none() {
+            return Err(format!("{} is neither a weapon nor armor", item.name).into());
+        }
+        item.equipped = equipped;
+        self.update_derived_attributes();
+        Ok(())
+    }
+
+    // The equipped weapon of that name, or the first equipped ranged weapon without a name.
+    pub fn equipped_weapon(&self, name: Option<&str>) -> Result<(&Item, &Weapon)> {
+        let mut weapons = self.inventory.values().filter_map(|item| {
+            item.weapon
+                .as_ref()
+                .filter(|_| item.equipped)
+                .map(|weapon| (item, weapon))
+        });
+        match name {
+            Some(name) => weapons
+                .find(|(item, _)| item.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| format!("{} has no {name} equipped", self.name).into()),
+            None => weapons
+                .find(|(_, weapon)| weapon.is_ranged())
+                .ok_or_else(|| format!("{} has no ranged weapon equipped", self.name).into()),
+        }
+    }
+
+    pub fn reload_weapon(&mut self, name: &str) -> Result<()> {
+        let item = self.item_mut(name)?;
+        match item.weapon.as_mut().filter(|weapon| weapon.is_ranged()) {
+            Some(weapon) => {
+                weapon.ammo = weapon.clip;
+                Ok(())
+            }
+            None => Err(format!("{} is not a ranged weapon", item.name).into()),
+        }
+    }
+
+    pub fn consume_ammo(&mut self, name: &str, rounds: u8) -> Result<()> {
+        let item = self.item_mut(name)?;
+        let Some(weapon) = item.weapon.as_mut() else {
+            return Err(format!("{} is not a weapon", item.name).into());
         };
+        if weapon.ammo < rounds {
+            return Err(format!(
+                "{} has {} rounds left, not enough to fire {rounds}",
+                item.name, weapon.ammo
+            )
+            .into());
+        }
+        weapon.ammo -= rounds;
+        Ok(())
+    }
 
-        let skill_value = self
-            .get_all_active_skills()
-            .get(skill)
-            .cloned()
-            .unwrap_or(0);
+    // A new tier drops the months paid in advance for the old one. The months paid in advance
+    // cost nuyen right away and first pay back the months behind.
+    pub fn change_lifestyle(
+        &mut self,
+        tier: Option<LifestyleTier>,
+        options: Option<Vec<LifestyleOption>>,
+        prepay_months: u32,
+    ) -> Result<()> {
+        let mut lifestyle = self.lifestyle.clone();
+        if let Some(tier) = tier.filter(|tier| *tier != lifestyle.tier) {
+            lifestyle.tier = tier;
+            lifestyle.prepaid_months = 0;
+        }
+        if let Some(options) = options {
+            lifestyle.options = options;
+        }
+        let cost = lifestyle.monthly_cost().saturating_mul(prepay_months);
+        if cost > self.nuyen {
+            return Err(format!(
+                "{} has ¥{}, not enough for {} of a {} lifestyle costing ¥{cost}",
+                self.name,
+                self.nuyen,
+                months(prepay_months),
+                lifestyle.tier
+            )
+            .into());
+        }
+        self.nuyen -= cost;
+        let settled = prepay_months.min(lifestyle.months_behind);
+        lifestyle.months_behind -= settled;
+        lifestyle.prepaid_months += prepay_months - settled;
+        self.lifestyle = lifestyle;
+        Ok(())
+    }
 
-        attribute_value + skill_value
+    // Each month of rent comes from the months paid in advance, or else from the nuyen, which
+    // also pay back the months behind as far as they go.
+    pub fn pay_upkeep(&mut self, months_due: u32) -> Upkeep {
+        let cost = self.lifestyle.monthly_cost();
+        let lifestyle = &mut self.lifestyle;
+        let prepaid_months_used = months_due.min(lifestyle.prepaid_months);
+        lifestyle.prepaid_months -= prepaid_months_used;
+        lifestyle.months_behind += months_due - prepaid_months_used;
+        let paid = match cost {
+            0 => lifestyle.months_behind,
+            cost => (self.nuyen / cost).min(lifestyle.months_behind),
+        };
+        lifestyle.months_behind -= paid;
+        self.nuyen -= paid * cost;
+
+        let months_behind = lifestyle.months_behind;
+        Upkeep {
+            character_name: self.name.clone(),
+            lifestyle: lifestyle.tier,
+            monthly_cost: cost,
+            nuyen_paid: paid * cost,
+            prepaid_months_used,
+            prepaid_months_left: lifestyle.prepaid_months,
+            months_behind,
+            nuyen_left: self.nuyen,
+            healing_modifier: lifestyle.healing_modifier(),
+            warning: (months_behind > 0).then(|| {
+                format!(
+                    "{} is {} behind on the rent of the {} lifestyle and owes ¥{}",
+                    self.name,
+                    months(months_behind),
+                    lifestyle.tier,
+                    cost.saturating_mul(months_behind)
+                )
+            }),
+        }
+    }
+
+    // Edge points are spent one at a time and come back up to the Edge rating.
+    pub fn spend_edge(&mut self) -> Result<()> {
+        if self.derived_attributes.edge_points == 0 {
+            return Err(format!("{} has no Edge points left", self.name).into());
+        }
+        self.derived_attributes.edge_points -= 1;
+        Ok(())
+    }
+
+    // Without points, all of them come back.
+    pub fn refresh_edge(&mut self, points: Option<u8>) {
+        let edge_points = &mut self.derived_attributes.edge_points;
+        *edge_points = match points {
+            Some(points) => edge_points.saturating_add(points),
+            None => self.attributes.edge,
+        }
+        .min(self.attributes.edge);
+    }
+
+    pub fn is_unconscious(&self) -> bool {
+        let monitors = &self.derived_attributes.monitors;
+        monitors.stun_damage >= monitors.stun || monitors.physical_damage >= monitors.physical
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.derived_attributes.monitors.overflow > self.attributes.body
     }
 
     // Get the maximum limit for an action based on the type of limit (physical, mental, social).
@@ -349,6 +781,11 @@ pub struct CharacterSheetBuilder {
     resonance: u8,
     skills: Skills,
     knowledge_skills: Skill,
+    specializations: HashMap<String, String>,
+    skill_groups: Skill,
+    knowledge_categories: HashMap<String, KnowledgeCategory>,
+    languages: Skill,
+    native_language: Option<String>,
     qualities: Vec<Quality>,
     nuyen: u32,
     inventory: HashMap<String, Item>,
@@ -381,6 +818,11 @@ impl CharacterSheetBuilder {
                 technical: HashMap::new(),
             },
             knowledge_skills: HashMap::new(),
+            specializations: HashMap::new(),
+            skill_groups: HashMap::new(),
+            knowledge_categories: HashMap::new(),
+            languages: HashMap::new(),
+            native_language: None,
             qualities: vec![],
             nuyen: 0,
             inventory: HashMap::new(),
@@ -453,6 +895,34 @@ impl CharacterSheetBuilder {
         self
     }
 
+    pub fn specializations(mut self, specializations: HashMap<String, String>) -> Self {
+        self.specializations = specializations;
+        self
+    }
+
+    pub fn skill_groups(mut self, skill_groups: Skill) -> Self {
+        self.skill_groups = skill_groups;
+        self
+    }
+
+    pub fn knowledge_categories(
+        mut self,
+        knowledge_categories: HashMap<String, KnowledgeCategory>,
+    ) -> Self {
+        self.knowledge_categories = knowledge_categories;
+        self
+    }
+
+    pub fn languages(mut self, languages: Skill) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    pub fn native_language(mut self, native_language: Option<String>) -> Self {
+        self.native_language = native_language;
+        self
+    }
+
     pub fn qualities(mut self, qualities: Vec<Quality>) -> Self {
         self.qualities = qualities;
         self
@@ -491,6 +961,48 @@ pub enum CharacterSheetUpdate {
         attribute: String,
         operation: UpdateOperation<CharacterValue>,
     },
+    // Boxes left after the soak roll.
+    Damage {
+        damage_type: DamageType,
+        boxes: u8,
+    },
+    Heal {
+        damage_type: DamageType,
+        boxes: u8,
+    },
+    SpendEdge,
+    RefreshEdge {
+        points: Option<u8>,
+    },
+    InstallAugmentation {
+        augmentation_type: AugmentationType,
+        augmentation: Augmentation,
+    },
+    RemoveAugmentation {
+        augmentation_type: AugmentationType,
+        name: String,
+    },
+    // The months paid in advance go to the months behind first.
+    ChangeLifestyle {
+        tier: Option<LifestyleTier>,
+        options: Option<Vec<LifestyleOption>>,
+        prepay_months: u32,
+    },
+    // Rent due for the months gone by.
+    PayUpkeep {
+        months: u32,
+    },
+    EquipItem {
+        item: String,
+        equipped: bool,
+    },
+    ReloadWeapon {
+        item: String,
+    },
+    FireWeapon {
+        item: String,
+        rounds: u8,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -502,8 +1014,9 @@ pub enum CharacterValue {
     Race(Race),
     Skills(Skills),
     HashMapStringU8(Skill),
+    HashMapStringString(HashMap<String, String>),
+    HashMapStringKnowledgeCategory(HashMap<String, KnowledgeCategory>),
     VecQuality(Vec<Quality>),
-    VecString(Vec<String>),
     HashMapStringItem(HashMap<String, Item>),
     HashMapStringContact(HashMap<String, Contact>),
     OptionMatrixAttributes(Option<MatrixAttributes>),
@@ -527,6 +1040,39 @@ impl CharacterSheet {
                 self.update_derived_attributes();
                 Ok(())
             }
+            CharacterSheetUpdate::Damage { damage_type, boxes } => {
+                self.take_damage(*damage_type, *boxes);
+                Ok(())
+            }
+            CharacterSheetUpdate::Heal { damage_type, boxes } => {
+                self.heal(*damage_type, *boxes);
+                Ok(())
+            }
+            CharacterSheetUpdate::SpendEdge => self.spend_edge(),
+            CharacterSheetUpdate::RefreshEdge { points } => {
+                self.refresh_edge(*points);
+                Ok(())
+            }
+            CharacterSheetUpdate::InstallAugmentation {
+                augmentation_type,
+                augmentation,
+            } => self.install_augmentation(*augmentation_type, augmentation.clone()),
+            CharacterSheetUpdate::RemoveAugmentation {
+                augmentation_type,
+                name,
+            } => self.remove_augmentation(*augmentation_type, name),
+            CharacterSheetUpdate::ChangeLifestyle {
+                tier,
+                options,
+                prepay_months,
+            } => self.change_lifestyle(*tier, options.clone(), *prepay_months),
+            CharacterSheetUpdate::PayUpkeep { months } => {
+                self.pay_upkeep(*months);
+                Ok(())
+            }
+            CharacterSheetUpdate::EquipItem { item, equipped } => self.equip_item(item, *equipped),
+            CharacterSheetUpdate::ReloadWeapon { item } => self.reload_weapon(item),
+            CharacterSheetUpdate::FireWeapon { item, rounds } => self.consume_ammo(item, *rounds),
         }
     }
 
@@ -566,18 +1112,27 @@ impl CharacterSheet {
             ("knowledge_skills", CharacterValue::HashMapStringU8(v)) => {
                 self.knowledge_skills.extend(v)
             }
+            ("specializations", CharacterValue::HashMapStringString(v)) => {
+                self.specializations.extend(v)
+            }
+            ("skill_groups", CharacterValue::HashMapStringU8(v)) => self.skill_groups.extend(v),
+            ("knowledge_categories", CharacterValue::HashMapStringKnowledgeCategory(v)) => {
+                self.knowledge_categories.extend(v)
+            }
+            ("languages", CharacterValue::HashMapStringU8(v)) => self.languages.extend(v),
+            ("native_language", CharacterValue::String(v)) => self.native_language = Some(v),
             ("nuyen", CharacterValue::Nuyen(v)) => self.nuyen = v,
-            ("lifestyle", CharacterValue::String(v)) => self.lifestyle = v,
             ("contacts", CharacterValue::HashMapStringContact(v)) => self.contacts = v,
             ("qualities", CharacterValue::VecQuality(v)) => self.qualities = v,
-            ("cyberware", CharacterValue::VecString(v)) => self.cyberware = v,
-            ("bioware", CharacterValue::VecString(v)) => self.bioware = v,
             ("inventory", CharacterValue::HashMapStringItem(v)) => {
                 for (key, new_item) in v {
                     if let Some(existing_item) = self.inventory.get_mut(&key) {
                         // Update existing item
                         existing_item.quantity = new_item.quantity;
                         existing_item.description = new_item.description;
+                        existing_item.category = new_item.category;
+                        existing_item.weapon = new_item.weapon;
+                        existing_item.armor_rating = new_item.armor_rating;
                     } else {
                         // Add new item
                         self.inventory.insert(key, new_item);
@@ -603,8 +1158,6 @@ impl CharacterSheet {
             ("nuyen", CharacterValue::Nuyen(v)) => self.nuyen = self.nuyen.saturating_add(v),
             ("contacts", CharacterValue::HashMapStringContact(v)) => self.contacts.extend(v),
             ("qualities", CharacterValue::VecQuality(v)) => self.qualities.extend(v),
-            ("cyberware", CharacterValue::VecString(v)) => self.cyberware.extend(v),
-            ("bioware", CharacterValue::VecString(v)) => self.bioware.extend(v),
             ("inventory", CharacterValue::HashMapStringItem(v)) => {
                 for (key, item) in v {
                     if let Some(existing_item) = self.inventory.get_mut(&key) {
@@ -636,12 +1189,6 @@ impl CharacterSheet {
             ("qualities", CharacterValue::VecQuality(v)) => {
                 self.qualities.retain(|q| !v.contains(q))
             }
-            ("cyberware", CharacterValue::VecString(v)) => {
-                self.cyberware.retain(|item| !v.contains(item))
-            }
-            ("bioware", CharacterValue::VecString(v)) => {
-                self.bioware.retain(|item| !v.contains(item))
-            }
             ("inventory", CharacterValue::HashMapStringItem(v)) => {
                 for (key, item) in v {
                     if let Some(existing_item) = self.inventory.get_mut(&key) {
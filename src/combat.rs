@@ -0,0 +1,241 @@
+// /combat.rs
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::character::CharacterSheet;
+
+// Initiative spent by each pass, combatants keep acting while their score stays above zero.
+const PASS_COST: i16 = 10;
+// Initiative dice rolled with the Blitz Edge action.
+const BLITZ_DICE: u8 = 5;
+
+// An ongoing effect on a combatant, counted down at each new round.
+// Without rounds it lasts until it is removed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub name: String,
+    pub rounds: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Combatant {
+    pub name: String,
+    pub main: bool,
+    // The initiative score, rolled at each round and lowered by passes and interrupts.
+    pub initiative: i16,
+    pub dice_results: Vec<u8>,
+    // Whether the combatant took their action of the current pass.
+    pub acted: bool,
+    pub status_effects: Vec<StatusEffect>,
+    // Spent Edge to go first in every pass of the round.
+    #[serde(default)]
+    pub seized: bool,
+}
+
+impl Combatant {
+    fn roll(character: &CharacterSheet, rng: &mut impl Rng) -> Self {
+        let mut combatant = Combatant {
+            name: character.name.clone(),
+            main: character.main,
+            initiative: 0,
+            dice_results: Vec::new(),
+            acted: false,
+            status_effects: Vec::new(),
+            seized: false,
+        };
+        combatant.reroll(character, character.derived_attributes.initiative.1, rng);
+        combatant
+    }
+
+    // Reaction and intuition plus the initiative dice, less the wound modifier.
+    fn reroll(&mut self, character: &CharacterSheet, dice: u8, rng: &mut impl Rng) {
+        let base = character.derived_attributes.initiative.0;
+        self.dice_results = (0..dice).map(|_| rng.random_range(1..=6)).collect();
+        self.initiative = i16::from(base)
+            + self
+                .dice_results
+                .iter()
+                .map(|&die| i16::from(die))
+                .sum::<i16>()
+            - i16::from(character.wound_modifier());
+        self.acted = false;
+    }
+
+    fn can_act(&self) -> bool {
+        self.initiative > 0 && !self.acted
+    }
+}
+
+// A fight in initiative passes, kept in the save while it lasts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Combat {
+    pub round: u32,
+    pub pass: u32,
+    pub combatants: Vec<Combatant>,
+    // The combatant whose action is being narrated.
+    pub current: Option<String>,
+}
+
+impl Combat {
+    pub fn start(characters: &[&CharacterSheet]) -> Self {
+        let mut rng = rand::rng();
+        let mut combat = Combat {
+            round: 1,
+            pass: 1,
+            combatants: characters
+                .iter()
+                .map(|character| Combatant::roll(character, &mut rng))
+                .collect(),
+            current: None,
+        };
+        combat.sort();
+        combat.current = combat.next_actor();
+        combat
+    }
+
+    // Those who seized the initiative first, then the highest initiative, the player character
+    // wins the ties.
+    fn sort(&mut self) {
+        self.combatants.sort_by_key(|combatant| {
+            (
+                !combatant.seized,
+                std::cmp::Reverse(combatant.initiative),
+                !combatant.main,
+            )
+        });
+    }
+
+    fn next_actor(&self) -> Option<String> {
+        self.combatants
+            .iter()
+            .find(|combatant| combatant.can_act())
+            .map(|combatant| combatant.name.clone())
+    }
+
+    fn combatant_mut(&mut self, name: &str) -> Result<&mut Combatant, String> {
+        self.combatants
+            .iter_mut()
+            .find(|combatant| combatant.name == name)
+            .ok_or_else(|| format!("'{name}' is not in the combat"))
+    }
+
+    // Ends the action of the current combatant and moves to the next one, starting a new pass
+    // when nobody is left and a new round, with initiative rolled again, when nobody can act.
+    // The characters give the initiative of the new round.
+    pub fn next_turn(&mut self, characters: &[CharacterSheet]) {
+        if let Some(name) = self.current.take()
+            && let Ok(combatant) = self.combatant_mut(&name)
+        {
+            combatant.acted = true;
+        }
+        self.current = self.next_actor();
+        if self.current.is_some() {
+            return;
+        }
+
+        for combatant in &mut self.combatants {
+            combatant.initiative -= PASS_COST;
+            combatant.acted = false;
+        }
+        self.pass += 1;
+        self.current = self.next_actor();
+        if self.current.is_some() {
+            return;
+        }
+
+        let mut rng = rand::rng();
+        self.round += 1;
+        self.pass = 1;
+        for combatant in &mut self.combatants {
+            if let Some(character) = characters.iter().find(|c| c.name == combatant.name) {
+                combatant.reroll(
+                    character,
+                    character.derived_attributes.initiative.1,
+                    &mut rng,
+                );
+            } else {
+                combatant.initiative = 0;
+            }
+            combatant.seized = false;
+            combatant
+                .status_effects
+                .retain_mut(|effect| match &mut effect.rounds {
+                    Some(rounds) => {
+                        *rounds = rounds.saturating_sub(1);
+                        *rounds > 0
+                    }
+                    None => true,
+                });
+        }
+        self.sort();
+        self.current = self.next_actor();
+    }
+
+    // Interrupt actions like a dodge or a full defense are taken out of turn and paid
+    // with initiative.
+    pub fn interrupt(&mut self, name: &str, cost: u8) -> Result<(), String> {
+        let combatant = self.combatant_mut(name)?;
+        if combatant.initiative < i16::from(cost) {
+            return Err(format!(
+                "{name} has {} initiative left, not enough for an interrupt costing {cost}",
+                combatant.initiative
+            ));
+        }
+        combatant.initiative -= i16::from(cost);
+        self.sort();
+        Ok(())
+    }
+
+    // Seize the Initiative: the combatant goes first in every pass until the end of the round.
+    pub fn seize_the_initiative(&mut self, name: &str) -> Result<(), String> {
+        self.combatant_mut(name)?.seized = true;
+        self.sort();
+        Ok(())
+    }
+
+    // Blitz: the initiative of the round is rolled again with 5 dice, less the passes gone by.
+    pub fn blitz(&mut self, character: &CharacterSheet) -> Result<(), String> {
+        let passes = i16::try_from(self.pass - 1).unwrap_or(i16::MAX / PASS_COST);
+        let combatant = self.combatant_mut(&character.name)?;
+        let acted = combatant.acted;
+        combatant.reroll(character, BLITZ_DICE, &mut rand::rng());
+        combatant.initiative -= PASS_COST * passes;
+        combatant.acted = acted;
+        self.sort();
+        Ok(())
+    }
+
+    pub fn add_status_effect(&mut self, name: &str, effect: StatusEffect) -> Result<(), String> {
+        let combatant = self.combatant_mut(name)?;
+        combatant.status_effects.retain(|e| e.name != effect.name);
+        combatant.status_effects.push(effect);
+        Ok(())
+    }
+
+    pub fn remove_status_effect(&mut self, name: &str, effect: &str) -> Result<(), String> {
+        let combatant = self.combatant_mut(name)?;
+        combatant.status_effects.retain(|e| e.name != effect);
+        Ok(())
+    }
+
+    // What the Game Master needs to narrate the next action.
+    pub fn summary(&self) -> Value {
+        json!({
+            "round": self.round,
+            "initiative_pass": self.pass,
+            "acting": self.current,
+            "turn_order": self
+                .combatants
+                .iter()
+                .map(|combatant| json!({
+                    "name": combatant.name,
+                    "initiative": combatant.initiative,
+                    "acted": combatant.acted,
+                    "can_act_this_pass": combatant.initiative > 0,
+                    "status_effects": combatant.status_effects,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
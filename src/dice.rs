@@ -1,29 +1,194 @@
 // Import required modules and crates.
-use crate::game_state::GameState;
+use crate::{
+    character::{CharacterSheet, DamageType},
+    dice_pool::{DicePool, PoolModifier},
+    game_state::GameState,
+    gear::{FiringMode, Weapon},
+};
 use rand::Rng; // RNG utilities from the rand crate for generating random numbers.
 use serde::{Deserialize, Serialize}; // Serialization utilities for struct serialization.
 
 // Structure to handle the request for a dice roll.
 #[derive(Deserialize)]
 pub struct DiceRollRequest {
-    character_name: String,      // Name of the character making the roll.
-    attribute: String,           // The attribute involved in the dice roll.
-    skill: String,               // The skill involved in the dice roll.
-    limit_type: String,          // The type of limit (e.g., physical, mental) applied to the roll.
-    threshold: Option<u8>,       // Optional threshold for determining success.
-    edge_action: Option<String>, // Optional action that uses "edge" to affect the roll.
-    extra_dice: Option<u8>,      // Optional number of extra dice to roll.
+    pub character_name: String,      // Name of the character making the roll.
+    attribute: Option<String>,       // The attribute, the linked one of the skill by default.
+    skill: String,                   // The skill involved in the dice roll.
+    limit_type: String, // The type of limit (e.g., physical, mental) applied to the roll.
+    threshold: Option<u8>, // Optional threshold for determining success.
+    edge_action: Option<EdgeAction>, // Optional Edge spent on the roll.
+    specialization: Option<String>, // Optional specialization of the skill covering the action.
+    #[serde(default)]
+    modifiers: Vec<PoolModifier>, // Situational modifiers like cover or visibility.
+}
+
+// Structure to handle one side of an opposed test.
+#[derive(Deserialize)]
+pub struct TestSide {
+    pub character_name: String,      // Name of the character rolling.
+    attribute: Option<String>,       // The attribute, the linked one of the skill by default.
+    skill: String,                   // The skill involved in the dice roll.
+    limit_type: String,              // The type of limit applied to the roll.
+    edge_action: Option<EdgeAction>, // Optional Edge spent on the roll.
+    specialization: Option<String>,  // Optional specialization of the skill covering the action.
+    #[serde(default)]
+    modifiers: Vec<PoolModifier>, // Situational modifiers like cover or visibility.
+}
+
+// Structure to handle the request for an opposed test, the attacker against the defender.
+#[derive(Deserialize)]
+pub struct OpposedTestRequest {
+    pub attacker: TestSide,
+    pub defender: TestSide,
+}
+
+// Structure to handle the request for an extended test, rolled until the hits add up.
+#[derive(Deserialize)]
+pub struct ExtendedTestRequest {
+    pub character_name: String,     // Name of the character making the rolls.
+    attribute: Option<String>,      // The attribute, the linked one of the skill by default.
+    skill: String,                  // The skill involved in the dice rolls.
+    limit_type: String,             // The type of limit applied to each roll.
+    threshold: u8,                  // Hits to accumulate over the rolls.
+    interval: String,               // Time each roll takes, like "1 hour".
+    max_rolls: Option<u8>,          // Optional number of rolls before the time runs out.
+    specialization: Option<String>, // Optional specialization of the skill covering the work.
+    #[serde(default)]
+    modifiers: Vec<PoolModifier>, // Situational modifiers like working conditions.
+}
+
+// Structure to handle the request to fire an equipped weapon, at a defender or not.
+#[derive(Deserialize)]
+pub struct FireWeaponRequest {
+    pub character_name: String,      // Name of the character firing.
+    weapon: Option<String>,          // The weapon fired, the first equipped ranged one by default.
+    mode: FiringMode,                // The firing mode, which sets the rounds fired.
+    edge_action: Option<EdgeAction>, // Optional Edge spent on the roll.
+    specialization: Option<String>,  // Optional specialization of the skill covering the attack.
+    #[serde(default)]
+    modifiers: Vec<PoolModifier>, // Situational modifiers like cover or visibility.
+    pub defender: Option<TestSide>,  // The defense roll of the target, for an opposed test.
 }
 
 // Structure to encapsulate the response after a dice roll.
 #[derive(Debug, Serialize)]
 pub struct DiceRollResponse {
+    pub dice_pool: DicePool,    // Where the dice of the pool come from.
     pub hits: u8,               // Number of successful hits.
     pub glitch: bool,           // Whether a glitch occurred.
     pub critical_glitch: bool,  // Whether a critical glitch occurred.
     pub critical_success: bool, // Whether a critical success was achieved.
     pub dice_results: Vec<u8>,  // Results of each die rolled.
     pub success: bool,          // Whether the roll was overall a success.
+    pub edge_spent: bool,       // Whether the Edge action was needed and a point was spent.
+    pub edge_points_left: u8,   // Edge points of the character after the roll.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opposed: Option<OpposedRoll>, // The defender side of an opposed test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended: Option<ExtendedRoll>, // Each roll of an extended test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attack: Option<WeaponAttack>, // The weapon fired and the damage of the attack.
+}
+
+impl DiceRollResponse {
+    fn new(dice_pool: DicePool, roll: DiceRoll, edge_points: u8) -> Self {
+        DiceRollResponse {
+            dice_pool,
+            hits: roll.hits,
+            glitch: roll.glitch,
+            critical_glitch: roll.critical_glitch,
+            critical_success: roll.critical_success,
+            dice_results: roll.dice_results,
+            success: roll.success,
+            edge_spent: roll.edge_spent,
+            edge_points_left: edge_points - u8::from(roll.edge_spent),
+            opposed: None,
+            extended: None,
+            attack: None,
+        }
+    }
+}
+
+// Structure to encapsulate the defender side of an opposed test.
+#[derive(Debug, Serialize)]
+pub struct OpposedRoll {
+    pub defender: String,
+    pub defender_dice_pool: DicePool,
+    pub defender_hits: u8,
+    pub defender_glitch: bool,
+    pub defender_critical_glitch: bool,
+    pub defender_dice_results: Vec<u8>,
+    pub defender_edge_spent: bool,
+    pub defender_edge_points_left: u8,
+    pub net_hits: i16, // Attacker hits less defender hits, ties go to the defender.
+}
+
+// Structure to encapsulate the weapon fired, the damage adds the net hits of an opposed test.
+#[derive(Debug, Serialize)]
+pub struct WeaponAttack {
+    pub weapon: String,
+    pub mode: FiringMode,
+    pub rounds: u8,
+    pub ammo_left: u8,
+    pub damage_value: u8,
+    pub damage_type: DamageType,
+    pub armor_penetration: i8,
+    pub defense_modifier: i8, // Dice the defender loses against the rounds fired.
+}
+
+// Structure to encapsulate the rolls of an extended test.
+#[derive(Debug, Serialize)]
+pub struct ExtendedRoll {
+    pub threshold: u8,
+    pub rolls: Vec<ExtendedStep>,
+    pub time_taken: String, // The number of rolls times the interval.
+}
+
+// Structure to encapsulate a single roll of an extended test.
+#[derive(Debug, Serialize)]
+pub struct ExtendedStep {
+    pub dice_pool: u8,  // The pool shrinks by one die at each roll.
+    pub hits: u8,       // Hits of this roll.
+    pub hits_lost: u8,  // Hits taken off the total by a glitch.
+    pub total_hits: u8, // Hits accumulated after this roll.
+    pub dice_results: Vec<u8>,
+    pub glitch: bool,
+    pub critical_glitch: bool, // A critical glitch fails the whole test.
+}
+
+// Helper function to find a character by name in the game state.
+fn find_character<'a>(
+    game_state: &'a GameState,
+    character_name: &str,
+) -> Result<&'a CharacterSheet, String> {
+    game_state
+        .characters
+        .iter()
+        .find(|c| c.name == character_name)
+        .ok_or_else(|| format!("Character '{character_name}' not found"))
+}
+
+// Helper function to roll a test of a character, refusing Edge without points left.
+fn roll_test(
+    character: &CharacterSheet,
+    dice_pool: &DicePool,
+    limit: u8,
+    threshold: Option<u8>,
+    edge_action: Option<EdgeAction>,
+) -> Result<DiceRoll, String> {
+    // Edge can only be spent while the character has points left.
+    if edge_action.is_some() && character.derived_attributes.edge_points == 0 {
+        return Err(format!("{} has no Edge points left", character.name));
+    }
+
+    // Execute the dice roll with the calculated parameters.
+    Ok(dice_roll(
+        dice_pool.total,
+        Some(limit),
+        threshold,
+        edge_action,
+        character.attributes.edge,
+    ))
 }
 
 // Function to perform a dice roll based on a request and game state.
@@ -31,54 +196,356 @@ pub fn perform_dice_roll(
     request: DiceRollRequest,
     game_state: &GameState,
 ) -> Result<DiceRollResponse, String> {
-    // Find the character by name from the game state.
-    let character = game_state
-        .characters
-        .iter()
-        .find(|c| c.name == request.character_name)
-        .ok_or_else(|| format!("Character '{}' not found", request.character_name))?;
+    let character = find_character(game_state, &request.character_name)?;
+    // Calculate the total dice pool from character's attributes, skills and modifiers.
+    let dice_pool = DicePool::new(
+        character,
+        request.attribute.as_deref(),
+        &request.skill,
+        request.specialization.as_deref(),
+        &request.modifiers,
+    )?;
+    let roll = roll_test(
+        character,
+        &dice_pool,
+        character.get_limit(&request.limit_type),
+        request.threshold,
+        request.edge_action,
+    )?;
+    Ok(DiceRollResponse::new(
+        dice_pool,
+        roll,
+        character.derived_attributes.edge_points,
+    ))
+}
 
-    // Calculate the total dice pool from character's attributes and skills.
-    let dice_pool = character.get_dice_pool(&request.attribute, &request.skill);
+// Function to perform an opposed test: both sides roll and the attacker needs more hits.
+pub fn perform_opposed_test(
+    request: OpposedTestRequest,
+    game_state: &GameState,
+) -> Result<DiceRollResponse, String> {
+    check_opposed(
+        &request.attacker.character_name,
+        request.attacker.edge_action,
+        &request.defender,
+    )?;
+    let attacker = roll_side(game_state, &request.attacker, &[]);
+    let defender = roll_side(game_state, &request.defender, &[]);
+    let (attacker_pool, attacker, attacker_edge_points) = attacker?;
+    let response = DiceRollResponse::new(attacker_pool, attacker, attacker_edge_points);
+    Ok(oppose(response, request.defender.character_name, defender?))
+}
 
-    // Get the applicable limit for the dice roll from the character's stats.
-    let limit = Some(character.get_limit(&request.limit_type));
+// Helper function to check an opposed test before any roll. Each side is checked for Edge
+// against its own sheet, so a character cannot be on both sides. Second Chance needs a
+// threshold to know that the test failed, which only the other side gives.
+fn check_opposed(
+    attacker: &str,
+    attacker_edge_action: Option<EdgeAction>,
+    defender: &TestSide,
+) -> Result<(), String> {
+    if attacker == defender.character_name {
+        return Err(format!("{attacker} cannot oppose themselves"));
+    }
+    if [attacker_edge_action, defender.edge_action].contains(&Some(EdgeAction::SecondChance)) {
+        return Err("SecondChance cannot be spent on an opposed test".to_string());
+    }
+    Ok(())
+}
 
-    // Parse the optional edge action.
-    let edge_action = match request.edge_action.as_deref() {
-        Some("RerollFailures") => Some(EdgeAction::RerollFailures),
-        Some("AddExtraDice") => request.extra_dice.map(EdgeAction::AddExtraDice),
-        Some("PushTheLimit") => Some(EdgeAction::PushTheLimit),
-        Some(_) => return Err("Invalid edge action".to_string()),
-        None => None,
-    };
+// Helper function to roll one side of an opposed test, with modifiers of the action opposed.
+fn roll_side(
+    game_state: &GameState,
+    side: &TestSide,
+    opposing: &[PoolModifier],
+) -> Result<(DicePool, DiceRoll, u8), String> {
+    let character = find_character(game_state, &side.character_name)?;
+    let modifiers: Vec<PoolModifier> = side.modifiers.iter().chain(opposing).cloned().collect();
+    let dice_pool = DicePool::new(
+        character,
+        side.attribute.as_deref(),
+        &side.skill,
+        side.specialization.as_deref(),
+        &modifiers,
+    )?;
+    let roll = roll_test(
+        character,
+        &dice_pool,
+        character.get_limit(&side.limit_type),
+        None,
+        side.edge_action,
+    )?;
+    Ok((dice_pool, roll, character.derived_attributes.edge_points))
+}
 
-    // Execute the dice roll with the calculated parameters.
-    let roll_result = dice_roll(dice_pool, limit, request.threshold, edge_action);
+// Helper function to add the defender side to the roll of the attacker.
+fn oppose(
+    mut response: DiceRollResponse,
+    defender_name: String,
+    (defender_pool, defender, defender_edge_points): (DicePool, DiceRoll, u8),
+) -> DiceRollResponse {
+    let net_hits = i16::from(response.hits) - i16::from(defender.hits);
+    response.success = net_hits > 0;
+    response.opposed = Some(OpposedRoll {
+        defender: defender_name,
+        defender_dice_pool: defender_pool,
+        defender_hits: defender.hits,
+        defender_glitch: defender.glitch,
+        defender_critical_glitch: defender.critical_glitch,
+        defender_dice_results: defender.dice_results,
+        defender_edge_spent: defender.edge_spent,
+        defender_edge_points_left: defender_edge_points - u8::from(defender.edge_spent),
+        net_hits,
+    });
+    response
+}
 
-    // Determine if the roll met the success criteria.
-    let success = match request.threshold {
-        Some(threshold) => roll_result.hits >= threshold,
-        None => roll_result.hits > 0,
-    };
+// Function to fire an equipped ranged weapon: the character rolls the skill of the weapon, less
+// the recoil they cannot compensate, limited by the accuracy of the weapon. The clip must hold
+// the rounds of the firing mode. Against a defender, the mode lowers the defense.
+pub fn fire_weapon(
+    request: FireWeaponRequest,
+    game_state: &GameState,
+) -> Result<DiceRollResponse, String> {
+    let character = find_character(game_state, &request.character_name)?;
+    let (item, weapon) = character
+        .equipped_weapon(request.weapon.as_deref())
+        .map_err(|e| e.to_string())?;
+    if !weapon.is_ranged() {
+        return Err(format!(
+            "{} is a melee weapon, roll its attacks with perform_opposed_test",
+            item.name
+        ));
+    }
+    if !weapon.modes.contains(&request.mode) {
+        return Err(format!(
+            "{} cannot fire in {} mode",
+            item.name, request.mode
+        ));
+    }
+    let rounds = request.mode.rounds();
+    if weapon.ammo < rounds {
+        return Err(format!(
+            "{} has {} rounds left, not enough to fire {rounds}, reload it first",
+            item.name, weapon.ammo
+        ));
+    }
 
+    let mut modifiers = request.modifiers;
+    let recoil = i16::from(rounds) - i16::from(recoil_compensation(character, weapon));
+    if recoil > 0 {
+        modifiers.push(PoolModifier {
+            name: "Recoil".to_string(),
+            value: -i8::try_from(recoil).unwrap_or(i8::MAX),
+        });
+    }
+    let dice_pool = DicePool::new(
+        character,
+        None,
+        &weapon.skill,
+        request.specialization.as_deref(),
+        &modifiers,
+    )?;
+    if let Some(defender) = &request.defender {
+        check_opposed(&character.name, request.edge_action, defender)?;
+    }
+    let limit = weapon
+        .accuracy
+        .unwrap_or_else(|| character.get_limit("physical"));
+    let roll = roll_test(character, &dice_pool, limit, None, request.edge_action)?;
+    let mut response =
+        DiceRollResponse::new(dice_pool, roll, character.derived_attributes.edge_points);
+    response.attack = Some(WeaponAttack {
+        weapon: item.name.clone(),
+        mode: request.mode,
+        rounds,
+        ammo_left: weapon.ammo - rounds,
+        damage_value: weapon.damage_value,
+        damage_type: weapon.damage_type,
+        armor_penetration: weapon.armor_penetration,
+        defense_modifier: request.mode.defense_modifier(),
+    });
+
+    if let Some(defender) = request.defender {
+        let defense: Vec<PoolModifier> = Some(PoolModifier {
+            name: format!("{} fire", request.mode),
+            value: request.mode.defense_modifier(),
+        })
+        .filter(|modifier| modifier.value != 0)
+        .into_iter()
+        .collect();
+        let roll = roll_side(game_state, &defender, &defense)?;
+        response = oppose(response, defender.character_name, roll);
+    }
+    Ok(response)
+}
+
+// Rounds fired in one action without a penalty: 1, a third of the strength and the recoil
+// compensation of the weapon.
+fn recoil_compensation(character: &CharacterSheet, weapon: &Weapon) -> u8 {
+    1 + character.augmented_attribute("strength").div_ceil(3) + weapon.recoil_compensation
+}
+
+// Function to perform an extended test: the character rolls again with one die less each
+// interval, until the hits add up to the threshold, the pool or the time runs out, or a
+// critical glitch ruins the work. A glitch takes 1D6 hits off the total.
+pub fn perform_extended_test(
+    request: ExtendedTestRequest,
+    game_state: &GameState,
+) -> Result<DiceRollResponse, String> {
+    let character = find_character(game_state, &request.character_name)?;
+    let pool = DicePool::new(
+        character,
+        request.attribute.as_deref(),
+        &request.skill,
+        request.specialization.as_deref(),
+        &request.modifiers,
+    )?;
+    let mut dice_pool = pool.total;
+    let limit = Some(character.get_limit(&request.limit_type));
+    let max_rolls = request.max_rolls.unwrap_or(u8::MAX);
+
+    let mut rng = rand::rng();
+    let mut rolls: Vec<ExtendedStep> = Vec::new();
+    let mut total_hits: u8 = 0;
+    while total_hits < request.threshold && dice_pool > 0 && rolls.len() < max_rolls as usize {
+        let roll = dice_roll(dice_pool, limit, None, None, 0);
+        let hits_lost = if roll.glitch && !roll.critical_glitch {
+            roll_die(&mut rng).min(total_hits.saturating_add(roll.hits))
+        } else {
+            0
+        };
+        total_hits = total_hits.saturating_add(roll.hits) - hits_lost;
+        rolls.push(ExtendedStep {
+            dice_pool,
+            hits: roll.hits,
+            hits_lost,
+            total_hits,
+            dice_results: roll.dice_results,
+            glitch: roll.glitch,
+            critical_glitch: roll.critical_glitch,
+        });
+        if roll.critical_glitch {
+            break;
+        }
+        dice_pool -= 1;
+    }
+
+    let critical_glitch = rolls.iter().any(|roll| roll.critical_glitch);
     Ok(DiceRollResponse {
-        hits: roll_result.hits,
-        glitch: roll_result.glitch,
-        critical_glitch: roll_result.critical_glitch,
-        critical_success: roll_result.critical_success,
-        dice_results: roll_result.dice_results,
-        success,
+        dice_pool: pool,
+        hits: total_hits,
+        glitch: rolls.iter().any(|roll| roll.glitch),
+        critical_glitch,
+        critical_success: false,
+        dice_results: rolls
+            .iter()
+            .flat_map(|roll| roll.dice_results.iter().copied())
+            .collect(),
+        success: !critical_glitch && total_hits >= request.threshold,
+        edge_spent: false,
+        edge_points_left: character.derived_attributes.edge_points,
+        opposed: None,
+        extended: Some(ExtendedRoll {
+            threshold: request.threshold,
+            time_taken: format!("{} x {}", rolls.len(), request.interval),
+            rolls,
+        }),
+        attack: None,
     })
 }
 
+// Structure to encapsulate the damage a character took after soaking it.
+#[derive(Debug, Serialize)]
+pub struct DamageResponse {
+    pub damage_type: DamageType, // Physical damage below the armor becomes stun.
+    pub soak_hits: u8,           // Boxes resisted by the soak roll.
+    pub dice_results: Vec<u8>,   // Results of each soak die, empty when the damage was not soaked.
+    pub boxes: u8,               // Boxes marked on the condition monitor.
+    pub condition: Condition,    // The condition of the character once the damage is marked.
+}
+
+// Structure to describe the condition monitors of a character to the model.
+#[derive(Debug, Serialize)]
+pub struct Condition {
+    pub physical: String,
+    pub stun: String,
+    pub overflow: u8,
+    pub wound_modifier: i16,
+    pub unconscious: bool,
+    pub dead: bool,
+}
+
+impl From<&CharacterSheet> for Condition {
+    fn from(character: &CharacterSheet) -> Self {
+        let monitors = &character.derived_attributes.monitors;
+        Condition {
+            physical: format!("{}/{}", monitors.physical_damage, monitors.physical),
+            stun: format!("{}/{}", monitors.stun_damage, monitors.stun),
+            overflow: monitors.overflow,
+            wound_modifier: -i16::from(character.wound_modifier()),
+            unconscious: character.is_unconscious(),
+            dead: character.is_dead(),
+        }
+    }
+}
+
+// Function to resist damage with body and armor, and mark what is left on the character.
+// The character is a copy: the sheet itself is updated once the app applies the damage.
+pub fn soak_damage(
+    mut character: CharacterSheet,
+    damage_type: DamageType,
+    damage_value: u8,
+    armor_penetration: i8,
+    soak: bool,
+) -> DamageResponse {
+    // Armor penetration lowers the armor, never below zero.
+    let armor = (i16::from(character.derived_attributes.armor) + i16::from(armor_penetration))
+        .clamp(0, u8::MAX.into()) as u8;
+    let damage_type = if damage_type == DamageType::Physical && damage_value < armor {
+        DamageType::Stun
+    } else {
+        damage_type
+    };
+
+    let (soak_hits, dice_results) = if soak {
+        let soak_pool = character.attributes.body.saturating_add(armor);
+        let roll = dice_roll(soak_pool, None, None, None, 0);
+        (roll.hits, roll.dice_results)
+    } else {
+        (0, Vec::new())
+    };
+    let boxes = damage_value.saturating_sub(soak_hits);
+    character.take_damage(damage_type, boxes);
+
+    DamageResponse {
+        damage_type,
+        soak_hits,
+        dice_results,
+        boxes,
+        condition: Condition::from(&character),
+    }
+}
+
 // Structure to hold the results of a dice roll.
 pub struct DiceRoll {
     pub hits: u8,
     pub glitch: bool,
     pub critical_glitch: bool,
     pub critical_success: bool,
+    pub success: bool,
     pub dice_results: Vec<u8>,
+    pub edge_spent: bool,
+}
+
+// Enum to represent the Edge actions that change a dice roll, each costs a point of Edge.
+// Push the Limit is declared before the roll, Second Chance and Close Call are only spent
+// when the roll needs them.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeAction {
+    PushTheLimit, // Adds the Edge rating to the pool, sixes explode and the limit is ignored.
+    SecondChance, // Rerolls the dice that are not hits when the test fails.
+    CloseCall,    // Negates a glitch, or turns a critical glitch into a plain one.
 }
 
 // Function to execute the dice roll logic.
@@ -87,51 +554,62 @@ pub fn dice_roll(
     limit: Option<u8>,
     threshold: Option<u8>,
     edge_action: Option<EdgeAction>,
+    edge_rating: u8,
 ) -> DiceRoll {
     let mut rng = rand::rng(); // Random number generator.
-    let mut dice_results = Vec::new(); // Store results of each die roll.
-    let mut hits = 0; // Count of successful hits (dice results of 5 or 6).
-    let mut ones = 0; // Count of dice results that are 1, which might indicate a glitch.
+    let push_the_limit = edge_action == Some(EdgeAction::PushTheLimit);
+    let dice_pool = if push_the_limit {
+        dice_pool.saturating_add(edge_rating)
+    } else {
+        dice_pool
+    };
 
-    // Roll the dice as per the dice pool count.
+    // The Rule of Six only applies when Edge pushes the limit.
+    let mut dice_results = Vec::new();
     for _ in 0..dice_pool {
-        let mut die_result = roll_die(&mut rng); // Roll a single die.
+        let mut die_result = roll_die(&mut rng);
         dice_results.push(die_result);
-
-        // Implement "Rule of Six" where a roll of 6 allows re-rolling.
-        while die_result == 6 {
-            hits += 1; // Count hits from the dice.
+        while push_the_limit && die_result == 6 {
             die_result = roll_die(&mut rng);
             dice_results.push(die_result);
         }
+    }
 
-        if die_result >= 5 {
-            hits += 1; // A roll of 5 or 6 counts as a hit.
-        } else if die_result == 1 {
-            ones += 1; // Count ones to check for glitches.
+    // Hits are dice results of 5 or 6, capped by the limit unless Edge pushes it.
+    let count_hits = |dice_results: &[u8]| {
+        let hits = dice_results.iter().filter(|&&die| die >= 5).count() as u8;
+        match limit {
+            Some(limit) if !push_the_limit => hits.min(limit),
+            _ => hits,
         }
-    }
+    };
+    let succeeds = |hits: u8| match threshold {
+        Some(threshold) => hits >= threshold,
+        None => hits > 0,
+    };
+    let mut edge_spent = push_the_limit;
 
-    // Apply any edge actions that may alter the outcome of the roll.
-    if let Some(edge_action) = edge_action {
-        apply_edge_action(
-            &mut dice_results,
-            &mut hits,
-            &mut ones,
-            edge_action,
-            &mut rng,
-        );
+    if edge_action == Some(EdgeAction::SecondChance) && !succeeds(count_hits(&dice_results)) {
+        for die in dice_results.iter_mut().filter(|die| **die < 5) {
+            *die = roll_die(&mut rng);
+        }
+        edge_spent = true;
     }
+    let hits = count_hits(&dice_results);
 
-    // Apply the limit to the number of hits if specified.
-    if let Some(lim) = limit {
-        hits = hits.min(lim);
+    // A glitch is more than half the dice pool showing ones.
+    let ones = dice_results.iter().filter(|&&die| die == 1).count();
+    let mut glitch = ones > dice_pool as usize / 2;
+    let mut critical_glitch = glitch && hits == 0;
+    if edge_action == Some(EdgeAction::CloseCall) && glitch {
+        if critical_glitch {
+            critical_glitch = false;
+        } else {
+            glitch = false;
+        }
+        edge_spent = true;
     }
 
-    // Determine if a glitch or a critical glitch occurred.
-    let glitch = ones > dice_pool as usize / 2;
-    let critical_glitch = glitch && hits == 0;
-
     // Check for critical success if a threshold is specified.
     let critical_success = match threshold {
         Some(t) => hits >= t * 2,
@@ -143,7 +621,9 @@ pub fn dice_roll(
         glitch,
         critical_glitch,
         critical_success,
+        success: succeeds(hits),
         dice_results,
+        edge_spent,
     }
 }
 
@@ -151,61 +631,3 @@ pub fn dice_roll(
 fn roll_die(rng: &mut impl Rng) -> u8 {
     rng.random_range(1..=6)
 }
-
-// Enum to represent possible edge actions during a dice roll.
-pub enum EdgeAction {
-    RerollFailures,
-    AddExtraDice(u8),
-    PushTheLimit, // Ignore limits on the dice roll.
-                  // Additional edge actions could be added here.
-}
-
-// Function to apply an edge action during a dice roll.
-fn apply_edge_action(
-    dice_results: &mut Vec<u8>,
-    hits: &mut u8,
-    ones: &mut usize,
-    edge_action: EdgeAction,
-    rng: &mut impl Rng,
-) {
-    match edge_action {
-        EdgeAction::RerollFailures => {
-            // Reroll all dice that failed to hit (less than 5).
-            for die in dice_results.iter_mut() {
-                if *die < 5 {
-                    if *die == 1 {
-                        *ones -= 1; // Adjust the count of ones if rerolling a one.
-                    }
-                    *die = roll_die(rng); // Reroll the die.
-                    if *die >= 5 {
-                        *hits += 1; // Increment hits if the reroll is successful.
-                    } else if *die == 1 {
-                        *ones += 1; // Increment ones if the reroll results in a one.
-                    }
-                }
-            }
-        }
-        EdgeAction::AddExtraDice(extra) => {
-            // Roll additional dice specified by the edge action.
-            for _ in 0..extra {
-                let mut die_result = roll_die(rng);
-                dice_results.push(die_result);
-
-                while die_result == 6 {
-                    *hits += 1; // Count hits from the additional dice.
-                    die_result = roll_die(rng);
-                    dice_results.push(die_result);
-                }
-
-                if die_result >= 5 {
-                    *hits += 1; // Count hits from the additional dice.
-                } else if die_result == 1 {
-                    *ones += 1; // Count ones from the additional dice.
-                }
-            }
-        }
-        EdgeAction::PushTheLimit => {
-            // This edge action typically affects limit handling, which is considered in the main dice roll function.
-        }
-    }
-}
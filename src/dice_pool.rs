@@ -0,0 +1,173 @@
+// /dice_pool.rs
+use serde::{Deserialize, Serialize};
+
+use crate::{augmentation::AUGMENTED_MAXIMUM, character::CharacterSheet, skills::find_skill};
+
+// Dice lost when the character rolls a skill they do not have.
+const DEFAULTING_PENALTY: i8 = -1;
+// Dice added when the action falls under a specialization of the skill.
+const SPECIALIZATION_BONUS: i8 = 2;
+
+// A skill of "none", or no skill at all, makes an attribute only test, like Composure.
+const NO_SKILL: [&str; 2] = ["", "none"];
+
+// A named modifier of a dice pool, like cover (-2) or a called shot (-4).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PoolModifier {
+    pub name: String,
+    pub value: i8,
+}
+
+impl PoolModifier {
+    fn new(name: impl Into<String>, value: i8) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+// The dice pool of a test and where every die comes from, for the model and the tool log.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DicePool {
+    pub attribute: String,
+    pub attribute_rating: u8,
+    pub skill: String,
+    pub skill_rating: u8,
+    pub modifiers: Vec<PoolModifier>,
+    pub total: u8,
+    pub summary: String, // Like "Agility 5 + Pistols 4 + Cover -2 = 7".
+}
+
+impl DicePool {
+    // Attribute and skill, -1 when defaulting on a missing skill, +2 for a specialization on the
+    // sheet, the augmentation bonuses, the wound modifier and the situational modifiers.
+    // Without an attribute the skill rolls its linked one.
+    pub fn new(
+        character: &CharacterSheet,
+        attribute: Option<&str>,
+        skill: &str,
+        specialization: Option<&str>,
+        situational: &[PoolModifier],
+    ) -> Result<Self, String> {
+        let attribute_only = NO_SKILL.contains(&skill.trim().to_lowercase().as_str());
+        let attribute = match attribute {
+            Some(attribute) => attribute.to_lowercase(),
+            None if attribute_only => {
+                return Err("An attribute only test needs its attribute".to_string());
+            }
+            None => character
+                .linked_attribute(skill)
+                .ok_or_else(|| format!("No linked attribute is known for '{skill}', pass one"))?
+                .to_string(),
+        };
+        let attribute_rating = character.get_attribute(&attribute);
+        let skill_rating = character.get_skill_rating(skill);
+        let mut modifiers = Vec::new();
+
+        // The native language needs no roll, rolling it is never defaulting.
+        let defaulting =
+            !attribute_only && skill_rating == 0 && !character.is_native_language(skill);
+        if defaulting && find_skill(skill).is_some_and(|info| !info.defaultable) {
+            return Ok(DicePool {
+                attribute,
+                attribute_rating,
+                skill: skill.trim().to_string(),
+                skill_rating,
+                modifiers,
+                total: 0,
+                summary: format!("{} cannot be defaulted = 0", skill.trim()),
+            });
+        }
+        if defaulting {
+            modifiers.push(PoolModifier::new("Defaulting", DEFAULTING_PENALTY));
+        }
+        if skill_rating > 0
+            && let Some(specialization) = specialization
+            && character
+                .get_specialization(skill)
+                .is_some_and(|s| s.eq_ignore_ascii_case(specialization.trim()))
+        {
+            modifiers.push(PoolModifier::new(
+                format!("Specialization ({specialization})"),
+                SPECIALIZATION_BONUS,
+            ));
+        }
+        modifiers.extend(augmentation_bonuses(character, &attribute, skill));
+        let wounds = character.wound_modifier();
+        if wounds > 0 {
+            modifiers.push(PoolModifier::new(
+                "Wounds",
+                -i8::try_from(wounds).unwrap_or(i8::MAX),
+            ));
+        }
+        modifiers.extend(
+            situational
+                .iter()
+                .filter(|modifier| modifier.value != 0)
+                .cloned(),
+        );
+
+        let total = modifiers
+            .iter()
+            .map(|modifier| i16::from(modifier.value))
+            .sum::<i16>()
+            + i16::from(attribute_rating)
+            + i16::from(skill_rating);
+        let total = u8::try_from(total.max(0)).unwrap_or(u8::MAX);
+
+        let mut terms = vec![format!("{} {attribute_rating}", capitalize(&attribute))];
+        if !attribute_only {
+            terms.push(format!("{} {skill_rating}", skill.trim()));
+        }
+        terms.extend(
+            modifiers
+                .iter()
+                .map(|modifier| format!("{} {:+}", modifier.name, modifier.value)),
+        );
+        let summary = format!("{} = {total}", terms.join(" + "));
+
+        Ok(DicePool {
+            attribute,
+            attribute_rating,
+            skill: skill.trim().to_string(),
+            skill_rating,
+            modifiers,
+            total,
+            summary,
+        })
+    }
+}
+
+// Each augmentation adding to the attribute or the skill, the attribute bonuses staying within
+// the augmented maximum.
+fn augmentation_bonuses(
+    character: &CharacterSheet,
+    attribute: &str,
+    skill: &str,
+) -> Vec<PoolModifier> {
+    let mut attribute_bonus = 0;
+    let mut modifiers = Vec::new();
+    for augmentation in character.augmentations() {
+        let bonus = augmentation
+            .attribute_bonus(attribute)
+            .min(AUGMENTED_MAXIMUM - attribute_bonus);
+        attribute_bonus += bonus;
+        let value = bonus.saturating_add(augmentation.skill_bonus(skill));
+        if value > 0 {
+            modifiers.push(PoolModifier::new(
+                augmentation.name.clone(),
+                i8::try_from(value).unwrap_or(i8::MAX),
+            ));
+        }
+    }
+    modifiers
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
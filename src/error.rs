@@ -6,7 +6,7 @@ use log::error;
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 
-use crate::message::AIMessage;
+use crate::{message::AIMessage, tools::ToolError};
 
 // TODO: Add Jeremy Chone Error trick https://www.youtube.com/watch?v=j-VQCYP7wyw
 pub type Result<T> = core::result::Result<T, Error>;
@@ -17,6 +17,7 @@ pub enum Error {
     App(AppError),
     Game(GameError),
     AI(AIError),
+    Tool(ToolError),
     Serialization(serde_json::Error),
     String(String),
     Box(Box<dyn std::error::Error + Send + Sync>),
@@ -156,9 +157,6 @@ pub enum AIError {
     #[error("No message found")]
     NoMessageFound, // Error when expected message content is not found.
 
-    #[error("Failed to parse game state: {:#}", 0)]
-    GameStateParseError(String), // Error during parsing of game state.
-
     #[error("Error handling IO: {:#}", 0)]
     Io(std::io::Error),
 
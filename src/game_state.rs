@@ -1,32 +1,87 @@
 use std::path::PathBuf;
 
 // Import necessary modules from the local crate and external crates.
-use crate::character::CharacterSheet;
+use crate::{
+    archivist::CampaignMemory,
+    campaign::CampaignConfig,
+    character::CharacterSheet,
+    combat::Combat,
+    provider::{Backend, truncate_to_last_user_message},
+    usage::UsageLedger,
+};
+use async_openai::types::ChatCompletionRequestMessage;
 use serde::{Deserialize, Serialize};
 
 // Define a struct to manage the state of a game session, with serialization and deserialization.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GameState {
     pub assistant_id: String,
+    // Version of the shared assistant the save was moved to, None when it owns its assistant.
+    #[serde(default)]
+    pub assistant_version: Option<String>,
     pub thread_id: String,
     pub save_name: String,
     pub characters: Vec<CharacterSheet>,
     pub save_path: Option<PathBuf>,
     pub main_character_sheet: Option<CharacterSheet>,
     pub image_path: Option<PathBuf>,
+    #[serde(default)]
+    pub backend: Backend,
+    // Conversation kept locally by backends that don't store it remotely.
+    #[serde(default)]
+    pub history: Vec<ChatCompletionRequestMessage>,
+    #[serde(default)]
+    pub memory: CampaignMemory,
+    #[serde(default)]
+    pub usage: UsageLedger,
+    #[serde(default)]
+    pub campaign: CampaignConfig,
+    #[serde(default)]
+    pub combat: Option<Combat>,
+    // Days of in-game time since the start of the campaign.
+    #[serde(default)]
+    pub days: u32,
 }
 impl GameState {
-    pub fn new(assistant_id: String, thread_id: String, save_name: String) -> Self {
+    pub fn new(backend: Backend, save_name: String) -> Self {
         Self {
-            assistant_id,
-            thread_id,
+            assistant_id: String::new(),
+            assistant_version: None,
+            thread_id: String::new(),
             save_name,
             characters: Vec::new(),
             save_path: None,
             main_character_sheet: None,
             image_path: None,
+            backend,
+            history: Vec::new(),
+            memory: CampaignMemory::default(),
+            usage: UsageLedger::default(),
+            campaign: CampaignConfig::default(),
+            combat: None,
+            days: 0,
         }
     }
+
+    // Goes back to the game as it was before a turn. What the turn cost stays in the usage.
+    pub fn rewind(&mut self, snapshot: GameState) {
+        let usage = std::mem::take(&mut self.usage);
+        *self = snapshot;
+        self.usage = usage;
+    }
+
+    // Goes back to the game before a turn whose reply is rerolled. The player message stays in
+    // the history and the reply is dropped from it, so a cancelled reroll leaves nothing behind.
+    pub fn rewind_for_reroll(&mut self, snapshot: GameState) {
+        let mut history = std::mem::take(&mut self.history);
+        if self.backend == Backend::ChatCompletions
+            && let Err(e) = truncate_to_last_user_message(&mut history)
+        {
+            log::error!("Failed to drop the rerolled reply from the history: {e:#?}");
+        }
+        self.rewind(snapshot);
+        self.history = history;
+    }
 }
 
 // Implement the Debug trait manually to control what information is shown when debug printed.
@@ -36,6 +91,7 @@ impl std::fmt::Debug for GameState {
         f.debug_struct("GameState")
             .field("assistant_id", &self.assistant_id)
             .field("thread_id", &self.thread_id)
+            .field("backend", &self.backend)
             .field("character_sheet", &self.main_character_sheet)
             .field("image_path", &self.image_path)
             .finish() // Properly ends the debug struct helper.
@@ -0,0 +1,108 @@
+// /gear.rs
+use serde::{Deserialize, Serialize};
+
+use crate::character::DamageType;
+
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    strum_macros::Display,
+)]
+pub enum ItemCategory {
+    Weapon,
+    Armor,
+    #[default]
+    Gear,
+}
+
+// Firing modes of the fifth edition, by the rounds they fire in a single action.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display)]
+pub enum FiringMode {
+    // Single shot.
+    SS,
+    // Semi-automatic.
+    SA,
+    // Burst fire.
+    BF,
+    // Full auto.
+    FA,
+}
+
+impl FiringMode {
+    pub fn rounds(&self) -> u8 {
+        match self {
+            FiringMode::SS | FiringMode::SA => 1,
+            FiringMode::BF => 3,
+            FiringMode::FA => 6,
+        }
+    }
+
+    // Dice the defender loses against the spray of bullets.
+    pub fn defense_modifier(&self) -> i8 {
+        match self {
+            FiringMode::SS | FiringMode::SA => 0,
+            FiringMode::BF => -2,
+            FiringMode::FA => -5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Weapon {
+    // The skill attacking with the weapon, like Pistols or Blades.
+    pub skill: String,
+    pub damage_value: u8,
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub armor_penetration: i8,
+    // The limit of the attack, the physical limit without it.
+    #[serde(default)]
+    pub accuracy: Option<u8>,
+    #[serde(default)]
+    pub modes: Vec<FiringMode>,
+    #[serde(default)]
+    pub recoil_compensation: u8,
+    // Rounds left in the clip and the rounds it holds, both 0 for melee weapons.
+    #[serde(default)]
+    pub ammo: u8,
+    #[serde(default)]
+    pub clip: u8,
+}
+
+impl Weapon {
+    pub fn is_ranged(&self) -> bool {
+        self.clip > 0
+    }
+
+    // Like "8P, AP -1, SA/BF, RC 1, 12/15".
+    pub fn stats(&self) -> String {
+        let damage_type = match self.damage_type {
+            DamageType::Physical => "P",
+            DamageType::Stun => "S",
+        };
+        let mut stats = vec![format!("{}{damage_type}", self.damage_value)];
+        if self.armor_penetration != 0 {
+            stats.push(format!("AP {}", self.armor_penetration));
+        }
+        if !self.modes.is_empty() {
+            let modes: Vec<String> = self.modes.iter().map(FiringMode::to_string).collect();
+            stats.push(modes.join("/"));
+        }
+        if self.recoil_compensation > 0 {
+            stats.push(format!("RC {}", self.recoil_compensation));
+        }
+        if self.is_ranged() {
+            stats.push(format!("{}/{}", self.ammo, self.clip));
+        }
+        stats.join(", ")
+    }
+}
@@ -1,11 +1,10 @@
 use crate::{
     error::{Error, Result},
     save::get_game_data_dir,
+    settings::ModelClient,
 };
-use async_openai::{
-    Client,
-    config::OpenAIConfig,
-    types::{CreateImageRequestArgs, ImageModel, ImageResponseFormat, ImageSize, ImagesResponse},
+use async_openai::types::{
+    CreateImageRequestArgs, ImageModel, ImageResponseFormat, ImageSize, ImagesResponse,
 };
 use futures::TryFutureExt;
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol};
@@ -20,24 +19,21 @@ fn add_sharad_prepromt(prompt: &str) -> String {
 }
 
 // TODO: implement an image correction/edition method.
-pub async fn generate_and_save_image(
-    client: Client<OpenAIConfig>,
-    prompt: &str,
-) -> Result<PathBuf> {
+pub async fn generate_and_save_image(images: ModelClient, prompt: &str) -> Result<PathBuf> {
     log::debug!("generate_and_save_image: {prompt}");
     let prompt = add_sharad_prepromt(prompt);
     log::debug!("Arranged Prompt: {prompt}");
 
     let request = CreateImageRequestArgs::default()
         .prompt(prompt)
-        .model(ImageModel::DallE3)
+        .model(ImageModel::Other(images.model))
         .n(1)
         .response_format(ImageResponseFormat::Url)
         .size(ImageSize::S1024x1792)
         .build()
         .map_err(|e| Error::AI(e.into()))?;
 
-    let response: ImagesResponse = match client.images().create(request).await {
+    let response: ImagesResponse = match images.client.images().create(request).await {
         Ok(res) => {
             log::debug!("generate_and_save_image response: {res:#?}");
             res
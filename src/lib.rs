@@ -2,21 +2,35 @@
 
 pub mod ai;
 pub mod app;
+pub mod archivist;
 pub mod assistant;
 pub mod audio;
+pub mod augmentation;
+pub mod campaign;
 pub mod character;
+pub mod combat;
 pub mod context;
 pub mod dice;
+pub mod dice_pool;
 pub mod error;
 pub mod game_state;
+pub mod gear;
 pub mod imager;
+pub mod lifestyle;
 pub mod logging;
 pub mod message;
+pub mod provider;
+pub mod retry;
+pub mod safety;
 pub mod save;
 pub mod settings;
 pub mod settings_state;
+pub mod skills;
+pub mod tool_log;
+pub mod tools;
 pub mod tui;
 pub mod ui;
+pub mod usage;
 
 // Re-export commonly used items for easier access
 pub use ai::*;
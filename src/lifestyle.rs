@@ -0,0 +1,166 @@
+// /lifestyle.rs
+use serde::{Deserialize, Deserializer, Serialize};
+use strum::IntoEnumIterator;
+
+// Rent is due every 30 days of in-game time.
+pub const DAYS_PER_MONTH: u32 = 30;
+
+// The lifestyles of the core rules, cf assets/docs/lifestyles.md.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+pub enum LifestyleTier {
+    #[default]
+    Street,
+    Squatter,
+    Low,
+    Middle,
+    High,
+    Luxury,
+}
+
+impl LifestyleTier {
+    pub fn monthly_cost(&self) -> u32 {
+        match self {
+            LifestyleTier::Street => 0,
+            LifestyleTier::Squatter => 500,
+            LifestyleTier::Low => 2_000,
+            LifestyleTier::Middle => 5_000,
+            LifestyleTier::High => 10_000,
+            LifestyleTier::Luxury => 100_000,
+        }
+    }
+
+    // Dice added to the healing tests made at home: the street slows recovery down, a high
+    // lifestyle speeds it up.
+    pub fn healing_modifier(&self) -> i8 {
+        match self {
+            LifestyleTier::Street => -2,
+            LifestyleTier::Squatter => -1,
+            LifestyleTier::Low | LifestyleTier::Middle => 0,
+            LifestyleTier::High => 1,
+            LifestyleTier::Luxury => 2,
+        }
+    }
+}
+
+// An option of the lifestyle, like a special work area (+1,000¥) or a cramped place (-10%
+// of the rent, as a negative cost).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LifestyleOption {
+    pub name: String,
+    #[serde(default)]
+    pub monthly_cost: i32,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Lifestyle {
+    pub tier: LifestyleTier,
+    #[serde(default)]
+    pub options: Vec<LifestyleOption>,
+    // Months of rent paid in advance, used before any nuyen.
+    #[serde(default)]
+    pub prepaid_months: u32,
+    // Months of rent the character could not pay.
+    #[serde(default)]
+    pub months_behind: u32,
+}
+
+impl Lifestyle {
+    pub fn new(tier: LifestyleTier) -> Self {
+        Self {
+            tier,
+            ..Default::default()
+        }
+    }
+
+    // The rent of the tier and of the options, never below zero.
+    pub fn monthly_cost(&self) -> u32 {
+        let options: i64 = self
+            .options
+            .iter()
+            .map(|option| i64::from(option.monthly_cost))
+            .sum();
+        u32::try_from((i64::from(self.tier.monthly_cost()) + options).max(0)).unwrap_or(u32::MAX)
+    }
+
+    pub fn healing_modifier(&self) -> i8 {
+        self.tier.healing_modifier()
+    }
+}
+
+impl std::fmt::Display for Lifestyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tier)?;
+        if self.months_behind > 0 {
+            write!(f, " ({} behind)", months(self.months_behind))?;
+        } else if self.prepaid_months > 0 {
+            write!(f, " ({} paid)", months(self.prepaid_months))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn months(count: u32) -> String {
+    if count == 1 {
+        "1 month".to_string()
+    } else {
+        format!("{count} months")
+    }
+}
+
+// Saves from before structured lifestyles only have a name, like "Middle". A name that is no
+// tier of the core rules is taken for a street lifestyle.
+impl From<String> for Lifestyle {
+    fn from(name: String) -> Self {
+        let name = name.to_lowercase();
+        let tier = LifestyleTier::iter()
+            .find(|tier| name.contains(&tier.to_string().to_lowercase()))
+            .unwrap_or_default();
+        Lifestyle::new(tier)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedLifestyle {
+    Lifestyle(Lifestyle),
+    Name(String),
+}
+
+// Reads the lifestyle of a save, a name included.
+pub fn deserialize_lifestyle<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Lifestyle, D::Error> {
+    Ok(match SavedLifestyle::deserialize(deserializer)? {
+        SavedLifestyle::Lifestyle(lifestyle) => lifestyle,
+        SavedLifestyle::Name(name) => Lifestyle::from(name),
+    })
+}
+
+// What a character paid for their lifestyle as time went by, for the Game Master.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Upkeep {
+    pub character_name: String,
+    pub lifestyle: LifestyleTier,
+    pub monthly_cost: u32,
+    pub nuyen_paid: u32,
+    pub prepaid_months_used: u32,
+    pub prepaid_months_left: u32,
+    pub months_behind: u32,
+    pub nuyen_left: u32,
+    pub healing_modifier: i8,
+    // Set when the character is behind on the rent.
+    pub warning: Option<String>,
+}
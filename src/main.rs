@@ -17,21 +17,35 @@ use ui::{MIN_HEIGHT, MIN_WIDTH};
 
 mod ai;
 mod app;
+mod archivist;
 mod assistant;
 mod audio;
+mod augmentation;
+mod campaign;
 mod character;
+mod combat;
 mod context;
 mod dice;
+mod dice_pool;
 mod error;
 mod game_state;
+mod gear;
 mod imager;
+mod lifestyle;
 mod logging;
 mod message;
+mod provider;
+mod retry;
+mod safety;
 mod save;
 mod settings;
 mod settings_state;
+mod skills;
+mod tool_log;
+mod tools;
 mod tui;
 mod ui;
+mod usage;
 
 // Entry point for the Tokio runtime.
 #[tokio::main]
@@ -1,12 +1,17 @@
 // Import the necessary modules and structs from other parts of the application or crates.
 use crate::{
     ai::GameAI,
+    archivist::CampaignMemory,
     audio::AudioNarration,
+    campaign::CampaignConfig,
     character::{CharacterSheet, CharacterSheetUpdate},
+    combat::Combat,
     game_state::GameState,
+    tool_log::ToolCallEntry,
+    usage::Usage,
 };
 
-use async_openai::types::Voice;
+use async_openai::types::{ChatCompletionRequestMessage, Voice};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::hash_map::DefaultHasher,
@@ -100,6 +105,70 @@ impl Fluff {
         }
         rendered_fluff.to_string()
     }
+
+    // Extracts the dialogue lines already complete in a GameMessage that is still streaming.
+    // Lines are only kept up to the first one whose speaker has not arrived yet.
+    pub fn from_partial_response(partial: &str) -> Option<Fluff> {
+        let value: serde_json::Value = serde_json::from_str(&close_partial_json(partial)?).ok()?;
+        let fluff = value.get("fluff")?;
+        let speakers: Vec<Speaker> = parse_complete_items(fluff.get("speakers"));
+        let dialogue = parse_complete_items::<FluffLine>(fluff.get("dialogue"))
+            .into_iter()
+            .take_while(|line| speakers.iter().any(|s| s.index == line.speaker_index))
+            .collect();
+        Some(Fluff { speakers, dialogue })
+    }
+}
+
+// Cuts a truncated JSON document after its last complete value and closes what is still open.
+fn close_partial_json(partial: &str) -> Option<String> {
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut cut = None;
+
+    for (index, character) in partial.char_indices() {
+        if in_string {
+            match character {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match character {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+                cut = Some((index + 1, closers.len()));
+            }
+            ',' => cut = Some((index, closers.len())),
+            _ => {}
+        }
+    }
+
+    let (end, depth) = cut?;
+    let mut json = partial[..end].to_string();
+    json.extend(closers[..depth].iter().rev());
+    Some(json)
+}
+
+// Keeps the leading array items that deserialize, the last one may still be cut short.
+fn parse_complete_items<T: serde::de::DeserializeOwned>(
+    array: Option<&serde_json::Value>,
+) -> Vec<T> {
+    array
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .map_while(|item| serde_json::from_value(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 // TODO: Implement the new voices
@@ -157,13 +226,40 @@ impl std::fmt::Debug for Message {
 #[derive(Debug)]
 pub enum AIMessage {
     Game((Vec<Message>, GameAI, GameState)),
-    StartGame(String),
+    StartGame(String, CampaignConfig),
     Response(GameMessage),
+    StreamFluff(Fluff),
     Save(GameState),
     Load(PathBuf),
     AudioNarration(AudioNarration),
     RequestCharacterUpdate(CharacterSheetUpdate, String),
     AddCharacter(CharacterSheet),
+    UpdateHistory(Vec<ChatCompletionRequestMessage>),
+    // The campaign memory after the turn it sums up, None when the Archivist failed.
+    UpdateMemory(Option<u64>, Option<CampaignMemory>),
+    // None once the combat is over.
+    UpdateCombat(Option<Combat>),
+    // Days of in-game time since the start of the campaign.
+    UpdateDays(u32),
+    ThreadMessage(MessageType, String),
+    ToolCall(ToolCallEntry),
+    Usage(Usage),
+    // The attempt about to be made and the maximum.
+    Retrying(u32, u32),
+    // A character image generated by the GM.
+    Image(PathBuf),
+    // A message sent while playing the turn with this id, dropped once the turn is taken back.
+    Turn(u64, Box<AIMessage>),
+}
+
+impl AIMessage {
+    // Tags the message with the turn it was sent for, if any.
+    pub fn for_turn(self, turn_id: Option<u64>) -> Self {
+        match turn_id {
+            Some(id) => AIMessage::Turn(id, Box::new(self)),
+            None => self,
+        }
+    }
 }
 
 // Implementation of Message struct, providing a method to create new messages.
@@ -195,10 +291,22 @@ impl UserMessage {
 }
 
 // Function to create a new UserMessage with predefined instructions.
-pub fn create_user_message(language: &str, player_action: &str) -> UserMessage {
+pub fn create_user_message(
+    campaign: &CampaignConfig,
+    language: &str,
+    player_action: &str,
+) -> UserMessage {
+    // Long string for default instructions to act as a professional Game Master.
+    let mut instructions = format!(
+        "Act as the Game Master in a Shadowrun table top role-playing game. Allow the player to attempt one action at a time without providing choices. For actions involving multiple steps or failure points, require the player to choose a course of action at each step. Make sure the story keeps progressing by leading the story line. Keep the story going as a good Game Master, never let the tension fall down. Write your response in valid JSON. Use the following language in the 'fluff': {}.",
+        language
+    );
+    if let Some(reminder) = campaign.turn_instructions() {
+        instructions.push(' ');
+        instructions.push_str(&reminder);
+    }
     UserMessage::new(
-        // Long string for default instructions to act as a professional Game Master.
-        format!("Act as the Game Master in a Shadowrun table top role-playing game. Allow the player to attempt one action at a time without providing choices. For actions involving multiple steps or failure points, require the player to choose a course of action at each step. Make sure the story keeps progressing by leading the story line. Keep the story going as a good Game Master, never let the tension fall down. Write your response in valid JSON. Use the following language in the 'fluff': {}.", language).to_string(),
+        instructions,
         player_action.to_string(), // Convert the input action to a String and pass it to the new UserMessage.
     )
 }
@@ -0,0 +1,398 @@
+// /provider/assistants.rs
+use async_openai::{
+    Client,
+    config::OpenAIConfig,
+    types::{
+        AssistantEventStream, AssistantStreamEvent, CreateMessageRequestArgs, CreateRunRequestArgs,
+        CreateThreadRequestArgs, LastErrorCode, MessageContent, MessageDeltaContent, MessageRole,
+        RunObject, RunStatus, SubmitToolOutputsRunRequest, ToolsOutputs,
+    },
+};
+use futures::StreamExt;
+use tokio::time::{Duration, Instant, sleep, timeout_at};
+
+use super::{FluffStream, LlmProvider, OPENING_MESSAGE};
+use crate::{
+    ai::GameAI,
+    error::{AIError, AppError, Error, Result, ShadowrunError},
+    game_state::GameState,
+    message::{AIMessage, Message, MessageType},
+    save::ThreadRegistry,
+};
+
+// The legacy backend: one remote thread per save, run against an OpenAI Assistant.
+#[derive(Debug, Clone)]
+pub struct AssistantsProvider {
+    client: Client<OpenAIConfig>,
+}
+
+impl LlmProvider for AssistantsProvider {
+    async fn start_conversation(&self, game_state: &mut GameState) -> Result<()> {
+        let thread = self
+            .client
+            .threads()
+            .create(
+                CreateThreadRequestArgs::default()
+                    .build()
+                    .map_err(AIError::OpenAI)?,
+            )
+            .await
+            .map_err(AIError::OpenAI)?;
+
+        game_state.thread_id = thread.id.to_string();
+        if let Err(e) = ThreadRegistry::default().add([thread.id.clone()]) {
+            log::error!("Failed to register the thread: {e:#?}");
+        }
+        self.add_message_to_thread(&thread.id, OPENING_MESSAGE)
+            .await?;
+        Ok(())
+    }
+
+    async fn send_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: &str,
+    ) -> Result<String> {
+        let message_id = ai
+            .with_retry(|| self.add_message_to_thread(&game_state.thread_id, content))
+            .await?;
+        track_thread_message(ai, MessageType::User, message_id);
+        self.run_turn(ai, game_state).await
+    }
+
+    async fn reroll_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        self.delete_last_reply(&game_state.thread_id).await?;
+        self.run_turn(ai, game_state).await
+    }
+
+    // Asynchronous method to fetch all messages from a thread, ordered and formatted appropriately.
+    async fn fetch_messages(&self, game_state: &GameState) -> Result<Vec<Message>> {
+        let thread_id = &game_state.thread_id;
+        let mut all_messages = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let mut params = vec![("order", "desc"), ("limit", "100")];
+            if let Some(before_id) = &before {
+                params.push(("before", before_id));
+            }
+            let messages = self
+                .client
+                .threads()
+                .messages(thread_id)
+                .list(&params)
+                .await
+                .map_err(|e| Error::from(AIError::OpenAI(e)))?;
+
+            for message in messages.data.into_iter().rev() {
+                if let Some(MessageContent::Text(text_content)) = message.content.first() {
+                    let message_type = match message.role {
+                        MessageRole::User => MessageType::User,
+                        MessageRole::Assistant => MessageType::Game,
+                    };
+                    all_messages.push(Message::new(message_type, text_content.text.value.clone()));
+                }
+            }
+
+            if messages.has_more {
+                before = messages.first_id;
+            } else {
+                break;
+            }
+        }
+        Ok(all_messages)
+    }
+}
+
+impl AssistantsProvider {
+    pub fn new(client: Client<OpenAIConfig>) -> Self {
+        Self { client }
+    }
+
+    //
+    pub async fn cancel_run(&self, thread_id: &str, run_id: &str) -> Result<()> {
+        self.client
+            .threads()
+            .runs(thread_id)
+            .cancel(run_id)
+            .await
+            .map_err(|e| ShadowrunError::OpenAI(e.to_string()))
+            .map_err(AppError::Shadowrun)?;
+        Ok(())
+    }
+
+    // Runs the assistant on the thread as it stands and streams its reply.
+    async fn run_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        let thread_id = game_state.thread_id.clone();
+        let deadline = Instant::now() + Duration::from_secs(60 * 3);
+        let mut stream = ai.with_retry_stream(|| self.create_run(game_state)).await?;
+        let mut run_id: Option<String> = None;
+        let mut reply = FluffStream::new(ai);
+        let mut attempt = 1;
+        let mut tools_called = false;
+
+        loop {
+            let event = match timeout_at(deadline, stream.next()).await {
+                Ok(Some(event)) => event.map_err(AIError::OpenAI)?,
+                Ok(None) => return Err(AIError::NoMessageFound.into()),
+                Err(_) => {
+                    if let Some(run_id) = &run_id {
+                        self.cancel_run(&thread_id, run_id).await?;
+                    }
+                    return Err(AppError::Timeout.into());
+                }
+            };
+
+            match event {
+                AssistantStreamEvent::ThreadRunCreated(run) => run_id = Some(run.id),
+                // Only the last message of the run is the GM reply.
+                AssistantStreamEvent::ThreadMessageCreated(message) => {
+                    track_thread_message(ai, MessageType::Game, message.id);
+                    reply = FluffStream::new(ai);
+                }
+                AssistantStreamEvent::ThreadMessageDelta(message) => {
+                    for part in message.delta.content.into_iter().flatten() {
+                        if let MessageDeltaContent::Text(text) = part
+                            && let Some(value) = text.text.and_then(|text| text.value)
+                        {
+                            reply.push(&value);
+                        }
+                    }
+                }
+                AssistantStreamEvent::ThreadRunRequiresAction(run) => {
+                    tools_called = true;
+                    let tool_outputs = self.handle_required_action(ai, &run, game_state)?;
+                    stream = ai
+                        .with_retry_stream(|| {
+                            self.submit_tool_outputs(&run.thread_id, &run.id, tool_outputs.clone())
+                        })
+                        .await?;
+                }
+                AssistantStreamEvent::ThreadRunCompleted(run) => {
+                    if let Some(usage) = run.usage {
+                        ai.report_usage(usage);
+                    }
+                    return if reply.text().is_empty() {
+                        self.get_latest_message(&thread_id).await
+                    } else {
+                        Ok(reply.into_text())
+                    };
+                }
+                // The run is started again unless its tool calls already changed the game.
+                AssistantStreamEvent::ThreadRunFailed(run)
+                | AssistantStreamEvent::ThreadRunExpired(run)
+                    if !tools_called && attempt < ai.retry.max_attempts && can_retry(&run) =>
+                {
+                    log::warn!("Retrying the run after: {:#?}", run.last_error);
+                    ai.report_retry(attempt + 1, ai.retry.max_attempts);
+                    sleep(ai.retry.delay(attempt, None)).await;
+                    attempt += 1;
+                    reply = FluffStream::new(ai);
+                    stream = ai.with_retry_stream(|| self.create_run(game_state)).await?;
+                }
+                AssistantStreamEvent::ThreadRunFailed(run)
+                | AssistantStreamEvent::ThreadRunIncomplete(run)
+                | AssistantStreamEvent::ThreadRunCancelled(run)
+                | AssistantStreamEvent::ThreadRunExpired(run) => {
+                    return Err(format!("Run failed with status: {:#?}", run.status).into());
+                }
+                AssistantStreamEvent::ErrorEvent(e) => {
+                    return Err(ShadowrunError::OpenAI(e.message).into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Deletes the assistant messages that follow the last user message of the thread.
+    async fn delete_last_reply(&self, thread_id: &str) -> Result<()> {
+        let messages = self
+            .client
+            .threads()
+            .messages(thread_id)
+            .list(&[("order", "desc"), ("limit", "100")])
+            .await
+            .map_err(AIError::OpenAI)?;
+        for message in messages
+            .data
+            .iter()
+            .take_while(|message| message.role == MessageRole::Assistant)
+        {
+            self.client
+                .threads()
+                .messages(thread_id)
+                .delete(&message.id)
+                .await
+                .map_err(AIError::OpenAI)?;
+        }
+        Ok(())
+    }
+
+    // Deletes the given messages, an undone turn leaves nothing behind on the thread.
+    pub async fn delete_messages(&self, thread_id: &str, message_ids: &[String]) -> Result<()> {
+        for message_id in message_ids {
+            self.client
+                .threads()
+                .messages(thread_id)
+                .delete(message_id)
+                .await
+                .map_err(AIError::OpenAI)?;
+        }
+        Ok(())
+    }
+
+    // Cancels the latest run of the thread if it is still going.
+    pub async fn cancel_active_run(&self, thread_id: &str) -> Result<()> {
+        let runs = self
+            .client
+            .threads()
+            .runs(thread_id)
+            .list(&[("limit", "1")])
+            .await
+            .map_err(AIError::OpenAI)?;
+        if let Some(run) = runs.data.first()
+            && matches!(
+                run.status,
+                RunStatus::Queued | RunStatus::InProgress | RunStatus::RequiresAction
+            )
+        {
+            self.cancel_run(thread_id, &run.id).await?;
+        }
+        Ok(())
+    }
+
+    //
+    fn handle_required_action(
+        &self,
+        ai: &GameAI,
+        run: &RunObject,
+        game_state: &mut GameState,
+    ) -> Result<Vec<ToolsOutputs>> {
+        if let Some(required_action) = &run.required_action {
+            match required_action.r#type.as_str() {
+                "submit_tool_outputs" => {
+                    let mut tool_outputs = Vec::new();
+                    for tool_call in &required_action.submit_tool_outputs.tool_calls {
+                        let output = ai.handle_tool_call(
+                            &tool_call.function.name,
+                            &tool_call.function.arguments,
+                            game_state,
+                        )?;
+                        tool_outputs.push(ToolsOutputs {
+                            tool_call_id: Some(tool_call.id.clone()),
+                            output: Some(output),
+                        });
+                    }
+                    Ok(tool_outputs)
+                }
+                _ => Err(ShadowrunError::Game(format!(
+                    "Unknown required action type: {}",
+                    required_action.r#type
+                ))
+                .into()),
+            }
+        } else {
+            Err(ShadowrunError::Game("No required action found".to_string()).into())
+        }
+    }
+
+    // Asynchronous method to retrieve the latest message from a conversation thread.
+    async fn get_latest_message(&self, thread_id: &str) -> Result<String> {
+        let messages = self
+            .client
+            .threads()
+            .messages(thread_id)
+            .list(&[("limit", "1")])
+            .await
+            .map_err(|e| Error::from(AIError::OpenAI(e)))?;
+
+        if let Some(latest_message) = messages.data.first() {
+            if let Some(MessageContent::Text(text_content)) = latest_message.content.first() {
+                return Ok(text_content.text.value.clone());
+            }
+        }
+        Err(AIError::NoMessageFound.into())
+    }
+
+    //
+    async fn add_message_to_thread(&self, thread_id: &str, message: &str) -> Result<String> {
+        let message_request = CreateMessageRequestArgs::default()
+            .role(MessageRole::User)
+            .content(message)
+            .build()
+            .map_err(AIError::OpenAI)?;
+        let message = self
+            .client
+            .threads()
+            .messages(thread_id)
+            .create(message_request)
+            .await
+            .map_err(AIError::OpenAI)?;
+        Ok(message.id)
+    }
+
+    //
+    // The campaign settings and memory ride along as additional instructions, the shared
+    // assistant and the thread stay untouched.
+    async fn create_run(&self, game_state: &GameState) -> Result<AssistantEventStream> {
+        let mut run_request = CreateRunRequestArgs::default();
+        run_request.assistant_id(&game_state.assistant_id);
+        let additional: Vec<String> = [
+            game_state.campaign.instructions(),
+            (!game_state.memory.is_empty()).then(|| game_state.memory.to_context()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !additional.is_empty() {
+            run_request.additional_instructions(additional.join("\n\n"));
+        }
+        let run_request = run_request.build().map_err(AIError::OpenAI)?;
+        Ok(self
+            .client
+            .threads()
+            .runs(&game_state.thread_id)
+            .create_stream(run_request)
+            .await
+            .map_err(AIError::OpenAI)?)
+    }
+
+    // Asynchronous method to submit output from a tool during a run, the run keeps streaming.
+    async fn submit_tool_outputs(
+        &self,
+        thread_id: &str,
+        run_id: &str,
+        tool_outputs: Vec<ToolsOutputs>,
+    ) -> Result<AssistantEventStream> {
+        let submit_request = SubmitToolOutputsRunRequest {
+            tool_outputs,
+            stream: None,
+        };
+
+        Ok(self
+            .client
+            .threads()
+            .runs(thread_id)
+            .submit_tool_outputs_stream(run_id, submit_request)
+            .await
+            .map_err(AIError::OpenAI)?)
+    }
+}
+
+// Runs expire or fail on the server side, like requests do.
+fn can_retry(run: &RunObject) -> bool {
+    run.status == RunStatus::Expired
+        || run.last_error.as_ref().is_some_and(|error| {
+            matches!(
+                error.code,
+                LastErrorCode::ServerError | LastErrorCode::RateLimitExceeded
+            )
+        })
+}
+
+// Tells the game which thread messages belong to the turn so that it can be undone.
+fn track_thread_message(ai: &GameAI, message_type: MessageType, message_id: String) {
+    if let Err(e) = ai.send(AIMessage::ThreadMessage(message_type, message_id)) {
+        log::error!("Failed to send the thread message id: {e:#?}");
+    }
+}
@@ -0,0 +1,249 @@
+// /provider/chat.rs
+use async_openai::{
+    Client,
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionMessageToolCall, ChatCompletionMessageToolCallChunk,
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestAssistantMessageContent,
+        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+        ChatCompletionRequestToolMessage, ChatCompletionRequestUserMessage,
+        ChatCompletionRequestUserMessageContent, ChatCompletionStreamOptions, ChatCompletionTool,
+        ChatCompletionToolType, CreateChatCompletionRequestArgs, FunctionCall,
+    },
+};
+use futures::StreamExt;
+
+use super::{FluffStream, LlmProvider, OPENING_MESSAGE};
+use crate::{
+    ai::GameAI,
+    assistant::{define_schema, load_function_objects},
+    error::{AIError, Result, ShadowrunError},
+    game_state::GameState,
+    message::{Message, MessageType},
+};
+
+// Stateless Chat Completions backend: the whole conversation lives in `GameState::history`.
+#[derive(Debug, Clone)]
+pub struct ChatProvider {
+    client: Client<OpenAIConfig>,
+    model: String,
+}
+
+impl LlmProvider for ChatProvider {
+    async fn start_conversation(&self, game_state: &mut GameState) -> Result<()> {
+        game_state.history = vec![ChatCompletionRequestUserMessage::from(OPENING_MESSAGE).into()];
+        Ok(())
+    }
+
+    async fn send_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: &str,
+    ) -> Result<String> {
+        game_state
+            .history
+            .push(ChatCompletionRequestUserMessage::from(content).into());
+        self.run_turn(ai, game_state).await
+    }
+
+    async fn reroll_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        truncate_to_last_user_message(&mut game_state.history)?;
+        self.run_turn(ai, game_state).await
+    }
+
+    async fn fetch_messages(&self, game_state: &GameState) -> Result<Vec<Message>> {
+        let messages = game_state
+            .history
+            .iter()
+            .filter_map(|entry| match entry {
+                ChatCompletionRequestMessage::User(message) => match &message.content {
+                    ChatCompletionRequestUserMessageContent::Text(text) => {
+                        Some(Message::new(MessageType::User, text.clone()))
+                    }
+                    ChatCompletionRequestUserMessageContent::Array(_) => None,
+                },
+                ChatCompletionRequestMessage::Assistant(message)
+                    if message.tool_calls.is_none() =>
+                {
+                    match &message.content {
+                        Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => {
+                            Some(Message::new(MessageType::Game, text.clone()))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        Ok(messages)
+    }
+}
+
+impl ChatProvider {
+    pub fn new(client: Client<OpenAIConfig>, model: &str) -> Self {
+        Self {
+            client,
+            model: model.to_string(),
+        }
+    }
+
+    // Completes the turn ending the history, running tools until the GM answers with text.
+    async fn run_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        loop {
+            let request = CreateChatCompletionRequestArgs::default()
+                .model(&self.model)
+                .temperature(0.7)
+                .messages(self.build_messages(game_state))
+                .tools(self.build_tools()?)
+                .response_format(define_schema()?)
+                .stream_options(ChatCompletionStreamOptions {
+                    include_usage: true,
+                })
+                .build()
+                .map_err(AIError::OpenAI)?;
+
+            let mut stream = ai
+                .with_retry_stream(|| async {
+                    Ok(self
+                        .client
+                        .chat()
+                        .create_stream(request.clone())
+                        .await
+                        .map_err(AIError::OpenAI)?)
+                })
+                .await?;
+
+            let mut content = FluffStream::new(ai);
+            let mut tool_calls: Vec<ChatCompletionMessageToolCall> = Vec::new();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(AIError::OpenAI)?;
+                // Only the last chunk carries the usage, with no choice.
+                if let Some(usage) = chunk.usage {
+                    ai.report_usage(usage);
+                }
+                let Some(choice) = chunk.choices.into_iter().next() else {
+                    continue;
+                };
+                if let Some(delta) = choice.delta.content {
+                    content.push(&delta);
+                }
+                for call in choice.delta.tool_calls.into_iter().flatten() {
+                    self.merge_tool_call_chunk(&mut tool_calls, call);
+                }
+            }
+
+            if tool_calls.is_empty() {
+                if content.text().is_empty() {
+                    return Err(AIError::NoMessageFound.into());
+                }
+                let text = content.into_text();
+                game_state.history.push(
+                    ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(text.clone())
+                        .build()
+                        .map_err(AIError::OpenAI)?
+                        .into(),
+                );
+                return Ok(text);
+            }
+
+            let mut assistant_message = ChatCompletionRequestAssistantMessageArgs::default();
+            assistant_message.tool_calls(tool_calls.clone());
+            if !content.text().is_empty() {
+                assistant_message.content(content.into_text());
+            }
+            game_state
+                .history
+                .push(assistant_message.build().map_err(AIError::OpenAI)?.into());
+
+            for tool_call in tool_calls {
+                let output = ai.handle_tool_call(
+                    &tool_call.function.name,
+                    &tool_call.function.arguments,
+                    game_state,
+                )?;
+                game_state.history.push(
+                    ChatCompletionRequestToolMessage {
+                        content: output.into(),
+                        tool_call_id: tool_call.id,
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+
+    // The instructions are not stored in the save so that asset updates reach old campaigns,
+    // the campaign settings are layered on top at each turn.
+    fn build_messages(&self, game_state: &GameState) -> Vec<ChatCompletionRequestMessage> {
+        let mut messages = Vec::with_capacity(game_state.history.len() + 2);
+        messages.push(
+            ChatCompletionRequestSystemMessage::from(game_state.campaign.system_instructions())
+                .into(),
+        );
+        if !game_state.memory.is_empty() {
+            messages.push(
+                ChatCompletionRequestSystemMessage::from(game_state.memory.to_context()).into(),
+            );
+        }
+        messages.extend_from_slice(&game_state.history);
+        messages
+    }
+
+    // Tool calls arrive in pieces: the first chunk of a call carries its id and name,
+    // the following ones append to its arguments.
+    fn merge_tool_call_chunk(
+        &self,
+        tool_calls: &mut Vec<ChatCompletionMessageToolCall>,
+        chunk: ChatCompletionMessageToolCallChunk,
+    ) {
+        let index = chunk.index as usize;
+        while tool_calls.len() <= index {
+            tool_calls.push(ChatCompletionMessageToolCall {
+                id: String::new(),
+                r#type: ChatCompletionToolType::Function,
+                function: FunctionCall {
+                    name: String::new(),
+                    arguments: String::new(),
+                },
+            });
+        }
+        let tool_call = &mut tool_calls[index];
+        if let Some(id) = chunk.id {
+            tool_call.id = id;
+        }
+        if let Some(function) = chunk.function {
+            tool_call
+                .function
+                .name
+                .push_str(&function.name.unwrap_or_default());
+            tool_call
+                .function
+                .arguments
+                .push_str(&function.arguments.unwrap_or_default());
+        }
+    }
+
+    fn build_tools(&self) -> Result<Vec<ChatCompletionTool>> {
+        Ok(load_function_objects()?
+            .into_iter()
+            .map(|function| ChatCompletionTool {
+                r#type: ChatCompletionToolType::Function,
+                function,
+            })
+            .collect())
+    }
+}
+
+// Drops everything the GM answered after the last player message, tool calls included.
+pub fn truncate_to_last_user_message(
+    history: &mut Vec<ChatCompletionRequestMessage>,
+) -> Result<()> {
+    let last_user = history
+        .iter()
+        .rposition(|message| matches!(message, ChatCompletionRequestMessage::User(_)))
+        .ok_or_else(|| ShadowrunError::Game("No player turn to reroll".to_string()))?;
+    history.truncate(last_user + 1);
+    Ok(())
+}
@@ -0,0 +1,155 @@
+// /provider/mod.rs
+mod assistants;
+mod chat;
+mod replay;
+
+pub use self::assistants::*;
+pub use self::chat::*;
+pub use self::replay::*;
+
+use std::future::Future;
+
+use async_openai::{Client, config::OpenAIConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::GameAI,
+    error::Result,
+    game_state::GameState,
+    message::{AIMessage, Fluff, Message},
+};
+
+// Opening message seeded into every new conversation, hidden from the transcript on load.
+pub const OPENING_MESSAGE: &str =
+    "Start the game by assisting the player to create a character. Answer in valid json";
+
+// Which remote API a save is bound to.
+// Saves written before backends existed are Assistants threads, hence the default.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub enum Backend {
+    #[default]
+    Assistants,
+    ChatCompletions,
+}
+
+// A Game Master backend: send a turn with tools and get back the raw GM reply.
+pub trait LlmProvider {
+    // Prepares a fresh conversation for the game state and seeds the opening message.
+    fn start_conversation(
+        &self,
+        game_state: &mut GameState,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Sends one player turn, runs the requested tools through the GameAI, and returns the GM reply.
+    fn send_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: &str,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    // Discards the GM reply to the last player turn and runs that turn again.
+    fn reroll_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    // Loads the whole conversation, oldest first.
+    fn fetch_messages(
+        &self,
+        game_state: &GameState,
+    ) -> impl Future<Output = Result<Vec<Message>>> + Send;
+}
+
+#[derive(Debug, Clone)]
+pub enum Provider {
+    Assistants(AssistantsProvider),
+    Chat(ChatProvider),
+    Replay(ReplayProvider),
+}
+
+impl Provider {
+    pub fn new(backend: &Backend, client: Client<OpenAIConfig>, model: &str) -> Self {
+        match backend {
+            Backend::Assistants => Provider::Assistants(AssistantsProvider::new(client)),
+            Backend::ChatCompletions => Provider::Chat(ChatProvider::new(client, model)),
+        }
+    }
+}
+
+impl LlmProvider for Provider {
+    async fn start_conversation(&self, game_state: &mut GameState) -> Result<()> {
+        match self {
+            Provider::Assistants(provider) => provider.start_conversation(game_state).await,
+            Provider::Chat(provider) => provider.start_conversation(game_state).await,
+            Provider::Replay(provider) => provider.start_conversation(game_state).await,
+        }
+    }
+
+    async fn send_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: &str,
+    ) -> Result<String> {
+        match self {
+            Provider::Assistants(provider) => provider.send_turn(ai, game_state, content).await,
+            Provider::Chat(provider) => provider.send_turn(ai, game_state, content).await,
+            Provider::Replay(provider) => provider.send_turn(ai, game_state, content).await,
+        }
+    }
+
+    async fn reroll_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        match self {
+            Provider::Assistants(provider) => provider.reroll_turn(ai, game_state).await,
+            Provider::Chat(provider) => provider.reroll_turn(ai, game_state).await,
+            Provider::Replay(provider) => provider.reroll_turn(ai, game_state).await,
+        }
+    }
+
+    async fn fetch_messages(&self, game_state: &GameState) -> Result<Vec<Message>> {
+        match self {
+            Provider::Assistants(provider) => provider.fetch_messages(game_state).await,
+            Provider::Chat(provider) => provider.fetch_messages(game_state).await,
+            Provider::Replay(provider) => provider.fetch_messages(game_state).await,
+        }
+    }
+}
+
+// Accumulates a streamed GM reply and forwards each newly completed dialogue line to the app.
+pub struct FluffStream<'a> {
+    ai: &'a GameAI,
+    text: String,
+    lines: usize,
+}
+
+impl<'a> FluffStream<'a> {
+    pub fn new(ai: &'a GameAI) -> Self {
+        Self {
+            ai,
+            text: String::new(),
+            lines: 0,
+        }
+    }
+
+    pub fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+        if let Some(fluff) = Fluff::from_partial_response(&self.text)
+            && fluff.dialogue.len() > self.lines
+        {
+            self.lines = fluff.dialogue.len();
+            if let Err(e) = self.ai.send(AIMessage::StreamFluff(fluff)) {
+                log::error!("Failed to send the streamed fluff: {e:#?}");
+            }
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
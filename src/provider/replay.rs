@@ -0,0 +1,199 @@
+// /provider/replay.rs
+use async_openai::types::{
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestUserMessage,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use super::{Backend, LlmProvider, OPENING_MESSAGE, truncate_to_last_user_message};
+use crate::{
+    ai::GameAI,
+    error::{AIError, Result, ShadowrunError},
+    game_state::GameState,
+    message::Message,
+};
+
+// Recorded next to the save. Not a .json file so that it is never listed as a save.
+pub const SESSION_FILE: &str = "session.jsonl";
+
+// One line of a recorded session, in the order GameAI exchanged them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum SessionEntry {
+    Request {
+        content: String,
+    },
+    ToolCall {
+        name: String,
+        arguments: String,
+        output: String,
+    },
+    Response {
+        content: String,
+    },
+    Messages {
+        messages: Vec<Message>,
+    },
+}
+
+pub fn session_path(game_state: &GameState) -> Option<PathBuf> {
+    Some(game_state.save_path.as_ref()?.parent()?.join(SESSION_FILE))
+}
+
+// Append an entry to the session file of the save. Games without a save path are not recorded.
+pub fn record_entry(game_state: &GameState, entry: &SessionEntry) -> Result<()> {
+    let Some(path) = session_path(game_state) else {
+        return Ok(());
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+// The session file being replayed and the entries left in it.
+type LoadedSession = Option<(PathBuf, VecDeque<SessionEntry>)>;
+
+// Cursor over the recorded session, shared by every clone of the GameAI.
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySession {
+    entries: Arc<Mutex<LoadedSession>>,
+}
+
+impl ReplaySession {
+    // Pops entries up to and including the first one accepted by `until`.
+    fn advance(
+        &self,
+        game_state: &GameState,
+        until: impl Fn(&SessionEntry) -> bool,
+    ) -> Result<Vec<SessionEntry>> {
+        let path = session_path(game_state)
+            .ok_or_else(|| ShadowrunError::Game("Cannot replay an unsaved game".to_string()))?;
+        let mut guard = self.entries.lock().expect("Failed to lock replay session");
+        if guard.as_ref().is_none_or(|(loaded, _)| *loaded != path) {
+            let entries = fs::read_to_string(&path)?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<VecDeque<SessionEntry>, _>>()?;
+            *guard = Some((path, entries));
+        }
+        let (_, entries) = guard.as_mut().expect("Expected a loaded replay session");
+
+        let mut taken = Vec::new();
+        while let Some(entry) = entries.pop_front() {
+            let done = until(&entry);
+            taken.push(entry);
+            if done {
+                return Ok(taken);
+            }
+        }
+        Err(AIError::NoMessageFound.into())
+    }
+}
+
+// Feeds a recorded session back to the game without any network access.
+#[derive(Debug, Clone)]
+pub struct ReplayProvider {
+    session: ReplaySession,
+}
+
+impl LlmProvider for ReplayProvider {
+    async fn start_conversation(&self, game_state: &mut GameState) -> Result<()> {
+        if game_state.backend == Backend::ChatCompletions {
+            game_state.history =
+                vec![ChatCompletionRequestUserMessage::from(OPENING_MESSAGE).into()];
+        }
+        Ok(())
+    }
+
+    async fn send_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: &str,
+    ) -> Result<String> {
+        let response = self.replay_turn(ai, game_state, Some(content))?;
+        if game_state.backend == Backend::ChatCompletions {
+            game_state
+                .history
+                .push(ChatCompletionRequestUserMessage::from(content).into());
+            push_reply(game_state, &response)?;
+        }
+        Ok(response)
+    }
+
+    // A reroll was recorded as a new turn with the same request.
+    async fn reroll_turn(&self, ai: &GameAI, game_state: &mut GameState) -> Result<String> {
+        let response = self.replay_turn(ai, game_state, None)?;
+        if game_state.backend == Backend::ChatCompletions {
+            truncate_to_last_user_message(&mut game_state.history)?;
+            push_reply(game_state, &response)?;
+        }
+        Ok(response)
+    }
+
+    async fn fetch_messages(&self, game_state: &GameState) -> Result<Vec<Message>> {
+        let entries = self.session.advance(game_state, |entry| {
+            matches!(entry, SessionEntry::Messages { .. })
+        })?;
+        match entries.into_iter().last() {
+            Some(SessionEntry::Messages { messages }) => Ok(messages),
+            _ => Err(AIError::NoMessageFound.into()),
+        }
+    }
+}
+
+impl ReplayProvider {
+    pub fn new(session: ReplaySession) -> Self {
+        Self { session }
+    }
+
+    // Consumes the next recorded turn, re-running its tools, and returns the recorded reply.
+    fn replay_turn(
+        &self,
+        ai: &GameAI,
+        game_state: &mut GameState,
+        content: Option<&str>,
+    ) -> Result<String> {
+        let entries = self.session.advance(game_state, |entry| {
+            matches!(entry, SessionEntry::Response { .. })
+        })?;
+
+        let mut response = String::new();
+        for entry in entries {
+            match entry {
+                SessionEntry::Request { content: recorded }
+                    if content.is_some_and(|content| content != recorded) =>
+                {
+                    log::warn!("Replayed request differs from the recording: {recorded}");
+                }
+                // Images would need the network, the rest rebuilds the character sheets.
+                SessionEntry::ToolCall {
+                    name, arguments, ..
+                } if name != "generate_character_image" => {
+                    ai.handle_tool_call(&name, &arguments, game_state)?;
+                }
+                SessionEntry::Response { content } => response = content,
+                _ => {}
+            }
+        }
+        Ok(response)
+    }
+}
+
+fn push_reply(game_state: &mut GameState, response: &str) -> Result<()> {
+    game_state.history.push(
+        ChatCompletionRequestAssistantMessageArgs::default()
+            .content(response)
+            .build()
+            .map_err(AIError::OpenAI)?
+            .into(),
+    );
+    Ok(())
+}
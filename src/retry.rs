@@ -0,0 +1,173 @@
+// /retry.rs
+use async_openai::error::OpenAIError;
+use futures::{Stream, StreamExt, stream};
+use std::{future::Future, pin::Pin, time::Duration};
+
+use crate::{
+    error::{AIError, Error, Result},
+    settings::RetryPolicy,
+};
+
+pub type EventStream<T> = Pin<Box<dyn Stream<Item = std::result::Result<T, OpenAIError>> + Send>>;
+
+// Whether a failed request can be sent again, and how long the server asked to wait if it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retry {
+    Never,
+    After(Option<Duration>),
+}
+
+impl Retry {
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::AI(AIError::OpenAI(error)) => Self::from_openai_error(error),
+            _ => Retry::Never,
+        }
+    }
+
+    fn from_openai_error(error: &OpenAIError) -> Self {
+        match error {
+            OpenAIError::ApiError(error) => {
+                let kinds = [error.r#type.as_deref(), error.code.as_deref()];
+                if kinds.contains(&Some("insufficient_quota")) {
+                    Retry::Never
+                } else if kinds.contains(&Some("rate_limit_exceeded"))
+                    || kinds.contains(&Some("server_error"))
+                    // Server errors don't come as json, the client leaves them untyped.
+                    || kinds == [None, None]
+                {
+                    Retry::After(retry_after(&error.message))
+                } else {
+                    Retry::Never
+                }
+            }
+            OpenAIError::Reqwest(error)
+                if error.is_timeout()
+                    || error.is_connect()
+                    || error.status().is_some_and(is_transient_status) =>
+            {
+                Retry::After(None)
+            }
+            // Refused streams only keep the status line: "Invalid status code: 429 Too Many Requests".
+            OpenAIError::StreamError(message) => {
+                match message.strip_prefix("Invalid status code: ") {
+                    Some(status) => status
+                        .get(..3)
+                        .and_then(|code| code.parse().ok())
+                        .and_then(|code| reqwest::StatusCode::from_u16(code).ok())
+                        .filter(|status| is_transient_status(*status))
+                        .map_or(Retry::Never, |_| Retry::After(None)),
+                    None if message.starts_with("error sending request") => Retry::After(None),
+                    None => Retry::Never,
+                }
+            }
+            _ => Retry::Never,
+        }
+    }
+}
+
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Rate limit messages end with a wait like "Please try again in 1m3.5s." or "try again in 20ms."
+// This is the rate limit hint the policy honours: async-openai 0.28 reads the status and body
+// of a refused response and drops it, so its `retry-after` and `x-ratelimit-reset-*` headers are
+// out of reach.
+pub fn retry_after(message: &str) -> Option<Duration> {
+    let (_, wait) = message.split_once("try again in ")?;
+    let wait = wait.split(|c: char| c.is_whitespace() || c == ',').next()?;
+    let wait = wait.trim_end_matches('.');
+    let mut total = 0.0;
+    let mut rest = wait;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        total += number
+            * match &rest[..unit_len] {
+                "ms" => 0.001,
+                "s" => 1.0,
+                "m" => 60.0,
+                "h" => 3600.0,
+                _ => return None,
+            };
+        rest = &rest[unit_len..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+impl RetryPolicy {
+    // Exponential backoff with jitter, the wait asked by the server when it is longer.
+    pub fn delay(&self, retry: u32, asked: Option<Duration>) -> Duration {
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(1_u64 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay_ms);
+        let jittered = rand::random_range(exponential / 2..=exponential);
+        Duration::from_millis(jittered).max(asked.unwrap_or_default())
+    }
+}
+
+// Sends the request until it succeeds, fails for good or runs out of attempts.
+// `on_retry` gets the number of the next attempt and the maximum.
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    on_retry: impl Fn(u32, u32),
+    mut request: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        let error = match request().await {
+            Ok(response) => return Ok(response),
+            Err(error) => error,
+        };
+        let Retry::After(asked) = Retry::from_error(&error) else {
+            return Err(error);
+        };
+        if attempt >= policy.max_attempts {
+            return Err(error);
+        }
+        let delay = policy.delay(attempt, asked);
+        log::warn!("Retrying in {delay:?} after: {error:#?}");
+        attempt += 1;
+        on_retry(attempt, policy.max_attempts);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// Streams report a refused request with their first event. Waits for it so that the request
+// can be retried, then gives it back at the head of the stream.
+pub async fn with_retry_stream<T, F, Fut>(
+    policy: &RetryPolicy,
+    on_retry: impl Fn(u32, u32),
+    mut request: F,
+) -> Result<EventStream<T>>
+where
+    T: Send + 'static,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<EventStream<T>>>,
+{
+    with_retry(policy, on_retry, || {
+        let opened = request();
+        async move {
+            let mut events = opened.await?;
+            let first = events
+                .next()
+                .await
+                .unwrap_or_else(|| Err(OpenAIError::StreamError("Stream ended".to_string())))
+                .map_err(AIError::OpenAI)?;
+            Ok(Box::pin(stream::once(async { Ok(first) }).chain(events)) as EventStream<T>)
+        }
+    })
+    .await
+}
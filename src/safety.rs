@@ -0,0 +1,70 @@
+// /safety.rs
+use serde::{Deserialize, Serialize};
+
+// Added to the player turn sent again when the X-card is played on the last reply.
+pub const X_CARD_INSTRUCTIONS: &str = "The player played the X-card on your last reply: it did not happen. Rewind to before it and take the scene in a different direction, away from its content. Do not mention the X-card and do not ask the player why.";
+
+// Added to a redirect turn when nothing of this session can be taken back.
+pub const X_CARD_SCENE_INSTRUCTIONS: &str = "The player played the X-card on the current scene. Move the story away from it at once, with a cut or a change of scene. Do not mention the X-card and do not ask the player why.";
+
+// Content boundaries of a campaign. Lines are hard limits that never appear in the story, veils
+// happen off screen and fade to black.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Boundaries {
+    pub lines: Vec<String>,
+    pub veils: Vec<String>,
+}
+
+impl Boundaries {
+    // One topic per line or comma, blanks are dropped.
+    pub fn parse_topics(text: &str) -> Vec<String> {
+        text.split(['\n', ','])
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn instructions(&self) -> Option<String> {
+        let mut rules = Vec::new();
+        if !self.lines.is_empty() {
+            rules.push(format!(
+                "Lines, hard limits that must never appear in the story, not even mentioned: {}.",
+                self.lines.join("; ")
+            ));
+        }
+        if !self.veils.is_empty() {
+            rules.push(format!(
+                "Veils, topics that may happen but only off screen: fade to black and never describe them: {}.",
+                self.veils.join("; ")
+            ));
+        }
+        (!rules.is_empty()).then(|| rules.join(" "))
+    }
+
+    // The first line the text mentions, as a whole word and whatever the case.
+    pub fn crossed_line(&self, text: &str) -> Option<&str> {
+        let text = text.to_lowercase();
+        self.lines
+            .iter()
+            .find(|line| mentions(&text, &line.to_lowercase()))
+            .map(String::as_str)
+    }
+}
+
+// Plurals count: "spider" is mentioned by "spiders" but not by "spiderweb".
+fn mentions(text: &str, topic: &str) -> bool {
+    if topic.is_empty() {
+        return false;
+    }
+    let ends_word = |rest: &str| !rest.chars().next().is_some_and(char::is_alphanumeric);
+    text.match_indices(topic).any(|(start, _)| {
+        let starts_word = !text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        let rest = &text[start + topic.len()..];
+        starts_word && (ends_word(rest) || rest.strip_prefix('s').is_some_and(ends_word))
+    })
+}
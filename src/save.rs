@@ -1,9 +1,10 @@
 // /save.rs
-use crate::{assistant::delete_assistant, error::Result, game_state::GameState};
+use crate::{assistant::delete_assistant, error::Result, game_state::GameState, provider::Backend};
 
 use async_openai::{Client, config::OpenAIConfig};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeSet, HashSet},
     fs::{self, File, create_dir_all, read_dir, remove_dir_all, remove_file, write},
     path::PathBuf,
 };
@@ -90,6 +91,33 @@ impl SaveManager {
         path_vec
     }
 
+    // The assistants the saves play with. Read loosely, so that a save this version can't load
+    // still keeps its assistant from looking orphaned.
+    pub fn referenced_assistants(save_paths: &[PathBuf]) -> HashSet<String> {
+        Self::referenced_ids(save_paths, "assistant_id")
+    }
+
+    // The threads the Assistants saves play in, read as loosely.
+    pub fn referenced_threads(save_paths: &[PathBuf]) -> HashSet<String> {
+        Self::referenced_ids(save_paths, "thread_id")
+    }
+
+    fn referenced_ids(save_paths: &[PathBuf], key: &str) -> HashSet<String> {
+        save_paths
+            .iter()
+            .filter_map(|save_path| {
+                let file = File::open(save_path)
+                    .map_err(|e| log::error!("Failed to open {}: {e:#?}", save_path.display()))
+                    .ok()?;
+                let save: serde_json::Value = serde_json::from_reader(file)
+                    .map_err(|e| log::error!("Failed to read {}: {e:#?}", save_path.display()))
+                    .ok()?;
+                save[key].as_str().map(str::to_string)
+            })
+            .filter(|id| !id.is_empty())
+            .collect()
+    }
+
     pub fn load_from_file(&self, save_path: &PathBuf) -> Result<GameState> {
         let file = File::open(save_path).map_err(|e| {
             log::error!("Failed to open file: {e:#?}");
@@ -123,10 +151,26 @@ impl SaveManager {
     }
 
     pub fn delete_save(mut self, save_path: &PathBuf, api_key: &str) -> Result<()> {
-        if let Ok(game) = self.load_from_file(save_path) {
+        // Every Assistants save has a thread, only those from before shared assistants own an
+        // assistant to clean up as well.
+        if let Some(game) = self
+            .load_from_file(save_path)
+            .ok()
+            .filter(|game| game.backend == Backend::Assistants)
+        {
             let client = Client::with_config(OpenAIConfig::new().with_api_key(api_key));
             tokio::spawn(async move {
-                delete_assistant(&client, &game.assistant_id).await;
+                if game.assistant_version.is_none() {
+                    delete_assistant(&client, &game.assistant_id).await;
+                }
+                match client.threads().delete(&game.thread_id).await {
+                    Ok(_) => {
+                        if let Err(e) = ThreadRegistry::default().remove(&[game.thread_id]) {
+                            log::error!("Failed to forget the deleted thread: {e:#?}");
+                        }
+                    }
+                    Err(e) => log::error!("Failed to delete the thread: {e:#?}"),
+                }
             });
         };
         if let Some(save_dir) = save_path.parent() {
@@ -144,6 +188,63 @@ impl SaveManager {
     }
 }
 
+// The threads Sharad created for Assistants saves. The OpenAI API can't list threads, so this is
+// how those of save folders deleted by hand are found again.
+pub struct ThreadRegistry {
+    path: PathBuf,
+}
+
+impl Default for ThreadRegistry {
+    fn default() -> Self {
+        Self::at(get_game_data_dir().join("threads.json"))
+    }
+}
+
+impl ThreadRegistry {
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // A missing or unreadable registry knows no thread.
+    pub fn threads(&self) -> BTreeSet<String> {
+        let Ok(data) = fs::read_to_string(&self.path) else {
+            return BTreeSet::new();
+        };
+        serde_json::from_str(&data)
+            .map_err(|e| log::error!("Failed to read {}: {e:#?}", self.path.display()))
+            .unwrap_or_default()
+    }
+
+    pub fn add(&self, thread_ids: impl IntoIterator<Item = String>) -> Result<()> {
+        let mut threads = self.threads();
+        let count = threads.len();
+        threads.extend(thread_ids);
+        if threads.len() == count {
+            return Ok(());
+        }
+        self.write(&threads)
+    }
+
+    pub fn remove(&self, thread_ids: &[String]) -> Result<()> {
+        let mut threads = self.threads();
+        threads.retain(|thread_id| !thread_ids.contains(thread_id));
+        self.write(&threads)
+    }
+
+    // The registered threads that none of the saves refer to anymore.
+    pub fn orphans(&self, referenced: &HashSet<String>) -> Vec<String> {
+        self.threads()
+            .into_iter()
+            .filter(|thread_id| !referenced.contains(thread_id))
+            .collect()
+    }
+
+    fn write(&self, threads: &BTreeSet<String>) -> Result<()> {
+        write(&self.path, serde_json::to_string_pretty(threads)?)?;
+        Ok(())
+    }
+}
+
 fn serialize_save(current_save: &GameState) -> Result<String> {
     let serialized = serde_json::to_string_pretty(&current_save)
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
@@ -19,6 +19,120 @@ pub struct Settings {
     pub audio_output_enabled: bool,
     pub audio_input_enabled: bool,
     pub debug_mode: bool,
+    #[serde(default)]
+    pub endpoints: Endpoints,
+    #[serde(default)]
+    pub session: SessionMode,
+    #[serde(default)]
+    pub pricing: Pricing,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+// Whether AI exchanges go to the network, are also written to the save, or are read back from it.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Display)]
+pub enum SessionMode {
+    #[default]
+    Live,
+    Record,
+    Replay,
+}
+
+// Which kind of AI service a client is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Capability {
+    Narration,
+    Speech,
+    Transcription,
+    Images,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [
+        Capability::Narration,
+        Capability::Speech,
+        Capability::Transcription,
+        Capability::Images,
+    ];
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Capability::Narration => "gpt-4o-mini",
+            Capability::Speech => "tts-1",
+            Capability::Transcription => "whisper-1",
+            Capability::Images => "dall-e-3",
+        }
+    }
+}
+
+// An OpenAI-compatible server. Empty fields fall back to OpenAI and the main API key.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Endpoint {
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Endpoints {
+    pub narration: Endpoint,
+    pub speech: Endpoint,
+    pub transcription: Endpoint,
+    pub images: Endpoint,
+}
+
+// What the AI services charge, in millionths of a dollar. Defaults match the default models.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Pricing {
+    // Per million tokens.
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    // Per million characters.
+    pub tts_characters: u64,
+    // Per minute of audio.
+    pub transcription_minute: u64,
+    // Per image.
+    pub image: u64,
+}
+
+impl Default for Pricing {
+    fn default() -> Self {
+        Self {
+            prompt_tokens: 150_000,
+            completion_tokens: 600_000,
+            tts_characters: 15_000_000,
+            transcription_minute: 6_000,
+            image: 40_000,
+        }
+    }
+}
+
+// How failed AI requests are retried. Delays double from the base one up to the max one.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Including the first one.
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+// A client paired with the model it should be asked for.
+#[derive(Debug, Clone)]
+pub struct ModelClient {
+    pub client: Client<OpenAIConfig>,
+    pub model: String,
 }
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default, Display)]
@@ -49,6 +163,10 @@ impl Default for Settings {
             audio_output_enabled: false,
             audio_input_enabled: false,
             debug_mode: true,
+            endpoints: Endpoints::default(),
+            session: SessionMode::Live,
+            pricing: Pricing::default(),
+            retry: RetryPolicy::default(),
         }
     }
 }
@@ -103,6 +221,60 @@ impl Settings {
         Ok(())
     }
 
+    pub fn endpoint(&self, capability: Capability) -> &Endpoint {
+        match capability {
+            Capability::Narration => &self.endpoints.narration,
+            Capability::Speech => &self.endpoints.speech,
+            Capability::Transcription => &self.endpoints.transcription,
+            Capability::Images => &self.endpoints.images,
+        }
+    }
+
+    pub fn endpoint_mut(&mut self, capability: Capability) -> &mut Endpoint {
+        match capability {
+            Capability::Narration => &mut self.endpoints.narration,
+            Capability::Speech => &mut self.endpoints.speech,
+            Capability::Transcription => &mut self.endpoints.transcription,
+            Capability::Images => &mut self.endpoints.images,
+        }
+    }
+
+    // A capability is usable with a custom server or with any API key to send to OpenAI.
+    // Replayed narration never reaches a server.
+    pub fn is_configured(&self, capability: Capability) -> bool {
+        if capability == Capability::Narration && self.session == SessionMode::Replay {
+            return true;
+        }
+        let endpoint = self.endpoint(capability);
+        endpoint.base_url.is_some() || endpoint.api_key.is_some() || self.openai_api_key.is_some()
+    }
+
+    pub fn model_for(&self, capability: Capability) -> String {
+        match (&self.endpoint(capability).model, capability) {
+            (Some(model), _) => model.clone(),
+            (None, Capability::Narration) => self.model.clone(),
+            (None, capability) => capability.default_model().to_string(),
+        }
+    }
+
+    // Build the client for a capability. Local servers usually accept an empty key.
+    pub fn model_client(&self, capability: Capability) -> ModelClient {
+        let endpoint = self.endpoint(capability);
+        let api_key = endpoint
+            .api_key
+            .clone()
+            .or_else(|| self.openai_api_key.clone())
+            .unwrap_or_default();
+        let mut config = OpenAIConfig::new().with_api_key(api_key);
+        if let Some(base_url) = &endpoint.base_url {
+            config = config.with_api_base(base_url);
+        }
+        ModelClient {
+            client: Client::with_config(config),
+            model: self.model_for(capability),
+        }
+    }
+
     // Asynchronously validate an API key with OpenAI's services.
     pub async fn validate_ai_client(api_key: &str) -> Option<Client<OpenAIConfig>> {
         let client = Client::with_config(OpenAIConfig::new().with_api_key(api_key)); // Configure the OpenAI client with the API key.
@@ -1,6 +1,6 @@
 // settings_state.rs
 
-use crate::settings::{Language, Settings};
+use crate::settings::{Language, SessionMode, Settings};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -32,6 +32,15 @@ impl SettingsState {
                 if settings.audio_output_enabled { 0 } else { 1 },
                 if settings.audio_input_enabled { 0 } else { 1 },
                 if settings.debug_mode { 1 } else { 0 },
+                match settings.session {
+                    SessionMode::Live => 0,
+                    SessionMode::Record => 1,
+                    SessionMode::Replay => 2,
+                },
+                0, // Narration endpoint (opens the endpoint editor)
+                0, // Speech endpoint
+                0, // Transcription endpoint
+                0, // Images endpoint
             ],
         }
     }
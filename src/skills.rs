@@ -0,0 +1,159 @@
+// /skills.rs
+use serde::{Deserialize, Serialize};
+
+// An active skill of the fifth edition core rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillInfo {
+    pub name: &'static str,
+    // The attribute rolled with the skill.
+    pub attribute: &'static str,
+    pub group: Option<&'static str>,
+    // Whether a character without the skill can still roll the attribute, one die less.
+    pub defaultable: bool,
+}
+
+const fn skill(
+    name: &'static str,
+    attribute: &'static str,
+    group: Option<&'static str>,
+    defaultable: bool,
+) -> SkillInfo {
+    SkillInfo {
+        name,
+        attribute,
+        group,
+        defaultable,
+    }
+}
+
+pub const SKILL_CATALOG: [SkillInfo; 78] = [
+    // Combat
+    skill("Archery", "agility", None, true),
+    skill("Automatics", "agility", Some("Firearms"), true),
+    skill("Blades", "agility", Some("Close Combat"), true),
+    skill("Clubs", "agility", Some("Close Combat"), true),
+    skill("Exotic Melee Weapon", "agility", None, false),
+    skill("Exotic Ranged Weapon", "agility", None, false),
+    skill("Heavy Weapons", "agility", None, true),
+    skill("Longarms", "agility", Some("Firearms"), true),
+    skill("Pistols", "agility", Some("Firearms"), true),
+    skill("Throwing Weapons", "agility", None, true),
+    skill("Unarmed Combat", "agility", Some("Close Combat"), true),
+    // Physical
+    skill("Disguise", "intuition", Some("Stealth"), true),
+    skill("Diving", "body", None, true),
+    skill("Escape Artist", "agility", None, true),
+    skill("Flight", "agility", Some("Athletics"), false),
+    skill("Free-Fall", "body", None, true),
+    skill("Gymnastics", "agility", Some("Athletics"), true),
+    skill("Navigation", "intuition", Some("Outdoors"), true),
+    skill("Palming", "agility", Some("Stealth"), false),
+    skill("Perception", "intuition", None, true),
+    skill("Running", "strength", Some("Athletics"), true),
+    skill("Sneaking", "agility", Some("Stealth"), true),
+    skill("Survival", "willpower", Some("Outdoors"), true),
+    skill("Swimming", "strength", Some("Athletics"), true),
+    skill("Tracking", "intuition", Some("Outdoors"), true),
+    // Social
+    skill("Animal Handling", "charisma", None, true),
+    skill("Con", "charisma", Some("Acting"), true),
+    skill("Etiquette", "charisma", Some("Influence"), true),
+    skill("Impersonation", "charisma", Some("Acting"), true),
+    skill("Instruction", "charisma", None, true),
+    skill("Intimidation", "charisma", None, true),
+    skill("Leadership", "charisma", Some("Influence"), true),
+    skill("Negotiation", "charisma", Some("Influence"), true),
+    skill("Performance", "charisma", Some("Acting"), true),
+    // Magical
+    skill("Alchemy", "magic", Some("Enchanting"), false),
+    skill("Arcana", "logic", None, false),
+    skill("Artificing", "magic", Some("Enchanting"), false),
+    skill("Assensing", "intuition", None, false),
+    skill("Astral Combat", "willpower", None, false),
+    skill("Banishing", "magic", Some("Conjuring"), false),
+    skill("Binding", "magic", Some("Conjuring"), false),
+    skill("Counterspelling", "magic", Some("Sorcery"), false),
+    skill("Disenchanting", "magic", Some("Enchanting"), false),
+    skill("Ritual Spellcasting", "magic", Some("Sorcery"), false),
+    skill("Spellcasting", "magic", Some("Sorcery"), false),
+    skill("Summoning", "magic", Some("Conjuring"), false),
+    // Resonance
+    skill("Compiling", "resonance", Some("Tasking"), false),
+    skill("Decompiling", "resonance", Some("Tasking"), false),
+    skill("Registering", "resonance", Some("Tasking"), false),
+    // Technical
+    skill("Aeronautics Mechanic", "logic", Some("Engineering"), false),
+    skill("Armorer", "logic", None, true),
+    skill("Artisan", "intuition", None, false),
+    skill("Automotive Mechanic", "logic", Some("Engineering"), false),
+    skill("Biotechnology", "logic", Some("Biotech"), false),
+    skill("Chemistry", "logic", None, false),
+    skill("Computer", "logic", Some("Electronics"), true),
+    skill("Cybercombat", "logic", Some("Cracking"), true),
+    skill("Cybertechnology", "logic", Some("Biotech"), false),
+    skill("Demolitions", "logic", None, true),
+    skill("Electronic Warfare", "logic", Some("Cracking"), false),
+    skill("First Aid", "logic", Some("Biotech"), true),
+    skill("Forgery", "logic", None, true),
+    skill("Hacking", "logic", Some("Cracking"), true),
+    skill("Hardware", "logic", Some("Electronics"), false),
+    skill("Industrial Mechanic", "logic", Some("Engineering"), false),
+    skill("Locksmith", "agility", None, true),
+    skill("Medicine", "logic", Some("Biotech"), false),
+    skill("Nautical Mechanic", "logic", Some("Engineering"), false),
+    skill("Software", "logic", Some("Electronics"), false),
+    // Vehicle
+    skill("Gunnery", "agility", None, true),
+    skill("Pilot Aerospace", "reaction", None, false),
+    skill("Pilot Aircraft", "reaction", None, false),
+    skill("Pilot Exotic Vehicle", "reaction", None, false),
+    skill("Pilot Ground Craft", "reaction", None, true),
+    skill("Pilot Walker", "reaction", None, false),
+    skill("Pilot Watercraft", "reaction", None, true),
+    // Groups of the core rules the Game Master often rolls as a single skill.
+    skill("Stealth", "agility", None, true),
+    skill("Athletics", "agility", None, true),
+];
+
+// The skill of the catalog, whatever the case of its name.
+pub fn find_skill(name: &str) -> Option<&'static SkillInfo> {
+    let name = name.trim();
+    SKILL_CATALOG
+        .iter()
+        .find(|skill| skill.name.eq_ignore_ascii_case(name))
+}
+
+// The skills of a group of the catalog, empty for an unknown group.
+pub fn group_skills(group: &str) -> impl Iterator<Item = &'static SkillInfo> {
+    let group = group.trim().to_string();
+    SKILL_CATALOG.iter().filter(move |skill| {
+        skill
+            .group
+            .is_some_and(|name| name.eq_ignore_ascii_case(&group))
+    })
+}
+
+// The kind of a knowledge skill, which gives its linked attribute.
+#[derive(
+    Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Default, strum_macros::Display,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum KnowledgeCategory {
+    Academic,
+    Interests,
+    Professional,
+    #[default]
+    Street,
+}
+
+impl KnowledgeCategory {
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            KnowledgeCategory::Academic | KnowledgeCategory::Professional => "logic",
+            KnowledgeCategory::Interests | KnowledgeCategory::Street => "intuition",
+        }
+    }
+}
+
+// Languages are rolled with intuition, the native one needs no roll.
+pub const LANGUAGE_ATTRIBUTE: &str = "intuition";
@@ -0,0 +1,125 @@
+// /tool_log.rs
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use crate::{character::CharacterSheet, error::Result, game_state::GameState};
+
+// Written next to the save. Not a .json file so that it is never listed as a save.
+pub const TOOL_LOG_FILE: &str = "tool_calls.jsonl";
+
+// One tool call of the Game Master and what it did to the character sheets.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolCallEntry {
+    pub timestamp: String,
+    pub name: String,
+    pub arguments: String,
+    pub output: String,
+    #[serde(default)]
+    pub changes: Vec<SheetChange>,
+}
+
+impl ToolCallEntry {
+    pub fn new(name: &str, arguments: &str, output: &str) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            output: output.to_string(),
+            changes: Vec::new(),
+        }
+    }
+}
+
+// A single field of a character sheet before and after a tool call. Null when absent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SheetChange {
+    pub character: String,
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+pub fn tool_log_path(game_state: &GameState) -> Option<PathBuf> {
+    Some(game_state.save_path.as_ref()?.parent()?.join(TOOL_LOG_FILE))
+}
+
+// Append an entry to the tool log of the save. Games without a save path are not logged.
+pub fn append_tool_call(game_state: &GameState, entry: &ToolCallEntry) -> Result<()> {
+    let Some(path) = tool_log_path(game_state) else {
+        return Ok(());
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+// The tool calls of the save, oldest first. Unreadable lines are skipped.
+pub fn load_tool_calls(game_state: &GameState) -> Result<Vec<ToolCallEntry>> {
+    let Some(path) = tool_log_path(game_state).filter(|path| path.exists()) else {
+        return Ok(Vec::new());
+    };
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Skipped a tool log line: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
+// Field by field changes between two versions of a sheet, nested maps are walked with dotted paths.
+pub fn sheet_diff(before: Option<&CharacterSheet>, after: &CharacterSheet) -> Vec<SheetChange> {
+    let before = before
+        .and_then(|sheet| serde_json::to_value(sheet).ok())
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    let character = after.name.clone();
+    let after = serde_json::to_value(after).unwrap_or_default();
+    let mut changes = Vec::new();
+    diff_values(&character, "", &before, &after, &mut changes);
+    changes
+}
+
+fn diff_values(
+    character: &str,
+    path: &str,
+    before: &Value,
+    after: &Value,
+    changes: &mut Vec<SheetChange>,
+) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys = before
+                .keys()
+                .chain(after.keys().filter(|key| !before.contains_key(*key)));
+            for key in keys {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    character,
+                    &field,
+                    before.get(key).unwrap_or(&Value::Null),
+                    after.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (before, after) if before != after => changes.push(SheetChange {
+            character: character.to_string(),
+            field: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
@@ -0,0 +1,453 @@
+// /tools.rs
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::{collections::HashMap, sync::LazyLock};
+use thiserror::Error;
+
+use crate::{
+    assistant::load_function_objects,
+    augmentation::{Augmentation, AugmentationType},
+    character::{
+        Contact, DamageType, Item, MatrixAttributes, Quality, Race, Skill, UpdateOperation,
+    },
+    lifestyle::{LifestyleOption, LifestyleTier},
+    skills::KnowledgeCategory,
+};
+
+// Parameter schemas of the functions in assets/assistant_functions, by function name.
+static FUNCTION_SCHEMAS: LazyLock<HashMap<String, Value>> = LazyLock::new(|| {
+    load_function_objects()
+        .expect("Failed to load the assistant functions")
+        .into_iter()
+        .filter_map(|function| Some((function.name, function.parameters?)))
+        .collect()
+});
+
+// Arguments the model got wrong. They go back to the model as the tool output so it can retry.
+#[derive(Debug, Clone, Error)]
+#[error("Invalid arguments for {function}: {}", details.join("; "))]
+pub struct ToolError {
+    pub function: String,
+    pub details: Vec<String>,
+}
+
+impl ToolError {
+    fn new(function: &str, details: Vec<String>) -> Self {
+        Self {
+            function: function.to_string(),
+            details,
+        }
+    }
+
+    pub fn to_output(&self) -> String {
+        json!({
+            "error": format!("Invalid arguments for {}", self.function),
+            "details": self.details,
+            "hint": format!("Call {} again with arguments matching its schema.", self.function),
+        })
+        .to_string()
+    }
+}
+
+// Parses the arguments of a tool call after checking them against the function schema.
+pub fn parse_arguments<T: DeserializeOwned>(
+    function: &str,
+    arguments: &str,
+) -> Result<T, ToolError> {
+    let schema = FUNCTION_SCHEMAS
+        .get(function)
+        .ok_or_else(|| ToolError::new(function, vec![format!("Unknown function: {function}")]))?;
+    let value: Value = serde_json::from_str(arguments).map_err(|e| {
+        ToolError::new(function, vec![format!("Arguments are not valid json: {e}")])
+    })?;
+    let details = validate(schema, &value);
+    if !details.is_empty() {
+        return Err(ToolError::new(function, details));
+    }
+    serde_json::from_value(value).map_err(|e| ToolError::new(function, vec![e.to_string()]))
+}
+
+// Checks a value against the subset of JSON Schema used by the assistant functions.
+// Returns one message per violation, prefixed by the path of the offending value.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "$", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(expected)) => vec![expected],
+        Some(Value::Array(expected)) => expected.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|expected| has_type(value, expected)) {
+        errors.push(format!(
+            "{path}: expected {}, got {value}",
+            types.join(" or ")
+        ));
+        return;
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        let options: Vec<String> = options.iter().map(Value::to_string).collect();
+        errors.push(format!(
+            "{path}: {value} is not one of {}",
+            options.join(", ")
+        ));
+    }
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+            && number < minimum
+        {
+            errors.push(format!("{path}: {value} is below the minimum of {minimum}"));
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64)
+            && number > maximum
+        {
+            errors.push(format!("{path}: {value} is above the maximum of {maximum}"));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let required = schema.get("required").and_then(Value::as_array);
+            for property in required.into_iter().flatten().filter_map(Value::as_str) {
+                if !map.contains_key(property) {
+                    errors.push(format!("{path}: missing required property '{property}'"));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, item) in map {
+                let item_path = format!("{path}.{key}");
+                match (
+                    properties.and_then(|properties| properties.get(key)),
+                    schema.get("additionalProperties"),
+                ) {
+                    (Some(property), _) => validate_at(property, item, &item_path, errors),
+                    (None, Some(Value::Bool(false))) => {
+                        errors.push(format!("{item_path}: unexpected property"));
+                    }
+                    (None, Some(additional @ Value::Object(_))) => {
+                        validate_at(additional, item, &item_path, errors);
+                    }
+                    (None, _) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}[{index}]"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        _ => true,
+    }
+}
+
+// How a tool call changes a collection of the character sheet.
+// Each function schema lists the operations it accepts.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+pub enum Operation {
+    Add,
+    Remove,
+    Modify,
+}
+
+impl Operation {
+    pub fn update<T>(self, value: T) -> UpdateOperation<T> {
+        match self {
+            Operation::Add => UpdateOperation::Add(value),
+            Operation::Remove => UpdateOperation::Remove(value),
+            Operation::Modify => UpdateOperation::Modify(value),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateCharacterSheetArgs {
+    pub name: String,
+    pub race: Race,
+    pub gender: String,
+    pub backstory: String,
+    pub main: bool,
+    pub attributes: AttributeArgs,
+    pub skills: SkillListArgs,
+    pub qualities: Vec<Quality>,
+    pub nuyen: u32,
+    pub contacts: Vec<Contact>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AttributeArgs {
+    pub body: u8,
+    pub agility: u8,
+    pub reaction: u8,
+    pub strength: u8,
+    pub willpower: u8,
+    pub logic: u8,
+    pub intuition: u8,
+    pub charisma: u8,
+    pub edge: u8,
+    pub magic: u8,
+    pub resonance: u8,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SkillListArgs {
+    pub combat: Vec<SkillRating>,
+    pub physical: Vec<SkillRating>,
+    pub social: Vec<SkillRating>,
+    pub technical: Vec<SkillRating>,
+    pub knowledge: Vec<SkillRating>,
+    #[serde(default)]
+    pub groups: Vec<SkillRating>,
+    #[serde(default)]
+    pub languages: Vec<SkillRating>,
+    pub native_language: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SkillRating {
+    pub name: String,
+    pub rating: u8,
+    pub specialization: Option<String>,
+    // Only for knowledge skills.
+    pub category: Option<KnowledgeCategory>,
+}
+
+pub fn skill_map(ratings: &[SkillRating]) -> Skill {
+    ratings
+        .iter()
+        .map(|skill| (skill.name.clone(), skill.rating))
+        .collect()
+}
+
+// The specializations of the skills that have one, by skill name.
+pub fn specialization_map<'a>(
+    ratings: impl IntoIterator<Item = &'a SkillRating>,
+) -> HashMap<String, String> {
+    ratings
+        .into_iter()
+        .filter_map(|skill| Some((skill.name.clone(), skill.specialization.clone()?)))
+        .collect()
+}
+
+// The rest of the appearance only guides the model when it writes the prompt.
+#[derive(Deserialize, Debug)]
+pub struct GenerateCharacterImageArgs {
+    pub image_generation_prompt: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateBasicAttributesArgs {
+    pub character_name: String,
+    pub updates: BasicAttributeUpdates,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BasicAttributeUpdates {
+    pub name: Option<String>,
+    pub race: Option<Race>,
+    pub gender: Option<String>,
+    pub backstory: Option<String>,
+    pub body: Option<u8>,
+    pub agility: Option<u8>,
+    pub reaction: Option<u8>,
+    pub strength: Option<u8>,
+    pub willpower: Option<u8>,
+    pub logic: Option<u8>,
+    pub intuition: Option<u8>,
+    pub charisma: Option<u8>,
+    pub edge: Option<u8>,
+    pub magic: Option<u8>,
+    pub resonance: Option<u8>,
+    pub nuyen: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateSkillsArgs {
+    pub character_name: String,
+    pub updates: SkillUpdates,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SkillUpdates {
+    #[serde(default)]
+    pub skills: SkillCategoryUpdates,
+    #[serde(default)]
+    pub knowledge_skills: Skill,
+    // Skill name to its specialization.
+    #[serde(default)]
+    pub specializations: HashMap<String, String>,
+    #[serde(default)]
+    pub skill_groups: Skill,
+    #[serde(default)]
+    pub knowledge_categories: HashMap<String, KnowledgeCategory>,
+    #[serde(default)]
+    pub languages: Skill,
+    pub native_language: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SkillCategoryUpdates {
+    #[serde(default)]
+    pub combat: Skill,
+    #[serde(default)]
+    pub physical: Skill,
+    #[serde(default)]
+    pub social: Skill,
+    #[serde(default)]
+    pub technical: Skill,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateInventoryArgs {
+    pub character_name: String,
+    pub operation: Operation,
+    pub item: Item,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateQualitiesArgs {
+    pub character_name: String,
+    pub operation: Operation,
+    pub qualities: Vec<Quality>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateMatrixAttributesArgs {
+    pub character_name: String,
+    pub matrix_attributes: MatrixAttributes,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateContactsArgs {
+    pub character_name: String,
+    pub operation: Operation,
+    pub contacts: Vec<Contact>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateAugmentationsArgs {
+    pub character_name: String,
+    pub operation: Operation,
+    pub augmentation_type: AugmentationType,
+    pub augmentations: Vec<Augmentation>,
+}
+
+// The damage value is after the hits of the attack, the soak roll is made by the tool.
+#[derive(Deserialize, Debug)]
+pub struct ApplyDamageArgs {
+    pub character_name: String,
+    pub damage_type: DamageType,
+    pub damage_value: u8,
+    #[serde(default)]
+    pub armor_penetration: i8,
+    // Drain and other damage resisted otherwise skip the body and armor roll.
+    #[serde(default = "default_soak")]
+    pub soak: bool,
+}
+
+fn default_soak() -> bool {
+    true
+}
+
+// Without names, every character of the game joins the fight.
+#[derive(Deserialize, Debug)]
+pub struct StartCombatArgs {
+    #[serde(default)]
+    pub combatants: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum UpdateCombatArgs {
+    NextTurn,
+    Interrupt {
+        character_name: String,
+        initiative_cost: u8,
+    },
+    AddStatusEffect {
+        character_name: String,
+        status_effect: String,
+        rounds: Option<u8>,
+    },
+    RemoveStatusEffect {
+        character_name: String,
+        status_effect: String,
+    },
+    // Edge actions of the combat, each costs a point of Edge.
+    SeizeTheInitiative {
+        character_name: String,
+    },
+    Blitz {
+        character_name: String,
+    },
+    EndCombat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeadMansTriggerArgs {
+    pub character_name: String,
+}
+
+// Without points, the Edge is refreshed in full.
+#[derive(Deserialize, Debug)]
+pub struct RefreshEdgeArgs {
+    pub character_name: String,
+    pub points: Option<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HealArgs {
+    pub character_name: String,
+    pub damage_type: DamageType,
+    pub boxes: u8,
+}
+
+// Without a tier or options, the lifestyle keeps them.
+#[derive(Deserialize, Debug)]
+pub struct UpdateLifestyleArgs {
+    pub character_name: String,
+    pub tier: Option<LifestyleTier>,
+    pub options: Option<Vec<LifestyleOption>>,
+    #[serde(default)]
+    pub prepay_months: u32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PassTimeArgs {
+    pub days: u32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GearAction {
+    Equip,
+    Unequip,
+    Reload,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateGearArgs {
+    pub character_name: String,
+    pub item_name: String,
+    pub action: GearAction,
+}
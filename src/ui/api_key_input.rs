@@ -96,6 +96,12 @@ impl Component for ApiKeyInput {
             Transition::PageDown => None,
             Transition::ScrollUp => None,
             Transition::ScrollDown => None,
+            Transition::Cancel => None,
+            Transition::Reroll => None,
+            Transition::Undo => None,
+            Transition::ToolLog => None,
+            Transition::Usage => None,
+            Transition::XCard => None,
         }
     }
 
@@ -0,0 +1,180 @@
+// ui/boundaries_input.rs
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::*,
+};
+use tui_textarea::TextArea;
+
+use super::{
+    CampaignSetup, Component, ComponentEnum, center_rect, main_menu_fix::*, textarea::new_textarea,
+};
+use crate::{app::Action, campaign::CampaignConfig, context::Context, safety::Boundaries};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Lines,
+    Veils,
+    Start,
+}
+
+// New game step after the campaign settings: the topics the story must avoid.
+#[derive(Debug)]
+pub struct BoundariesInput {
+    save_name: String,
+    campaign: CampaignConfig,
+    lines: TextArea<'static>,
+    veils: TextArea<'static>,
+    focus: Focus,
+}
+
+impl Component for BoundariesInput {
+    fn on_key(&mut self, key: KeyEvent, _context: &mut Context) -> Option<Action> {
+        match (key.code, self.focus) {
+            (KeyCode::Esc, _) => {
+                return Some(Action::SwitchComponent(ComponentEnum::from(
+                    CampaignSetup::new(self.save_name.clone()),
+                )));
+            }
+            (KeyCode::Tab | KeyCode::Down, Focus::Start) | (KeyCode::BackTab, Focus::Veils) => {
+                self.focus = Focus::Lines
+            }
+            (KeyCode::Tab, Focus::Lines) | (KeyCode::BackTab | KeyCode::Up, Focus::Start) => {
+                self.focus = Focus::Veils
+            }
+            (KeyCode::Tab, Focus::Veils) | (KeyCode::BackTab, Focus::Lines) => {
+                self.focus = Focus::Start
+            }
+            (KeyCode::Enter, Focus::Start) => {
+                let mut campaign = self.campaign.clone();
+                campaign.boundaries = Boundaries {
+                    lines: Boundaries::parse_topics(&self.lines.lines().join("\n")),
+                    veils: Boundaries::parse_topics(&self.veils.lines().join("\n")),
+                };
+                return Some(Action::CreateNewGame(self.save_name.clone(), campaign));
+            }
+            (_, Focus::Lines) => {
+                self.lines.input(key);
+            }
+            (_, Focus::Veils) => {
+                self.veils.input(key);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&mut self, area: Rect, buffer: &mut Buffer, _context: &Context) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .flex(ratatui::layout::Flex::Center)
+            .constraints(
+                [
+                    Constraint::Max(1),
+                    Constraint::Length(if area.height.saturating_sub(20) > 20 {
+                        20
+                    } else {
+                        0
+                    }),
+                    Constraint::Length(if area.height.saturating_sub(7) > 7 {
+                        7
+                    } else {
+                        0
+                    }),
+                    Constraint::Max(1),
+                    Constraint::Min(12),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        render_header(buffer, chunks[0]);
+        render_art(buffer, chunks[1]);
+        render_title(buffer, chunks[2]);
+        Paragraph::new(
+            "Lines never appear in the story, veils fade to black. One topic per line, X during the game to redirect a scene.",
+        )
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .render(chunks[3], buffer);
+        self.render_inputs(buffer, chunks[4]);
+        self.render_hints(buffer, chunks[5]);
+    }
+}
+
+impl Hints for BoundariesInput {
+    fn display(&self) -> String {
+        "Main Menu -> New Game -> Campaign -> Lines and Veils".to_string()
+    }
+
+    fn key_hints(&self) -> String {
+        "Switch: Tab. Start the game: Enter on Start. Go back: Esc".to_string()
+    }
+}
+
+impl BoundariesInput {
+    pub fn new(save_name: String, campaign: CampaignConfig) -> Self {
+        Self {
+            save_name,
+            campaign,
+            lines: new_textarea("Hard limits, like: harm to children"),
+            veils: new_textarea("Off screen only, like: torture"),
+            focus: Focus::Lines,
+        }
+    }
+
+    fn render_inputs(&mut self, buffer: &mut Buffer, area: Rect) {
+        let area = center_rect(
+            area,
+            Constraint::Percentage(70),
+            Constraint::Percentage(100),
+        );
+        let [lines_area, veils_area, start_area] = Layout::vertical([
+            Constraint::Min(4),
+            Constraint::Min(4),
+            Constraint::Length(3),
+        ])
+        .areas(area);
+
+        for (textarea, title, focus, area) in [
+            (&mut self.lines, " Lines ", Focus::Lines, lines_area),
+            (&mut self.veils, " Veils ", Focus::Veils, veils_area),
+        ] {
+            let color = if self.focus == focus {
+                Color::Yellow
+            } else {
+                Color::DarkGray
+            };
+            textarea.set_block(
+                Block::default()
+                    .border_type(BorderType::Rounded)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(color))
+                    .title(title),
+            );
+            textarea.set_cursor_style(if self.focus == focus {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            });
+            Widget::render(&*textarea, area, buffer);
+        }
+
+        let start_style = if self.focus == Focus::Start {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        Paragraph::new("[ Start the game ]")
+            .style(start_style)
+            .alignment(Alignment::Center)
+            .block(Block::default().padding(Padding::top(1)))
+            .render(start_area, buffer);
+    }
+}
@@ -0,0 +1,247 @@
+// ui/campaign_setup.rs
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::*,
+};
+
+use super::{
+    BoundariesInput, Component, ComponentEnum, SaveName, draw::center_rect, main_menu_fix::*,
+};
+use crate::{
+    app::Action,
+    campaign::{CampaignConfig, Difficulty, Tone, get_instructions_dir, list_instruction_files},
+    context::Context,
+    settings::Language,
+};
+
+const TONE: usize = 0;
+const DIFFICULTY: usize = 1;
+const MODEL: usize = 2;
+const LANGUAGE: usize = 3;
+const INSTRUCTIONS: usize = 4;
+const ROWS: usize = 5;
+
+// The first option of the overrides keeps the settings.
+const MODELS: [&str; 4] = ["Settings", "gpt-4o-mini", "gpt-4o", "o1-mini"];
+const LANGUAGES: [&str; 5] = ["Settings", "English", "Français", "日本語", "Türkçe"];
+
+// New game step after the save name: how the Game Master runs this campaign.
+#[derive(Debug)]
+pub struct CampaignSetup {
+    save_name: String,
+    instruction_files: Vec<PathBuf>,
+    selected: usize,
+    options: [usize; ROWS],
+}
+
+impl Component for CampaignSetup {
+    fn on_key(&mut self, key: KeyEvent, _context: &mut Context) -> Option<Action> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = (self.selected + ROWS - 1) % ROWS;
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1) % ROWS,
+            KeyCode::Left | KeyCode::Char('h') => self.cycle(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.cycle(1),
+            KeyCode::Enter => {
+                return Some(Action::SwitchComponent(ComponentEnum::from(
+                    BoundariesInput::new(self.save_name.clone(), self.config()),
+                )));
+            }
+            KeyCode::Esc => {
+                return Some(Action::SwitchComponent(
+                    ComponentEnum::from(SaveName::new()),
+                ));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&mut self, area: Rect, buffer: &mut Buffer, _context: &Context) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .flex(ratatui::layout::Flex::Center)
+            .constraints(
+                [
+                    Constraint::Max(1),
+                    Constraint::Length(if area.height.saturating_sub(20) > 20 {
+                        20
+                    } else {
+                        0
+                    }),
+                    Constraint::Length(if area.height.saturating_sub(7) > 7 {
+                        7
+                    } else {
+                        0
+                    }),
+                    Constraint::Max(1),
+                    Constraint::Min(ROWS as u16 + 2),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        render_header(buffer, chunks[0]);
+        render_art(buffer, chunks[1]);
+        render_title(buffer, chunks[2]);
+        self.render_console(buffer, chunks[3]);
+        self.render_options(buffer, chunks[4]);
+        self.render_hints(buffer, chunks[5]);
+    }
+}
+
+impl Hints for CampaignSetup {
+    fn display(&self) -> String {
+        "Main Menu -> New Game -> Campaign".to_string()
+    }
+
+    fn key_hints(&self) -> String {
+        "Navigate: ↓↑ or jk. Change: ←→ or hl. Next: Enter. Go back: Esc".to_string()
+    }
+}
+
+impl CampaignSetup {
+    pub fn new(save_name: String) -> Self {
+        let defaults = CampaignConfig::default();
+        let mut options = [0; ROWS];
+        options[TONE] = Tone::ALL
+            .iter()
+            .position(|t| *t == defaults.tone)
+            .unwrap_or(0);
+        options[DIFFICULTY] = Difficulty::ALL
+            .iter()
+            .position(|d| *d == defaults.difficulty)
+            .unwrap_or(0);
+        Self {
+            save_name,
+            instruction_files: list_instruction_files(),
+            selected: 0,
+            options,
+        }
+    }
+
+    fn option_count(&self, row: usize) -> usize {
+        match row {
+            TONE => Tone::ALL.len(),
+            DIFFICULTY => Difficulty::ALL.len(),
+            MODEL => MODELS.len(),
+            LANGUAGE => LANGUAGES.len(),
+            // No file comes first.
+            _ => self.instruction_files.len() + 1,
+        }
+    }
+
+    fn cycle(&mut self, step: isize) {
+        let count = self.option_count(self.selected) as isize;
+        let option = self.options[self.selected] as isize + step;
+        self.options[self.selected] = option.rem_euclid(count) as usize;
+    }
+
+    pub fn config(&self) -> CampaignConfig {
+        CampaignConfig {
+            tone: Tone::ALL[self.options[TONE]],
+            difficulty: Difficulty::ALL[self.options[DIFFICULTY]],
+            instructions_file: self.options[INSTRUCTIONS]
+                .checked_sub(1)
+                .map(|index| self.instruction_files[index].clone()),
+            model: (self.options[MODEL] > 0).then(|| MODELS[self.options[MODEL]].to_string()),
+            language: match self.options[LANGUAGE] {
+                1 => Some(Language::English),
+                2 => Some(Language::French),
+                3 => Some(Language::Japanese),
+                4 => Some(Language::Turkish),
+                _ => None,
+            },
+            ..CampaignConfig::default()
+        }
+    }
+
+    fn render_console(&self, buffer: &mut Buffer, area: Rect) {
+        let console_text = format!(
+            "New campaign: {}. Instruction files are read from: {}",
+            self.save_name,
+            get_instructions_dir().display()
+        );
+        Paragraph::new(console_text)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .render(area, buffer);
+    }
+
+    fn render_options(&self, buffer: &mut Buffer, area: Rect) {
+        let tones: Vec<String> = Tone::ALL.iter().map(ToString::to_string).collect();
+        let difficulties: Vec<String> = Difficulty::ALL.iter().map(ToString::to_string).collect();
+        let instructions = match self.options[INSTRUCTIONS].checked_sub(1) {
+            Some(index) => self.instruction_files[index]
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            None => "None".to_string(),
+        };
+        let rows: [(&str, Vec<String>); ROWS] = [
+            ("Tone", tones),
+            ("Difficulty", difficulties),
+            ("Model", MODELS.iter().map(ToString::to_string).collect()),
+            (
+                "Language",
+                LANGUAGES.iter().map(ToString::to_string).collect(),
+            ),
+            (
+                "Instructions",
+                vec![format!(
+                    "{instructions} ({}/{})",
+                    self.options[INSTRUCTIONS],
+                    self.instruction_files.len()
+                )],
+            ),
+        ];
+
+        let text: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .map(|(row, (name, options))| {
+                let name_style = if row == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let mut spans = vec![Span::styled(format!("{name:<14}"), name_style)];
+                // The instruction files only show the selected one.
+                let selected_option = if row == INSTRUCTIONS {
+                    0
+                } else {
+                    self.options[row]
+                };
+                spans.extend(options.iter().enumerate().map(|(option, text)| {
+                    let style = if option == selected_option {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    Span::styled(format!("[{text}] "), style)
+                }));
+                Line::from(spans)
+            })
+            .collect();
+
+        let width = text.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let centered_area = center_rect(
+            area,
+            Constraint::Length(width),
+            Constraint::Length(ROWS as u16),
+        );
+        Paragraph::new(text).render(centered_area, buffer);
+    }
+}
@@ -1,5 +1,6 @@
 // /ui/sheet/augmentations.rs
 
+use crate::augmentation::{Augmentation, Grade};
 use crate::character::CharacterSheet;
 use crate::ui::game::HighlightedSection;
 use ratatui::{
@@ -24,35 +25,8 @@ pub fn draw_augmentations(
         ])
         .split(area);
 
-    let cyberware_elements: Vec<Line> = sheet
-        .cyberware
-        .iter()
-        .map(|cw| {
-            Line::from(Span::styled(
-                cw.clone(),
-                Style::default().fg(if sheet.cyberware.is_empty() {
-                    Color::DarkGray
-                } else {
-                    Color::White
-                }),
-            ))
-        })
-        .collect();
-
-    let bioware_elements: Vec<Line> = sheet
-        .bioware
-        .iter()
-        .map(|bw| {
-            Line::from(Span::styled(
-                bw.clone(),
-                Style::default().fg(if sheet.bioware.is_empty() {
-                    Color::DarkGray
-                } else {
-                    Color::White
-                }),
-            ))
-        })
-        .collect();
+    let cyberware_elements = augmentation_lines(&sheet.cyberware);
+    let bioware_elements = augmentation_lines(&sheet.bioware);
 
     let cyberware_paragraph = Paragraph::new(cyberware_elements)
         .block(
@@ -89,3 +63,37 @@ pub fn draw_augmentations(
     cyberware_paragraph.render(chunks[0], buffer);
     bioware_paragraph.render(chunks[1], buffer);
 }
+
+// The name with the rating and grade, then the essence cost and the bonuses.
+fn augmentation_lines(augmentations: &[Augmentation]) -> Vec<Line<'_>> {
+    augmentations
+        .iter()
+        .map(|augmentation| {
+            let mut name = augmentation.name.clone();
+            if let Some(rating) = augmentation
+                .rating
+                .filter(|rating| !name.ends_with(&rating.to_string()))
+            {
+                name.push_str(&format!(" {rating}"));
+            }
+            if augmentation.grade != Grade::Standard {
+                name.push_str(&format!(" ({})", augmentation.grade));
+            }
+            let mut spans = vec![
+                Span::styled(name, Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(" {:.2}", augmentation.essence()),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ];
+            let bonuses = augmentation.bonuses();
+            if !bonuses.is_empty() {
+                spans.push(Span::styled(
+                    format!(" {bonuses}"),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
@@ -1,4 +1,6 @@
 // /ui/sheet/inventory.rs
+use std::collections::BTreeMap;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Rect},
@@ -6,7 +8,8 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Cell, Row, Table, Widget},
 };
 
-use crate::character::CharacterSheet;
+use crate::character::{CharacterSheet, Item};
+use crate::gear::ItemCategory;
 use crate::ui::game::HighlightedSection;
 
 pub fn draw_inventory(
@@ -15,14 +18,31 @@ pub fn draw_inventory(
     area: Rect,
     highlighted: &HighlightedSection,
 ) {
-    let inventory_items: Vec<Row> = sheet
-        .inventory
-        .values()
-        .map(|item| {
-            let style = Style::default().fg(Color::White);
-            Row::new(vec![
-                Cell::from(format!("{} (x{})", item.name, item.quantity)).style(style),
-            ])
+    let inventory_items: Vec<Row> = inventory_by_category(sheet)
+        .into_iter()
+        .flat_map(|(category, items)| {
+            let header = Row::new(vec![
+                Cell::from(category.to_string()).style(Style::default().fg(Color::Yellow)),
+            ]);
+            let items = items.into_iter().map(|item| {
+                let style = if item.equipped {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let mut text = format!("{} (x{})", item.name, item.quantity);
+                if let Some(weapon) = &item.weapon {
+                    text.push_str(&format!(" {}", weapon.stats()));
+                }
+                if let Some(rating) = item.armor_rating {
+                    text.push_str(&format!(" Armor {rating}"));
+                }
+                if item.equipped {
+                    text.push_str(" [E]");
+                }
+                Row::new(vec![Cell::from(text).style(style)])
+            });
+            [header].into_iter().chain(items)
         })
         .collect();
 
@@ -46,3 +66,15 @@ pub fn draw_inventory(
 
     inventory_table.render(area, buffer);
 }
+
+// Weapons, then armor, then the rest of the gear, each sorted by name.
+pub fn inventory_by_category(sheet: &CharacterSheet) -> BTreeMap<ItemCategory, Vec<&Item>> {
+    let mut categories: BTreeMap<ItemCategory, Vec<&Item>> = BTreeMap::new();
+    for item in sheet.inventory.values() {
+        categories.entry(item.category).or_default().push(item);
+    }
+    for items in categories.values_mut() {
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    categories
+}
@@ -3,6 +3,7 @@ use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Rect},
     style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, BorderType, Borders, Cell, Row, Table, Widget},
 };
 
@@ -25,10 +26,35 @@ pub fn draw_resources(
 
     let nuyen = sheet.nuyen;
     let life_style = sheet.lifestyle.to_string();
-    let rows: Vec<Row> = vec![Row::new(vec![
-        Cell::from(format!("¥{}", nuyen)),
-        Cell::from(life_style),
-    ])];
+    let monitors = &sheet.derived_attributes.monitors;
+    let mut rows: Vec<Row> = vec![
+        Row::new(vec![
+            Cell::from(format!("¥{}", nuyen)),
+            Cell::from(life_style),
+        ]),
+        monitor_row(
+            "Physical",
+            monitors.physical_damage,
+            monitors.physical,
+            Color::Red,
+        ),
+        monitor_row("Stun", monitors.stun_damage, monitors.stun, Color::Yellow),
+    ];
+    if monitors.overflow > 0 {
+        rows.push(monitor_row(
+            "Overflow",
+            monitors.overflow,
+            sheet.attributes.body,
+            Color::Magenta,
+        ));
+    }
+    let wound_modifier = sheet.wound_modifier();
+    if wound_modifier > 0 {
+        rows.push(Row::new(vec![
+            Cell::from("Wounds"),
+            Cell::from(format!("-{wound_modifier} dice")).style(Style::default().fg(Color::Red)),
+        ]));
+    }
     let widths = vec![Constraint::Max(10), Constraint::Fill(0)];
     let table = Table::new(rows, widths).header(header).block(
         Block::default()
@@ -46,3 +72,23 @@ pub fn draw_resources(
 
     table.render(area, buffer);
 }
+
+// The boxes of a condition monitor, filled by the damage, one separator every 3 boxes.
+fn monitor_row(name: &str, damage: u8, boxes: u8, color: Color) -> Row<'static> {
+    let spans: Vec<Span> = (0..boxes.max(damage))
+        .flat_map(|i| {
+            let separator = (i > 0 && i % 3 == 0).then(|| Span::raw(" "));
+            let square = if i < damage {
+                Span::styled("■", Style::default().fg(color))
+            } else {
+                Span::styled("□", Style::default().fg(Color::DarkGray))
+            };
+            separator.into_iter().chain([square])
+        })
+        .chain([Span::raw(format!(" {damage}/{boxes}"))])
+        .collect();
+    Row::new(vec![
+        Cell::from(name.to_string()),
+        Cell::from(Line::from(spans)),
+    ])
+}
@@ -11,7 +11,9 @@ use std::cmp::min;
 use crate::ui::game::HighlightedSection;
 use crate::{character::CharacterSheet, character::DerivedAttributes, ui::descriptions::*};
 
-use super::{draw_augmentations, draw_inventory, draw_qualities, draw_resources};
+use super::{
+    draw_augmentations, draw_inventory, draw_qualities, draw_resources, inventory_by_category,
+};
 
 pub fn draw_character_sheet(
     buffer: &mut Buffer,
@@ -154,6 +156,7 @@ fn draw_attributes(
                         " ".to_string()
                     }),
                     Span::raw(value.to_string()),
+                    augmented_span(sheet, attr, *value),
                 ]))
             }))
         })
@@ -195,7 +198,10 @@ fn draw_derived_attributes(
         ),
         format!("Armor:  {}", sheet.derived_attributes.armor),
         format!("Essence:  {:.2}", sheet.derived_attributes.essence.current),
-        format!("Edge Points:  {}", sheet.attributes.edge),
+        format!(
+            "Edge Points:  {}/{}",
+            sheet.derived_attributes.edge_points, sheet.attributes.edge
+        ),
         format!(
             "Monitors:  PHY:{} STU:{}",
             sheet.derived_attributes.monitors.physical, sheet.derived_attributes.monitors.stun
@@ -390,7 +396,7 @@ fn draw_other_info(
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(sheet.qualities.len() as u16 + 1),
-            Constraint::Max(4),
+            Constraint::Max(8),
             Constraint::Fill(0),
         ])
         .split(chunks[0]);
@@ -399,7 +405,9 @@ fn draw_other_info(
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Fill((sheet.bioware.len() as u16).max(sheet.cyberware.len() as u16)),
-            Constraint::Min(sheet.inventory.len() as u16 + 2),
+            Constraint::Min(
+                (sheet.inventory.len() + inventory_by_category(sheet).len()) as u16 + 2,
+            ),
         ])
         .split(chunks[1]);
 
@@ -501,6 +509,16 @@ pub fn get_attributes(sheet: &CharacterSheet) -> Vec<(&str, u8)> {
     ]
 }
 
+// The attribute with the augmentation bonuses, like the 8 of "5(8)".
+fn augmented_span(sheet: &CharacterSheet, attribute: &str, value: u8) -> Span<'static> {
+    let augmented = sheet.augmented_attribute(&attribute.to_lowercase());
+    if augmented > value {
+        Span::styled(format!("({augmented})"), Style::default().fg(Color::Cyan))
+    } else {
+        Span::raw("")
+    }
+}
+
 fn get_attribute_description(attributes: &(&str, u8)) -> &'static str {
     match attributes.0 {
         "STRENGTH" => STRENGTH,
@@ -0,0 +1,346 @@
+// ui/cleanup.rs
+
+use async_openai::{Client, config::OpenAIConfig, types::AssistantObject};
+use crossterm::event::{KeyCode, KeyEvent};
+use futures::FutureExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::*,
+};
+use tokio::task::JoinHandle;
+
+use super::{Component, ComponentEnum, MainMenu, main_menu_fix::*, widgets::StatefulList};
+use crate::{
+    app::Action,
+    assistant::{list_sharad_assistants, orphaned_assistants, shared_assistant_model},
+    context::Context,
+    error::{AIError, Result},
+    save::{SaveManager, ThreadRegistry},
+};
+
+// The result of each deletion, by id.
+type Deletions = Vec<(String, Result<()>)>;
+
+// Something Sharad created on the OpenAI account that no save refers to anymore.
+#[derive(Debug)]
+enum Orphan {
+    Assistant(Box<AssistantObject>),
+    Thread(String),
+}
+
+impl Orphan {
+    fn id(&self) -> &str {
+        match self {
+            Orphan::Assistant(assistant) => &assistant.id,
+            Orphan::Thread(thread_id) => thread_id,
+        }
+    }
+}
+
+// Maintenance screen listing the Sharad assistants and threads left on the OpenAI account by
+// crashes and save folders deleted by hand. Starts as a dry run that only reports what would be
+// deleted.
+#[derive(Debug)]
+pub struct CleanupMenu {
+    client: Client<OpenAIConfig>,
+    state: StatefulList<Orphan>,
+    dry_run: bool,
+    confirm_deletion: bool,
+    scan: Option<JoinHandle<Result<Vec<Orphan>>>>,
+    deletion: Option<JoinHandle<Deletions>>,
+    report: String,
+}
+
+impl Component for CleanupMenu {
+    fn on_key(&mut self, key: KeyEvent, _context: &mut Context) -> Option<Action> {
+        if key.code != KeyCode::Enter {
+            self.confirm_deletion = false;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('h') => {
+                return Some(Action::SwitchComponent(ComponentEnum::from(
+                    MainMenu::default(),
+                )));
+            }
+            KeyCode::Up | KeyCode::Char('k') if !self.state.items.is_empty() => {
+                self.state.previous()
+            }
+            KeyCode::Down | KeyCode::Char('j') if !self.state.items.is_empty() => self.state.next(),
+            KeyCode::Char('d') => self.dry_run = !self.dry_run,
+            KeyCode::Char('r') if self.is_idle() => self.scan(),
+            KeyCode::Enter if self.is_idle() && !self.state.items.is_empty() => {
+                if self.dry_run {
+                    self.report = format!(
+                        "Dry run: {} orphan(s) would be deleted. Press d to disable the dry run.",
+                        self.state.items.len()
+                    );
+                } else if self.confirm_deletion {
+                    self.confirm_deletion = false;
+                    self.delete_orphans();
+                } else {
+                    self.confirm_deletion = true;
+                    self.report = format!(
+                        "Press Enter again to delete {} orphan(s) for good.",
+                        self.state.items.len()
+                    );
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&mut self, area: Rect, buffer: &mut Buffer, _context: &Context) {
+        self.check_tasks();
+        let orphans_length = self.state.items.len() as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .flex(ratatui::layout::Flex::Center)
+            .constraints(
+                [
+                    Constraint::Max(1),
+                    Constraint::Length(if area.height.saturating_sub(20) > 20 {
+                        20
+                    } else {
+                        0
+                    }),
+                    Constraint::Length(if area.height.saturating_sub(orphans_length + 9) > 7 {
+                        7
+                    } else {
+                        0
+                    }),
+                    Constraint::Length(2),
+                    Constraint::Min(orphans_length + 2),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        render_header(buffer, chunks[0]);
+        render_art(buffer, chunks[1]);
+        render_title(buffer, chunks[2]);
+        self.render_console(buffer, chunks[3]);
+        self.render_orphans(buffer, chunks[4]);
+        self.render_hints(buffer, chunks[5]);
+    }
+}
+
+impl Hints for CleanupMenu {
+    fn display(&self) -> String {
+        "Main Menu -> Clean Up".to_string()
+    }
+
+    fn key_hints(&self) -> String {
+        format!(
+            "Navigate: ↓↑ or jk. Dry run ({}): d. Delete the orphans: Enter. Scan again: r. Go back: Esc",
+            if self.dry_run { "on" } else { "off" }
+        )
+    }
+}
+
+impl CleanupMenu {
+    pub fn new(client: Client<OpenAIConfig>) -> Self {
+        let mut menu = Self {
+            client,
+            state: StatefulList::with_items(Vec::new()),
+            dry_run: true,
+            confirm_deletion: false,
+            scan: None,
+            deletion: None,
+            report: String::new(),
+        };
+        menu.scan();
+        menu
+    }
+
+    fn is_idle(&self) -> bool {
+        self.scan.is_none() && self.deletion.is_none()
+    }
+
+    fn scan(&mut self) {
+        // Saves are read now, so that the comparison is against the folders as they are.
+        let save_paths = SaveManager::scan_save_files();
+        let referenced = SaveManager::referenced_assistants(&save_paths);
+        let threads = SaveManager::referenced_threads(&save_paths);
+        // Threads of saves from before the registry are found from now on.
+        let registry = ThreadRegistry::default();
+        if let Err(e) = registry.add(threads.iter().cloned()) {
+            log::error!("Failed to register the threads of the saves: {e:#?}");
+        }
+        let orphaned_threads = registry.orphans(&threads);
+        let client = self.client.clone();
+        self.report = "Looking for the assistants and threads of deleted saves...".to_string();
+        self.scan = Some(tokio::spawn(async move {
+            let assistants = list_sharad_assistants(&client).await?;
+            Ok(orphaned_assistants(assistants, &referenced)
+                .into_iter()
+                .map(|assistant| Orphan::Assistant(Box::new(assistant)))
+                .chain(orphaned_threads.into_iter().map(Orphan::Thread))
+                .collect())
+        }));
+    }
+
+    fn delete_orphans(&mut self) {
+        let client = self.client.clone();
+        let orphans: Vec<(String, bool)> = self
+            .state
+            .items
+            .iter()
+            .map(|orphan| (orphan.id().to_string(), matches!(orphan, Orphan::Thread(_))))
+            .collect();
+        self.report = format!("Deleting {} orphan(s)...", orphans.len());
+        self.deletion = Some(tokio::spawn(async move {
+            let mut results = Vec::new();
+            for (id, thread) in orphans {
+                let result = if thread {
+                    client.threads().delete(&id).await.map(|_| ())
+                } else {
+                    client.assistants().delete(&id).await.map(|_| ())
+                };
+                // A deleted thread no longer needs to be found.
+                if thread
+                    && result.is_ok()
+                    && let Err(e) = ThreadRegistry::default().remove(std::slice::from_ref(&id))
+                {
+                    log::error!("Failed to forget the deleted thread {id}: {e:#?}");
+                }
+                results.push((id, result.map_err(|e| AIError::OpenAI(e).into())));
+            }
+            results
+        }));
+    }
+
+    // The tasks are polled at each frame, once they are done.
+    fn check_tasks(&mut self) {
+        if self.scan.as_ref().is_some_and(|scan| scan.is_finished())
+            && let Some(Ok(result)) = self.scan.take().and_then(|scan| scan.now_or_never())
+        {
+            match result {
+                Ok(orphans) => {
+                    self.report = if orphans.is_empty() {
+                        "No orphaned assistant or thread on the account.".to_string()
+                    } else {
+                        format!("{} orphan(s) found.", orphans.len())
+                    };
+                    self.state = StatefulList::with_items(orphans);
+                    if !self.state.items.is_empty() {
+                        self.state.next();
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to list the assistants: {e:#?}");
+                    self.report = format!("Could not list the assistants: {e}");
+                }
+            }
+        }
+        if self
+            .deletion
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+            && let Some(Ok(results)) = self.deletion.take().and_then(|task| task.now_or_never())
+        {
+            let failed: Vec<&String> = results
+                .iter()
+                .filter_map(|(id, result)| {
+                    if let Err(e) = result {
+                        log::error!("Failed to delete {id}: {e:#?}");
+                    }
+                    result.is_err().then_some(id)
+                })
+                .collect();
+            self.state = StatefulList::with_items(
+                self.state
+                    .items
+                    .drain(..)
+                    .filter(|orphan| failed.iter().any(|id| *id == orphan.id()))
+                    .collect(),
+            );
+            if !self.state.items.is_empty() {
+                self.state.next();
+            }
+            self.report = if failed.is_empty() {
+                format!("Deleted {} orphan(s).", results.len())
+            } else {
+                format!(
+                    "Deleted {} orphan(s), {} failed, see the logs.",
+                    results.len() - failed.len(),
+                    failed.len()
+                )
+            };
+        }
+    }
+
+    fn render_console(&self, buffer: &mut Buffer, area: Rect) {
+        let report_style = if self.confirm_deletion {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        let console = Paragraph::new(vec![
+            Line::from(Span::styled(self.report.clone(), report_style)),
+            // There is no endpoint to list threads, they are found from the registry.
+            Line::from(Span::styled(
+                "Threads are only found once Sharad knows them, those of saves deleted before can't be.",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center);
+        console.render(area, buffer);
+    }
+
+    fn render_orphans(&self, buffer: &mut Buffer, area: Rect) {
+        let lines: Vec<Line> = self
+            .state
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, orphan)| {
+                let text = match orphan {
+                    Orphan::Assistant(assistant) => {
+                        let kind = match shared_assistant_model(assistant) {
+                            Some(model) => format!("shared by the {model} saves"),
+                            None => format!(
+                                "save \"{}\"",
+                                assistant.name.as_deref().unwrap_or("unnamed")
+                            ),
+                        };
+                        let created =
+                            chrono::DateTime::from_timestamp(assistant.created_at.into(), 0)
+                                .map(|created| created.format("%Y-%m-%d").to_string())
+                                .unwrap_or_default();
+                        format!("{}  assistant {kind}, created {created}", assistant.id)
+                    }
+                    Orphan::Thread(thread_id) => format!("{thread_id}  thread of a deleted save"),
+                };
+                if Some(i) == self.state.state.selected() {
+                    let style = if self.confirm_deletion {
+                        Style::default().fg(Color::Red).rapid_blink()
+                    } else {
+                        Style::default().fg(Color::Yellow)
+                    };
+                    Line::from(Span::styled(text, style).add_modifier(Modifier::BOLD))
+                } else {
+                    Line::from(Span::raw(text))
+                }
+            })
+            .collect();
+
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .title(if self.dry_run {
+                " Orphaned Assistants and Threads (dry run) "
+            } else {
+                " Orphaned Assistants and Threads "
+            })
+            .border_style(Style::default().fg(Color::DarkGray));
+        Paragraph::new(lines)
+            .style(Style::default().fg(Color::White))
+            .block(block)
+            .render(area, buffer);
+    }
+}
@@ -0,0 +1,65 @@
+// ui/combat_tracker.rs
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::*,
+};
+
+use crate::combat::Combat;
+
+// Height of the tracker: a line per combatant within the borders.
+pub fn combat_tracker_height(combat: &Combat) -> u16 {
+    combat.combatants.len() as u16 + 2
+}
+
+// Side panel with the turn order, the acting combatant is highlighted while their action is
+// narrated and those out of the pass are dimmed.
+pub fn draw_combat_tracker(buffer: &mut Buffer, combat: &Combat, area: Rect) {
+    let lines: Vec<Line> = combat
+        .combatants
+        .iter()
+        .map(|combatant| {
+            let acting = combat.current.as_ref() == Some(&combatant.name);
+            let style = if acting {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if combatant.acted || combatant.initiative <= 0 {
+                Style::default().fg(Color::DarkGray)
+            } else if combatant.main {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::styled(if acting { "▶ " } else { "  " }, style),
+                Span::styled(format!("{:>3} ", combatant.initiative), style),
+                Span::styled(combatant.name.clone(), style),
+            ];
+            spans.extend(combatant.status_effects.iter().map(|effect| {
+                let text = match effect.rounds {
+                    Some(rounds) => format!(" [{} {rounds}]", effect.name),
+                    None => format!(" [{}]", effect.name),
+                };
+                Span::styled(text, Style::default().fg(Color::Magenta))
+            }));
+            Line::from(spans)
+        })
+        .collect();
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(format!(
+                    " Combat: round {}, pass {} ",
+                    combat.round, combat.pass
+                )),
+        )
+        .render(area, buffer);
+}
@@ -7,8 +7,8 @@ use crate::{
     app::Action,
     context::Context,
     ui::{
-        ImageMenu, LoadMenu, MainMenu, SaveName, SettingsMenu, api_key_input::ApiKeyInput,
-        game::InGame,
+        BoundariesInput, CampaignSetup, CleanupMenu, EndpointInput, ImageMenu, LoadMenu, MainMenu,
+        SaveName, SettingsMenu, api_key_input::ApiKeyInput, game::InGame,
     },
 };
 
@@ -32,6 +32,10 @@ pub enum ComponentEnum {
     ImageMenu,
     InGame,
     ApiKeyInput,
+    EndpointInput,
+    CleanupMenu,
+    CampaignSetup,
+    BoundariesInput,
 }
 
 impl ComponentEnum {
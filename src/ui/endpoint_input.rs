@@ -0,0 +1,190 @@
+// ui/endpoint_input.rs
+
+use crate::{
+    app::{Action, InputMode},
+    context::Context,
+    settings::{Capability, Endpoint},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    prelude::{Alignment, Buffer, Rect},
+    style::{Color, Style},
+    widgets::*,
+};
+use tui_textarea::TextArea;
+
+use super::{
+    Component, ComponentEnum, SettingsMenu, center_rect,
+    game::SectionMove,
+    textarea::{Mode, Transition, Vim, new_textarea_with_lines},
+};
+
+const FIELDS: [&str; 3] = [" Base URL ", " API Key ", " Model "];
+
+#[derive(Debug)]
+pub struct EndpointInput {
+    capability: Capability,
+    textareas: [TextArea<'static>; 3],
+    focused: usize,
+    vim: Vim,
+}
+
+impl Component for EndpointInput {
+    fn on_key(&mut self, key: KeyEvent, context: &mut Context) -> Option<Action> {
+        // match guard for validation of single lines.
+        let key = match self.vim.mode {
+            Mode::Normal => {
+                if matches!(key.code, KeyCode::Char('o' | 'O' | 'r')) {
+                    return None;
+                } else {
+                    key
+                }
+            }
+            Mode::Insert => {
+                if matches!(key.code, KeyCode::Enter) {
+                    self.vim.mode = Mode::Normal;
+                }
+                key
+            }
+            _ => key,
+        };
+        let textarea = &mut self.textareas[self.focused];
+        match self.vim.transition(key.into(), textarea) {
+            Transition::Mode(mode) if self.vim.mode != mode => {
+                textarea.set_cursor_style(mode.cursor_style());
+                self.vim.mode = mode;
+                match mode {
+                    Mode::Normal => Some(Action::SwitchInputMode(InputMode::Normal)),
+                    Mode::Insert => Some(Action::SwitchInputMode(InputMode::Editing)),
+                    Mode::Visual => Some(Action::SwitchInputMode(InputMode::Normal)),
+                    Mode::Recording => {
+                        self.vim.mode = Mode::Normal;
+                        None
+                    }
+                    Mode::Operator(_) => None,
+                    Mode::Warning(_) => None,
+                }
+            }
+            Transition::Nop | Transition::Mode(_) => None,
+            Transition::Pending(input) => {
+                self.vim.pending = input;
+                None
+            }
+            Transition::Validation => {
+                self.save_endpoint(context);
+                Some(Action::SwitchComponent(ComponentEnum::from(
+                    SettingsMenu::new(context),
+                )))
+            }
+            Transition::Exit => Some(Action::SwitchComponent(ComponentEnum::from(
+                SettingsMenu::new(context),
+            ))),
+            Transition::Detail(section_move) => {
+                self.focused = match section_move {
+                    SectionMove::Previous | SectionMove::Up => {
+                        (self.focused + FIELDS.len() - 1) % FIELDS.len()
+                    }
+                    _ => (self.focused + 1) % FIELDS.len(),
+                };
+                None
+            }
+            Transition::EndRecording => {
+                self.vim.mode = Mode::Normal;
+                None
+            }
+            Transition::ScrollTop => None,
+            Transition::ScrollBottom => None,
+            Transition::PageUp => None,
+            Transition::PageDown => None,
+            Transition::ScrollUp => None,
+            Transition::ScrollDown => None,
+            Transition::Cancel => None,
+            Transition::Reroll => None,
+            Transition::Undo => None,
+            Transition::ToolLog => None,
+            Transition::Usage => None,
+            Transition::XCard => None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buffer: &mut Buffer, context: &Context) {
+        let centered_area =
+            center_rect(area, Constraint::Percentage(70), Constraint::Percentage(60));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .flex(ratatui::layout::Flex::Center)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(centered_area);
+
+        let title = Paragraph::new(format!(" {} Endpoint ", self.capability))
+            .style(Style::default().fg(Color::Cyan))
+            .alignment(Alignment::Center);
+        title.render(chunks[0], buffer);
+
+        for (index, textarea) in self.textareas.iter_mut().enumerate() {
+            let block = if index == self.focused {
+                self.vim.mode.block()
+            } else {
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::DarkGray))
+            };
+            textarea.set_block(block.title(FIELDS[index]));
+            textarea.render(chunks[index + 1], buffer);
+        }
+
+        let info = Paragraph::new(format!(
+            " Tab to switch field, Enter to save. Empty fields use OpenAI, your Api Key and {} ",
+            context.settings.model_for(self.capability)
+        ))
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center);
+        info.render(chunks[4], buffer);
+    }
+}
+
+impl EndpointInput {
+    pub fn new(capability: Capability, endpoint: &Endpoint) -> Self {
+        let field = |value: &Option<String>, placeholder: &str| {
+            new_textarea_with_lines(value.iter().cloned().collect(), placeholder)
+        };
+        let mut textareas = [
+            field(&endpoint.base_url, "https://api.openai.com/v1"),
+            field(&endpoint.api_key, "Same as the OpenAI Api Key"),
+            field(&endpoint.model, capability.default_model()),
+        ];
+        textareas[1].set_mask_char('\u{2022}');
+        Self {
+            capability,
+            textareas,
+            focused: 0,
+            vim: Vim::new(Mode::Normal),
+        }
+    }
+
+    fn save_endpoint(&self, context: &mut Context<'_>) {
+        let [base_url, api_key, model] = self.textareas.each_ref().map(|textarea| {
+            let value = textarea.lines().concat().trim().to_string();
+            (!value.is_empty()).then_some(value)
+        });
+        *context.settings.endpoint_mut(self.capability) = Endpoint {
+            base_url,
+            api_key,
+            model,
+        };
+        if let Err(e) = context.settings.save() {
+            log::error!("Failed to save settings: {e}")
+        }
+    }
+}
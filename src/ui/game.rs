@@ -1,27 +1,39 @@
 use super::{
     Component, ComponentEnum, MainMenu, center_rect, chunk_attributes,
+    combat_tracker::{combat_tracker_height, draw_combat_tracker},
     descriptions::*,
-    draw_character_sheet, get_attributes, get_derived,
+    draw_character_sheet, get_attributes, get_derived, inventory_by_category,
     spinner::{Spinner, spinner_frame},
-    textarea::{Mode, Transition, Vim, new_textarea},
+    textarea::{Mode, Transition, Vim, new_textarea, new_textarea_with_lines},
+    tool_log::ToolLogView,
+    usage::{UsageKey, UsageView, status_line},
 };
 use crate::{
     ai::GameAI,
     app::{Action, InputMode},
-    audio::{Transcription, try_play_asset},
+    archivist::{ArchiveQueue, ArchiveRequest, CampaignMemory},
+    audio::{AudioNarration, Transcription, try_play_asset},
+    augmentation::Augmentation,
     character::{CharacterSheet, Skills},
     context::{self, Context},
     error::Error,
     game_state::GameState,
     imager::load_image_from_file,
     message::{
-        GameMessage, Message, MessageType, UserCompletionRequest, UserMessage, create_user_message,
+        AIMessage, Fluff, GameMessage, Message, MessageType, UserCompletionRequest, UserMessage,
+        create_user_message,
     },
+    safety::{X_CARD_INSTRUCTIONS, X_CARD_SCENE_INSTRUCTIONS},
+    save::SaveManager,
+    settings::{Capability, Language},
+    skills::group_skills,
     ui::textarea::Warning,
+    usage::BudgetStatus,
 };
 
 use crossterm::event::KeyEvent;
 use derive_more::Debug;
+use futures::FutureExt;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect, Size},
@@ -33,29 +45,39 @@ use ratatui_image::{
     CropOptions, Resize, StatefulImage, picker::Picker, protocol::StatefulProtocol,
 };
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::{sync::mpsc::UnboundedReceiver, task::JoinHandle};
 use tui_textarea::TextArea;
 
 pub struct InGame {
     // GamePlay state:
     pub state: GameState,
     pub content: Vec<Message>,
+    // Dialogue of the response being streamed, replaced by the full message when it arrives.
+    pub streamed: Option<Fluff>,
     pub image: Option<StatefulProtocol>,
     pub size: Size,
 
     //AI
     pub ai: GameAI,
+    // The turns of this session that can be rerolled or undone, most recent last.
+    pub turns: Vec<Turn>,
+    pub last_turn_id: u64,
+    pub archive_queue: ArchiveQueue,
 
     // User actions:
     pub textarea: TextArea<'static>,
     pub vim: Vim,
     pub receiver: Option<UnboundedReceiver<String>>,
     pub highlighted_section: HighlightedSection,
+    pub tool_log: Option<ToolLogView>,
+    pub usage_view: Option<UsageView>,
 
     // UI state:
     pub spinner: Spinner,
     pub last_spinner_update: Instant,
     pub spinner_active: bool,
+    // The attempt and the maximum when a GM request is being retried.
+    pub retrying: Option<(u32, u32)>,
     pub total_lines: usize,
     pub all_lines: Vec<(Line<'static>, Alignment)>,
     pub max_height: usize,
@@ -79,6 +101,42 @@ impl std::fmt::Debug for InGame {
     }
 }
 
+// Snapshots hold the whole chat history, so only the latest turns can be undone.
+const UNDO_DEPTH: usize = 20;
+
+// A player turn and what it takes to undo it.
+pub struct Turn {
+    // Tags what the turn sends in the background, a reroll gets a new one.
+    pub id: u64,
+    // The task waiting for the GM reply, None once the reply arrived.
+    pub handle: Option<JoinHandle<Result<(), Error>>>,
+    pub input: String,
+    pub reroll: bool,
+    // The game before the turn and the length of the transcript at that point.
+    pub state: GameState,
+    pub content_len: usize,
+    // Assistants thread messages created by the turn.
+    pub user_message_id: Option<String>,
+    pub assistant_message_ids: Vec<String>,
+    // Sent again after the X-card was played on its reply.
+    pub x_card: bool,
+}
+
+impl Turn {
+    // Stops the task waiting for the GM reply, whatever it did not send yet never arrives.
+    pub fn abort(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    // Puts the game and the transcript back to where they were before the turn.
+    pub fn restore(&self, state: &mut GameState, content: &mut Vec<Message>) {
+        state.rewind(self.state.clone());
+        content.truncate(self.content_len);
+    }
+}
+
 // TODO: Implement the 2d navigation logic
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SectionMove {
@@ -108,6 +166,24 @@ pub enum HighlightedSection {
 
 impl Component for InGame {
     fn on_key(&mut self, key: KeyEvent, context: &mut Context) -> Option<Action> {
+        if let Some(tool_log) = &mut self.tool_log {
+            if !tool_log.on_key(key) {
+                self.tool_log = None;
+            }
+            return None;
+        }
+        if let Some(usage_view) = &mut self.usage_view {
+            match usage_view.on_key(key, &mut self.state.usage) {
+                UsageKey::Close => self.usage_view = None,
+                UsageKey::BudgetChanged => {
+                    if let Err(e) = context.save_manager.save(&self.state) {
+                        log::error!("Failed to save the budget: {e:#?}");
+                    }
+                }
+                UsageKey::Nothing => {}
+            }
+            return None;
+        }
         match self.vim.transition(key.into(), &mut self.textarea) {
             Transition::Mode(mode) if self.vim.mode != mode => {
                 self.textarea
@@ -124,9 +200,10 @@ impl Component for InGame {
                         try_play_asset("end");
                         self.textarea.set_placeholder_text("Recording...");
                         log::debug!("Strated the recording");
-                        if let Ok((receiver, transcription)) =
-                            Transcription::new(None, context.ai_client.clone().unwrap())
-                        {
+                        if let Ok((receiver, transcription)) = Transcription::new(
+                            None,
+                            context.settings.model_client(Capability::Transcription),
+                        ) {
                             self.receiver = Some(receiver);
                             Some(Action::SwitchInputMode(InputMode::Recording(transcription)))
                         } else {
@@ -145,17 +222,48 @@ impl Component for InGame {
                 self.vim.pending = input;
                 None
             }
+            Transition::Validation if self.turn_in_progress() => {
+                self.vim.mode = Mode::new_warning(Warning::TurnInProgress);
+                None
+            }
+            Transition::Validation | Transition::Reroll
+                if self.budget_status(context) == BudgetStatus::Reached =>
+            {
+                self.vim.mode = Mode::new_warning(Warning::BudgetReached);
+                None
+            }
             Transition::Validation if self.textarea.lines().concat().len() > 1 => {
                 let value = self.textarea.lines().join("\n");
+                let content_len = self.content.len();
+                let snapshot = self.state.clone();
+                self.state.usage.start_turn();
                 self.spinner_active = true;
-                self.new_message(&Message::new(MessageType::User, value));
+                self.new_message(&Message::new(MessageType::User, value.clone()));
+                let id = self.new_turn_id();
                 let message = self.build_user_completion_message(context);
-                let ai = self.ai.clone();
-                tokio::spawn(async move {
-                    ai.send_message(message, ai.ai_sender.clone()).await?;
+                let ai = self.ai.for_turn(id);
+                let handle = tokio::spawn(async move {
+                    ai.send_message(message).await?;
                     Ok::<(), Error>(())
                 });
+                self.turns.push(Turn {
+                    id,
+                    handle: Some(handle),
+                    input: value,
+                    reroll: false,
+                    state: snapshot,
+                    content_len,
+                    user_message_id: None,
+                    assistant_message_ids: Vec::new(),
+                    x_card: false,
+                });
+                if self.turns.len() > UNDO_DEPTH {
+                    self.turns.remove(0);
+                }
                 self.textarea = new_textarea("Input text to play");
+                if self.budget_status(context) == BudgetStatus::Warning {
+                    self.vim.mode = Mode::new_warning(Warning::BudgetAlmostReached);
+                }
                 context
                     .save_manager
                     .save(&self.state)
@@ -166,6 +274,22 @@ impl Component for InGame {
                 self.vim.mode = Mode::Insert;
                 None
             }
+            Transition::Cancel => self.cancel_turn(context),
+            Transition::Reroll | Transition::Undo if self.turn_in_progress() => {
+                self.vim.mode = Mode::new_warning(Warning::TurnInProgress);
+                None
+            }
+            Transition::Reroll => self.reroll_turn(context),
+            Transition::XCard => Some(Action::XCard),
+            Transition::Undo => self.undo_turn(context),
+            Transition::ToolLog => {
+                self.tool_log = Some(ToolLogView::new(&self.state));
+                None
+            }
+            Transition::Usage => {
+                self.usage_view = Some(UsageView::default());
+                None
+            }
             Transition::Exit if self.highlighted_section == HighlightedSection::None => {
                 self.content.clear();
                 context
@@ -238,6 +362,20 @@ impl Component for InGame {
         self.draw_spinner(buffer, left_screen[0]);
         self.draw_user_input(buffer, context, left_screen[1]);
 
+        // The turn order goes above the character sheet while a combat lasts.
+        let sheet_area = match &self.state.combat {
+            Some(combat) => {
+                let [tracker_area, sheet_area] = Layout::vertical([
+                    Constraint::Length(combat_tracker_height(combat)),
+                    Constraint::Fill(1),
+                ])
+                .areas(screen_split_layout[1]);
+                draw_combat_tracker(buffer, combat, tracker_area);
+                sheet_area
+            }
+            None => screen_split_layout[1],
+        };
+
         let image_present = self.state.image_path.is_some();
         match &self.state.main_character_sheet {
             Some(sheet) => {
@@ -245,14 +383,14 @@ impl Component for InGame {
                     buffer,
                     sheet,
                     image_present,
-                    screen_split_layout[1],
+                    sheet_area,
                     &self.highlighted_section,
                 );
                 self.draw_detailed_info(screen_split_layout[0], buffer, context);
             }
             None => {
                 let center_rect = center_rect(
-                    screen_split_layout[1],
+                    sheet_area,
                     Constraint::Percentage(100),
                     Constraint::Length(3),
                 );
@@ -269,6 +407,12 @@ impl Component for InGame {
                 no_character.render(center_rect, buffer);
             }
         }
+        if let Some(tool_log) = &mut self.tool_log {
+            tool_log.render(area, buffer);
+        }
+        if let Some(usage_view) = &mut self.usage_view {
+            usage_view.render(area, buffer, &self.state.usage, &context.settings.pricing);
+        }
     }
 }
 
@@ -295,17 +439,24 @@ impl InGame {
         let textarea = new_textarea("Input text to play");
         let mut new_self = Self {
             ai: game_ai,
+            turns: Vec::new(),
+            last_turn_id: 0,
+            archive_queue: ArchiveQueue::default(),
             state,
             content,
+            streamed: None,
             image,
             size,
             textarea,
             vim: Vim::new(Mode::Normal),
             receiver: None,
             highlighted_section: HighlightedSection::None,
+            tool_log: None,
+            usage_view: None,
             spinner: Spinner::new(),
             last_spinner_update: Instant::now(),
             spinner_active: false,
+            retrying: None,
             all_lines: Vec::new(),
             total_lines: 0,
             max_height: size.height as usize,
@@ -316,6 +467,357 @@ impl InGame {
         new_self
     }
 
+    fn budget_status(&self, context: &Context) -> BudgetStatus {
+        self.state.usage.budget_status(&context.settings.pricing)
+    }
+
+    fn turn_in_progress(&self) -> bool {
+        self.turns
+            .last()
+            .and_then(|turn| turn.handle.as_ref())
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    fn new_turn_id(&mut self) -> u64 {
+        self.last_turn_id += 1;
+        self.last_turn_id
+    }
+
+    // Whether what the turn with this id sends still belongs to the game. It stops once the turn
+    // is taken back, or rerolled under a new id.
+    pub fn holds_turn(&self, id: u64) -> bool {
+        self.turns.iter().any(|turn| turn.id == id)
+    }
+
+    // Saves the game from the App. The state holds what the last turn changed so far, so the
+    // save goes with that turn if it is taken back before the save is made.
+    pub fn save_message(&self) -> AIMessage {
+        AIMessage::Save(self.state.clone()).for_turn(self.turns.last().map(|turn| turn.id))
+    }
+
+    // Queues the turn the GM just answered for the Archivist.
+    pub fn archive_turn(&mut self, reply: &GameMessage) {
+        let (turn_id, player_action) = match self.turns.last() {
+            Some(turn) => (Some(turn.id), turn.input.clone()),
+            None => (None, "Start the Game".to_string()),
+        };
+        self.archive_queue.push(ArchiveRequest {
+            turn_id,
+            player_action,
+            reply: format!("{}\n{}", reply.crunch, reply.fluff.render()),
+        });
+        self.archive_next();
+    }
+
+    // The Archivist is done with a turn. Its memory is kept if the turn still stands, the turns
+    // played since then get it in their snapshot, and the next turn is archived. Returns whether
+    // the memory changed.
+    pub fn memory_archived(
+        &mut self,
+        turn_id: Option<u64>,
+        memory: Option<CampaignMemory>,
+    ) -> bool {
+        self.archive_queue.done();
+        let later = match turn_id {
+            Some(id) => self
+                .turns
+                .iter()
+                .position(|turn| turn.id == id)
+                .map(|position| position + 1),
+            None => Some(0),
+        };
+        let changed = match (memory, later) {
+            (Some(memory), Some(later)) => {
+                for turn in self.turns.iter_mut().skip(later) {
+                    turn.state.memory = memory.clone();
+                }
+                self.state.memory = memory;
+                true
+            }
+            (Some(_), None) => {
+                log::info!("Dropped the campaign memory of a turn that was taken back");
+                false
+            }
+            (None, _) => false,
+        };
+        self.archive_next();
+        changed
+    }
+
+    fn archive_next(&mut self) {
+        let turns = &self.turns;
+        while let Some(request) = self
+            .archive_queue
+            .next(|id| turns.iter().any(|turn| turn.id == id))
+        {
+            if self.ai.archive_turn(request, &self.state) {
+                return;
+            }
+            self.archive_queue.done();
+        }
+    }
+
+    // The GM reply arrived, the turn stays around for a reroll or an undo.
+    pub fn end_turn(&mut self) {
+        self.retrying = None;
+        if let Some(turn) = self.turns.last_mut() {
+            turn.handle = None;
+        }
+    }
+
+    pub fn retrying(&mut self, attempt: u32, max_attempts: u32) {
+        self.retrying = Some((attempt, max_attempts));
+    }
+
+    // A turn whose task ended without a reply failed, the spinner stops and the player is told.
+    fn check_turn(&mut self) {
+        let Some(turn) = self.turns.last_mut() else {
+            return;
+        };
+        if !turn
+            .handle
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+        let result = turn.handle.take().and_then(|handle| handle.now_or_never());
+        if let Some(Ok(Err(e))) = result {
+            log::error!("The turn failed: {e:#?}");
+            self.spinner_active = false;
+            self.retrying = None;
+            self.vim.mode = Mode::new_warning(Warning::TurnFailed);
+        }
+    }
+
+    pub fn track_thread_message(&mut self, message_type: MessageType, message_id: String) {
+        let Some(turn) = self.turns.last_mut() else {
+            return;
+        };
+        match message_type {
+            MessageType::User => turn.user_message_id = Some(message_id),
+            _ => turn.assistant_message_ids.push(message_id),
+        }
+    }
+
+    // Stops the GM turn in flight. The turn is taken back and the player gets their input back,
+    // a cancelled reroll keeps the player message, which can be rerolled again. Either way the
+    // game goes back to where it was before the turn.
+    fn cancel_turn(&mut self, context: &mut Context) -> Option<Action> {
+        if !self.turn_in_progress() {
+            return None;
+        }
+        let id = self.new_turn_id();
+        let turn = self.turns.last_mut()?;
+        turn.abort();
+        self.spinner_active = false;
+        self.retrying = None;
+        if turn.reroll {
+            self.state.rewind_for_reroll(turn.state.clone());
+            let ai = self.ai.for_turn(turn.id);
+            // What the cancelled reroll still sends is dropped, the player message stays.
+            turn.id = id;
+            context
+                .save_manager
+                .save(&self.state)
+                .expect("Should have saved from the game");
+            self.streamed = None;
+            self.all_lines = self.parse_full_game_content();
+            self.total_lines = self.all_lines.len();
+            self.scroll_to_bottom();
+            let state = self.state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = ai.cancel_turn(&state).await {
+                    log::error!("Failed to cancel the run: {e:#?}");
+                }
+            });
+        } else {
+            let turn = self.turns.pop()?;
+            self.rewind(turn, context.save_manager);
+        }
+        Some(Action::TurnTakenBack)
+    }
+
+    // Takes back the last turn: sheets, memory, transcript and thread messages. What the turn
+    // still has queued is dropped by the App, and the memory the Archivist is writing for it is
+    // dropped once the turn is gone.
+    fn undo_turn(&mut self, context: &mut Context) -> Option<Action> {
+        let Some(mut turn) = self.turns.pop() else {
+            log::warn!("No turn to undo in this session");
+            return None;
+        };
+        turn.abort();
+        self.rewind(turn, context.save_manager);
+        Some(Action::TurnTakenBack)
+    }
+
+    // Removes thread messages of a turn that was taken back before they reached the turn.
+    pub fn delete_thread_messages(&self, message_ids: Vec<String>) {
+        let ai = self.ai.clone();
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ai.delete_thread_messages(&state, &message_ids).await {
+                log::error!("Failed to delete the thread messages: {e:#?}");
+            }
+        });
+    }
+
+    // Puts the game back to where it was before the turn and gives the player their input back.
+    // What the turn cost stays in the usage.
+    fn rewind(&mut self, turn: Turn, save_manager: &SaveManager) {
+        tokio::spawn(self.take_back(turn, save_manager));
+    }
+
+    // Rewinds the game locally and returns what is left to do on the remote thread.
+    fn take_back(
+        &mut self,
+        turn: Turn,
+        save_manager: &SaveManager,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        turn.restore(&mut self.state, &mut self.content);
+        self.streamed = None;
+        self.all_lines = self.parse_full_game_content();
+        self.total_lines = self.all_lines.len();
+        self.scroll_to_bottom();
+        self.textarea = new_textarea_with_lines(
+            turn.input.lines().map(String::from).collect(),
+            "Input text to play",
+        );
+
+        let message_ids: Vec<String> = turn
+            .user_message_id
+            .into_iter()
+            .chain(turn.assistant_message_ids)
+            .collect();
+        save_manager
+            .save(&self.state)
+            .expect("Should have saved from the game");
+        let ai = self.ai.for_turn(turn.id);
+        let state = self.state.clone();
+        async move {
+            if let Err(e) = ai.cancel_turn(&state).await {
+                log::error!("Failed to cancel the run: {e:#?}");
+            }
+            if let Err(e) = ai.delete_thread_messages(&state, &message_ids).await {
+                log::error!("Failed to delete the thread messages: {e:#?}");
+            }
+        }
+    }
+
+    // The X-card: stops the GM, takes its last reply back and asks for the scene to go elsewhere.
+    // It needs no explanation, so it works at any time, even while the GM is answering.
+    pub fn play_x_card(&mut self, save_manager: &SaveManager, language: &Language) -> Action {
+        self.retrying = None;
+        let (input, instructions, cleanup) = match self.turns.pop() {
+            Some(mut turn) => {
+                turn.abort();
+                let input = turn.input.clone();
+                let cleanup = self.take_back(turn, save_manager);
+                // The input is sent again, rather than given back.
+                self.textarea = new_textarea("Input text to play");
+                (input, X_CARD_INSTRUCTIONS, Some(cleanup))
+            }
+            None => ("X-card".to_string(), X_CARD_SCENE_INSTRUCTIONS, None),
+        };
+        log::info!("X-card played on: {input}");
+
+        let id = self.new_turn_id();
+        let content_len = self.content.len();
+        let snapshot = self.state.clone();
+        self.state.usage.start_turn();
+        self.new_message(&Message::new(MessageType::User, input.clone()));
+        let language = self.state.campaign.language(language);
+        let mut message = UserCompletionRequest {
+            message: create_user_message(&self.state.campaign, &language, &input),
+            language,
+            state: self.state.clone(),
+        };
+        message.message.instructions.push(' ');
+        message.message.instructions.push_str(instructions);
+        let ai = self.ai.for_turn(id);
+        let handle = tokio::spawn(async move {
+            if let Some(cleanup) = cleanup {
+                cleanup.await;
+            }
+            ai.send_message(message).await?;
+            Ok::<(), Error>(())
+        });
+        self.turns.push(Turn {
+            id,
+            handle: Some(handle),
+            input,
+            reroll: false,
+            state: snapshot,
+            content_len,
+            user_message_id: None,
+            assistant_message_ids: Vec::new(),
+            x_card: true,
+        });
+        self.spinner_active = true;
+        save_manager
+            .save(&self.state)
+            .expect("Should have saved from the game");
+        Action::AudioNarration(AudioNarration::Stopped)
+    }
+
+    // A reply that mentions a line of the campaign is taken back as soon as it shows up. When the
+    // reply to an X-card crosses it again, the turn is only taken back.
+    pub fn line_crossed(&mut self, save_manager: &SaveManager, language: &Language) -> Action {
+        let action = match self.turns.last() {
+            Some(turn) if turn.x_card => {
+                let mut turn = self.turns.pop().expect("Expected the last turn");
+                turn.abort();
+                self.spinner_active = false;
+                self.retrying = None;
+                self.rewind(turn, save_manager);
+                Action::AudioNarration(AudioNarration::Stopped)
+            }
+            _ => self.play_x_card(save_manager, language),
+        };
+        self.vim.mode = Mode::new_warning(Warning::LineCrossed);
+        action
+    }
+
+    // Discards the last GM reply, rolls back the game it changed and asks for a new one.
+    // The new reply takes the place of the old one in the transcript.
+    fn reroll_turn(&mut self, context: &mut Context) -> Option<Action> {
+        if self.turns.is_empty() {
+            log::warn!("No turn to reroll in this session");
+            return None;
+        }
+        let id = self.new_turn_id();
+        let turn = self.turns.last_mut()?;
+        turn.id = id;
+        self.content.truncate(turn.content_len + 1);
+        self.state.rewind_for_reroll(turn.state.clone());
+        // The provider deletes the previous reply from the thread.
+        turn.assistant_message_ids.clear();
+
+        let language = self.state.campaign.language(&context.settings.language);
+        let message = UserCompletionRequest {
+            message: create_user_message(&self.state.campaign, &language, &turn.input),
+            language,
+            state: self.state.clone(),
+        };
+        let ai = self.ai.for_turn(id);
+        turn.handle = Some(tokio::spawn(async move {
+            ai.reroll_message(message).await?;
+            Ok::<(), Error>(())
+        }));
+        turn.reroll = true;
+
+        self.spinner_active = true;
+        self.streamed = None;
+        self.all_lines = self.parse_full_game_content();
+        self.total_lines = self.all_lines.len();
+        self.scroll_to_bottom();
+        context
+            .save_manager
+            .save(&self.state)
+            .expect("Should have saved from the game");
+        Some(Action::AudioNarration(AudioNarration::Stopped))
+    }
+
     fn check_transcription(&mut self) {
         if let Some(receiver) = &mut self.receiver {
             if let Ok(transcription) = receiver.try_recv() {
@@ -365,14 +867,41 @@ impl InGame {
                 )]),
                 Line::from(vec![Span::raw(&sheet.backstory)]),
             ],
-            HighlightedSection::Inventory => sheet
-                .inventory
-                .values()
-                .map(|item| {
-                    Line::from(vec![
-                        Span::styled(&item.name, Style::default().fg(Color::Yellow)),
-                        Span::raw(format!("(x{}): {} ", &item.quantity, &item.description)),
-                    ])
+            HighlightedSection::Inventory => inventory_by_category(sheet)
+                .into_iter()
+                .flat_map(|(category, items)| {
+                    let header = Line::from(vec![Span::styled(
+                        format!("{category}:"),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )]);
+                    let items = items.into_iter().map(|item| {
+                        let mut spans = vec![
+                            Span::styled(&item.name, Style::default().fg(Color::Yellow)),
+                            Span::raw(format!("(x{}): {} ", &item.quantity, &item.description)),
+                        ];
+                        if let Some(weapon) = &item.weapon {
+                            spans.push(Span::styled(
+                                weapon.stats(),
+                                Style::default().fg(Color::White),
+                            ));
+                        }
+                        if let Some(rating) = item.armor_rating {
+                            spans.push(Span::styled(
+                                format!("Armor {rating}"),
+                                Style::default().fg(Color::White),
+                            ));
+                        }
+                        if item.equipped {
+                            spans.push(Span::styled(
+                                " (equipped)",
+                                Style::default().fg(Color::Green),
+                            ));
+                        }
+                        Line::from(spans)
+                    });
+                    [header].into_iter().chain(items)
                 })
                 .collect::<Vec<_>>(),
             HighlightedSection::Contact => sheet
@@ -402,16 +931,8 @@ impl InGame {
                     ]
                 })
                 .collect::<Vec<_>>(),
-            HighlightedSection::Cyberware => sheet
-                .cyberware
-                .iter()
-                .flat_map(|cw| vec![Line::from(vec![Span::raw(cw)])])
-                .collect::<Vec<_>>(),
-            HighlightedSection::Bioware => sheet
-                .bioware
-                .iter()
-                .flat_map(|bw| vec![Line::from(vec![Span::raw(bw)])])
-                .collect::<Vec<_>>(),
+            HighlightedSection::Cyberware => get_augmentations(&sheet.cyberware),
+            HighlightedSection::Bioware => get_augmentations(&sheet.bioware),
             HighlightedSection::Resources => vec![
                 Line::from(vec![
                     Span::styled("Nuyen: ", Style::default().fg(Color::Yellow)),
@@ -431,9 +952,28 @@ impl InGame {
                             .fg(Color::White)
                             .add_modifier(Modifier::BOLD),
                     ),
+                    Span::raw(format!(
+                        " ¥{}/month, healing {:+}",
+                        sheet.lifestyle.monthly_cost(),
+                        sheet.lifestyle.healing_modifier()
+                    )),
                 ]),
-                Line::from(vec![Span::raw(LIFESTYLE)]),
-            ],
+            ]
+            .into_iter()
+            .chain(sheet.lifestyle.options.iter().map(|option| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", option.name),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(format!(
+                        "({:+}¥) {}",
+                        option.monthly_cost, option.description
+                    )),
+                ])
+            }))
+            .chain([Line::from(vec![Span::raw(LIFESTYLE)])])
+            .collect(),
             HighlightedSection::Attributes(0) => chunk_attributes(attributes, 0),
             HighlightedSection::Attributes(1) => chunk_attributes(attributes, 1),
             HighlightedSection::Attributes(_) => chunk_attributes(attributes, 2),
@@ -562,8 +1102,12 @@ impl InGame {
         self.update_scroll();
     }
 
-    fn draw_user_input(&mut self, buffer: &mut Buffer, _context: &Context, area: Rect) {
-        self.textarea.set_block(self.vim.mode.block());
+    fn draw_user_input(&mut self, buffer: &mut Buffer, context: &Context, area: Rect) {
+        self.textarea.set_block(
+            self.vim.mode.block().title_top(
+                status_line(&self.state.usage, &context.settings.pricing).right_aligned(),
+            ),
+        );
         self.check_transcription();
         self.textarea.render(area, buffer);
     }
@@ -574,11 +1118,29 @@ impl InGame {
         for message in self.content.iter() {
             all_lines.extend(self.parse_message(message));
         }
+        if let Some(fluff) = &self.streamed {
+            all_lines.extend(self.parse_message(&Message::new(
+                MessageType::Game,
+                format!("fluff:\n{}", fluff.render()),
+            )));
+        }
 
         all_lines
     }
 
+    pub fn stream_fluff(&mut self, fluff: Fluff) {
+        self.retrying = None;
+        self.streamed = Some(fluff);
+        self.all_lines = self.parse_full_game_content();
+        self.total_lines = self.all_lines.len();
+        self.scroll_to_bottom();
+    }
+
     pub fn new_message(&mut self, new_message: &Message) {
+        if self.streamed.take().is_some() {
+            self.all_lines = self.parse_full_game_content();
+            self.total_lines = self.all_lines.len();
+        }
         self.content.push(new_message.clone());
         let new_lines = self.parse_message(new_message);
         self.total_lines += new_lines.len();
@@ -640,12 +1202,14 @@ impl InGame {
     }
 
     fn build_user_completion_message(&self, context: &Context) -> UserCompletionRequest {
+        let language = self.state.campaign.language(&context.settings.language);
         let message = UserCompletionRequest {
-            language: context.settings.language.to_string(),
             message: create_user_message(
-                &context.settings.language.to_string(),
+                &self.state.campaign,
+                &language,
                 &self.textarea.lines().join("\n"),
             ),
+            language,
             state: self.state.clone(),
         };
         message
@@ -769,6 +1333,7 @@ impl InGame {
             return;
         };
         self.update_spinner();
+        self.check_turn();
         let spinner_area = Rect::new(
             left_screen.x,
             left_screen.bottom() - 1,
@@ -776,7 +1341,10 @@ impl InGame {
             1,
         );
 
-        let spinner_text = spinner_frame(&self.spinner);
+        let mut spinner_text = spinner_frame(&self.spinner);
+        if let Some((attempt, max_attempts)) = self.retrying {
+            spinner_text.push_str(&format!("retrying ({attempt}/{max_attempts})… "));
+        }
         let spinner_widget = Paragraph::new(spinner_text)
             .style(Style::default())
             .alignment(Alignment::Center);
@@ -878,6 +1446,39 @@ pub fn parse_markdown(line: String, base_style: Style) -> Line<'static> {
     Line::from(spans)
 }
 
+fn get_augmentations(augmentations: &[Augmentation]) -> Vec<Line<'_>> {
+    augmentations
+        .iter()
+        .flat_map(|augmentation| {
+            let rating = augmentation
+                .rating
+                .map(|rating| format!(" Rating: {rating} "))
+                .unwrap_or_default();
+            vec![
+                Line::from(vec![Span::styled(
+                    &augmentation.name,
+                    Style::default().fg(Color::Yellow),
+                )]),
+                Line::from(vec![Span::styled(
+                    format!(
+                        "{rating} Grade: {} Essence: {:.2} ",
+                        augmentation.grade,
+                        augmentation.essence()
+                    ),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+                Line::from(vec![Span::styled(
+                    augmentation.bonuses(),
+                    Style::default().fg(Color::Green),
+                )]),
+                Line::from(vec![Span::raw(&augmentation.description)]),
+            ]
+        })
+        .collect()
+}
+
 fn get_skills(sheet: &CharacterSheet) -> Vec<Line<'_>> {
     let mut skills = Vec::new();
     let (
@@ -889,20 +1490,35 @@ fn get_skills(sheet: &CharacterSheet) -> Vec<Line<'_>> {
         },
         knowledge,
     ) = (&sheet.skills, &sheet.knowledge_skills);
-    skills_category_to_lines(&mut skills, combat, "Combat".into());
-    skills_category_to_lines(&mut skills, physical, "Physical".into());
-    skills_category_to_lines(&mut skills, social, "Social".into());
-    skills_category_to_lines(&mut skills, technical, "Technical".into());
-    skills_category_to_lines(&mut skills, knowledge, "Knowledge".into());
+    skills_category_to_lines(&mut skills, sheet, combat, "Combat".into());
+    skills_category_to_lines(&mut skills, sheet, physical, "Physical".into());
+    skills_category_to_lines(&mut skills, sheet, social, "Social".into());
+    skills_category_to_lines(&mut skills, sheet, technical, "Technical".into());
+    skills_category_to_lines(&mut skills, sheet, knowledge, "Knowledge".into());
+    skills_category_to_lines(&mut skills, sheet, &sheet.skill_groups, "Group".into());
+    if !sheet.languages.is_empty() || sheet.native_language.is_some() {
+        skills_category_to_lines(&mut skills, sheet, &sheet.languages, "Language".into());
+    }
+    if let Some(language) = &sheet.native_language {
+        skills.push(Line::from(vec![
+            Span::raw(format!("{language} ")),
+            Span::styled("N", Style::default().fg(Color::Green)),
+        ]));
+    }
 
     skills
 }
 
+// Each skill with its rating, then the dice pool it rolls and its specialization or category.
 fn skills_category_to_lines(
     skills: &mut Vec<Line<'_>>,
+    sheet: &CharacterSheet,
     category: &std::collections::HashMap<String, u8>,
     name: String,
 ) {
+    if category.is_empty() && name == "Group" {
+        return;
+    }
     skills.push(Line::raw(""));
     skills.push(Line::from(vec![Span::styled(
         format!("{name} Skills: "),
@@ -915,10 +1531,41 @@ fn skills_category_to_lines(
         .unwrap_or(6);
 
     for (skill, level) in category {
-        skills.push(Line::from(vec![
+        let mut spans = vec![
             Span::raw(format!("{:width$} ", skill, width = max_width)),
             Span::styled(format!("{}", level), Style::default().fg(Color::Green)),
-        ]));
+        ];
+        // Groups are not rolled, their skills are.
+        if name == "Group" {
+            let members: Vec<&str> = group_skills(skill).map(|skill| skill.name).collect();
+            spans.push(Span::styled(
+                format!("  {}", members.join(", ")),
+                Style::default().fg(Color::DarkGray),
+            ));
+        } else if let Some(attribute) = sheet.linked_attribute(skill) {
+            spans.push(Span::styled(
+                format!("  {} dice ({attribute})", sheet.get_dice_pool(skill)),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if let Some(specialization) = sheet.get_specialization(skill) {
+            spans.push(Span::styled(
+                format!("  [{specialization}]"),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if name == "Knowledge" {
+            let category = sheet
+                .knowledge_categories
+                .get(skill)
+                .copied()
+                .unwrap_or_default();
+            spans.push(Span::styled(
+                format!("  {category}"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        skills.push(Line::from(spans));
     }
 }
 
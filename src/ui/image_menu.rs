@@ -5,6 +5,7 @@ use crate::{
     audio::{Transcription, try_play_asset},
     context::Context,
     imager,
+    settings::Capability,
 };
 use crossterm::event::KeyEvent;
 use ratatui::{
@@ -79,11 +80,12 @@ impl ImageMenu {
         let prompt = self.textarea.lines().join("\n");
         let image_sender = self.image_sender.clone();
         log::info!("Requested image creation with context: {context:#?}");
-        if let Some(client) = context.ai_client.clone() {
+        if context.settings.is_configured(Capability::Images) {
+            let images = context.settings.model_client(Capability::Images);
             log::debug!("Spawning  the image generation");
             tokio::spawn(async move {
                 log::debug!("Spawned  the image generation");
-                let path = imager::generate_and_save_image(client, &prompt)
+                let path = imager::generate_and_save_image(images, &prompt)
                     .await
                     .expect("Expected a valid image path");
 
@@ -132,9 +134,10 @@ impl Component for ImageMenu {
                         };
                         try_play_asset("start");
                         self.textarea.set_placeholder_text("   Recording...");
-                        if let Ok((receiver, transcription)) =
-                            Transcription::new(None, context.ai_client.clone().unwrap())
-                        {
+                        if let Ok((receiver, transcription)) = Transcription::new(
+                            None,
+                            context.settings.model_client(Capability::Transcription),
+                        ) {
                             self.transcription_receiver = Some(receiver);
                             log::debug!("Sent the recording request");
                             Some(Action::SwitchInputMode(InputMode::Recording(transcription)))
@@ -183,6 +186,12 @@ impl Component for ImageMenu {
             Transition::PageDown => None,
             Transition::ScrollUp => None,
             Transition::ScrollDown => None,
+            Transition::Cancel => None,
+            Transition::Reroll => None,
+            Transition::Undo => None,
+            Transition::ToolLog => None,
+            Transition::Usage => None,
+            Transition::XCard => None,
         }
     }
 
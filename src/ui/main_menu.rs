@@ -2,12 +2,12 @@
 
 // Import required modules and structs from other parts of the application or external crates.
 use super::{
-    Component, ComponentEnum, api_key_input::ApiKeyInput, draw::center_rect, image_menu::ImageMenu,
-    load_menu::LoadMenu, main_menu_fix::*, save_name_input::SaveName, settings_menu::SettingsMenu,
-    widgets::StatefulList,
+    CleanupMenu, Component, ComponentEnum, api_key_input::ApiKeyInput, draw::center_rect,
+    image_menu::ImageMenu, load_menu::LoadMenu, main_menu_fix::*, save_name_input::SaveName,
+    settings_menu::SettingsMenu, widgets::StatefulList,
 };
 
-use crate::{app::Action, context::Context, message::MessageType};
+use crate::{app::Action, context::Context, message::MessageType, settings::Capability};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     buffer::Buffer,
@@ -17,11 +17,12 @@ use ratatui::{
     widgets::*,
 };
 
-const MAIN_MENU: [&str; 4] = [
+const MAIN_MENU: [&str; 5] = [
     "Start a new game",
     "Load a game",
     "Create an image",
     "Settings",
+    "Clean up the OpenAI account",
 ];
 
 #[derive(Debug)]
@@ -209,6 +210,19 @@ impl MainMenu {
             Some(3) => Some(Action::SwitchComponent(ComponentEnum::from(
                 SettingsMenu::new(context),
             ))),
+            Some(4) => {
+                if context.settings.is_configured(Capability::Narration) {
+                    Some(Action::SwitchComponent(ComponentEnum::from(
+                        CleanupMenu::new(
+                            context.settings.model_client(Capability::Narration).client,
+                        ),
+                    )))
+                } else {
+                    Some(Action::SwitchComponent(ComponentEnum::from(
+                        ApiKeyInput::new(&context.settings.openai_api_key),
+                    )))
+                }
+            }
             _ => None,
         }
     }
@@ -1,11 +1,16 @@
 // ui/mod.rs
 
 pub mod api_key_input;
+mod boundaries_input;
+mod campaign_setup;
 pub mod character_sheet;
+mod cleanup;
+pub mod combat_tracker;
 pub mod component;
 mod constants;
 pub mod descriptions;
 pub mod draw;
+mod endpoint_input;
 pub mod game;
 mod image_menu;
 mod load_menu;
@@ -15,11 +20,17 @@ mod save_name_input;
 mod settings_menu;
 pub mod spinner;
 pub mod textarea;
+pub mod tool_log;
+pub mod usage;
 pub mod widgets;
 
 pub use self::character_sheet::*;
 pub use self::component::*;
 pub use self::draw::*;
+pub use boundaries_input::*;
+pub use campaign_setup::*;
+pub use cleanup::*;
+pub use endpoint_input::*;
 pub use image_menu::*;
 pub use load_menu::*;
 pub use main_menu::*;
@@ -5,6 +5,7 @@ use crate::{
     app::{Action, InputMode},
     audio::{Transcription, try_play_asset},
     context::Context,
+    settings::Capability,
 };
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -17,7 +18,9 @@ use ratatui::{
 use tokio::sync::mpsc::UnboundedReceiver;
 use tui_textarea::TextArea;
 
-use super::{Component, ComponentEnum, center_rect, main_menu::MainMenu, textarea::*};
+use super::{
+    CampaignSetup, Component, ComponentEnum, center_rect, main_menu::MainMenu, textarea::*,
+};
 
 #[derive(Default, Debug)]
 pub struct SaveName {
@@ -75,9 +78,10 @@ impl Component for SaveName {
 
                         try_play_asset("start");
                         self.textarea.set_placeholder_text("Recording...");
-                        if let Ok((receiver, transcription)) =
-                            Transcription::new(None, context.ai_client.clone().unwrap())
-                        {
+                        if let Ok((receiver, transcription)) = Transcription::new(
+                            None,
+                            context.settings.model_client(Capability::Transcription),
+                        ) {
                             self.receiver = Some(receiver);
                             Some(Action::SwitchInputMode(InputMode::Recording(transcription)))
                         } else {
@@ -98,7 +102,9 @@ impl Component for SaveName {
             }
             Transition::Validation => {
                 if self.textarea.lines().concat().len() > 1 {
-                    Some(Action::CreateNewGame(self.textarea.lines()[0].to_string()))
+                    Some(Action::SwitchComponent(ComponentEnum::from(
+                        CampaignSetup::new(self.textarea.lines()[0].to_string()),
+                    )))
                 } else {
                     self.vim.mode = Mode::new_warning(Warning::InputTooShort);
                     log::info!("Played Warning {:#?}", self.vim.mode);
@@ -121,6 +127,12 @@ impl Component for SaveName {
             Transition::PageDown => None,
             Transition::ScrollUp => None,
             Transition::ScrollDown => None,
+            Transition::Cancel => None,
+            Transition::Reroll => None,
+            Transition::Undo => None,
+            Transition::ToolLog => None,
+            Transition::Usage => None,
+            Transition::XCard => None,
         }
     }
     fn render(&mut self, area: Rect, buffer: &mut Buffer, _context: &Context) {
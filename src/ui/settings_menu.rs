@@ -1,8 +1,12 @@
 // ui/settings_menu.rs
 
 use crate::{
-    app::Action, context::Context, save::get_game_data_dir, settings::Language,
-    settings_state::SettingsState, ui::draw::center_rect,
+    app::Action,
+    context::Context,
+    save::get_game_data_dir,
+    settings::{Capability, Language, SessionMode},
+    settings_state::SettingsState,
+    ui::draw::center_rect,
 };
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -13,7 +17,12 @@ use ratatui::{
     widgets::*,
 };
 
-use super::{Component, ComponentEnum, MainMenu, api_key_input::ApiKeyInput, main_menu_fix::*};
+use super::{
+    Component, ComponentEnum, EndpointInput, MainMenu, api_key_input::ApiKeyInput, main_menu_fix::*,
+};
+
+// Settings rows 8 to 11 open the endpoint editor of each capability.
+const ENDPOINTS_OFFSET: usize = 7;
 
 #[derive(Debug)]
 pub struct SettingsMenu {
@@ -44,16 +53,7 @@ impl Component for SettingsMenu {
                 self.change_settings(-1);
                 None
             }
-            KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') => {
-                if self.state.selected_setting == 1 {
-                    Some(Action::SwitchComponent(ComponentEnum::from(
-                        ApiKeyInput::new(&context.settings.openai_api_key),
-                    )))
-                } else {
-                    self.change_settings(1);
-                    None
-                }
-            }
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') => self.select_setting(context),
             KeyCode::Esc => Some(Action::SwitchComponent(ComponentEnum::from(
                 MainMenu::default(),
             ))),
@@ -61,15 +61,7 @@ impl Component for SettingsMenu {
                 if let Some(digit) = c.to_digit(10) {
                     self.state.selected_setting =
                         ((digit as usize).saturating_sub(1)) % self.state.selected_options.len();
-                    match self.state.selected_setting {
-                        1 => Some(Action::SwitchComponent(ComponentEnum::from(
-                            ApiKeyInput::new(&context.settings.openai_api_key),
-                        ))),
-                        _ => {
-                            self.change_settings(1);
-                            None
-                        }
-                    }
+                    self.select_setting(context)
                 } else {
                     None
                 }
@@ -132,6 +124,11 @@ impl SettingsMenu {
             ("Voice Output", vec!["On", "Off"]),
             ("Voice Input", vec!["On", "Off"]),
             ("Debug Mode", vec!["Off", "On"]),
+            ("AI Session", vec!["Live", "Record", "Replay"]),
+            ("Narration API", vec![]),
+            ("Speech API", vec![]),
+            ("Transcription API", vec![]),
+            ("Images API", vec![]),
         ];
 
         let text: Vec<Line> = settings
@@ -164,6 +161,13 @@ impl SettingsMenu {
                         Span::styled("[Not Valid]", Style::default().fg(Color::Red))
                     };
                     spans.push(api_key_status);
+                } else if let Some(capability) = Self::endpoint_capability(number) {
+                    let endpoint = context.settings.endpoint(capability);
+                    let server = endpoint.base_url.as_deref().unwrap_or("OpenAI");
+                    spans.push(Span::styled(
+                        format!("[{}] [{}]", server, context.settings.model_for(capability)),
+                        Style::default().fg(Color::Green),
+                    ));
                 } else {
                     let selected_option = self.state.selected_options[number];
                     spans.extend(options.iter().enumerate().map(|(option_number, option)| {
@@ -227,6 +231,28 @@ impl SettingsMenu {
         console.render(area, buffer);
     }
 
+    fn endpoint_capability(setting: usize) -> Option<Capability> {
+        Capability::ALL
+            .get(setting.checked_sub(ENDPOINTS_OFFSET)?)
+            .copied()
+    }
+
+    // The Api Key and the endpoints open their own editor, the others cycle their options.
+    fn select_setting(&mut self, context: &mut Context) -> Option<Action> {
+        if self.state.selected_setting == 1 {
+            Some(Action::SwitchComponent(ComponentEnum::from(
+                ApiKeyInput::new(&context.settings.openai_api_key),
+            )))
+        } else if let Some(capability) = Self::endpoint_capability(self.state.selected_setting) {
+            Some(Action::SwitchComponent(ComponentEnum::from(
+                EndpointInput::new(capability, context.settings.endpoint(capability)),
+            )))
+        } else {
+            self.change_settings(1);
+            None
+        }
+    }
+
     pub fn apply_settings(&mut self, context: &mut Context) {
         // Apply changes from settings_state to settings
         context.settings.language = match self.state.selected_options[0] {
@@ -245,6 +271,11 @@ impl SettingsMenu {
         context.settings.audio_output_enabled = self.state.selected_options[3] == 0;
         context.settings.audio_input_enabled = self.state.selected_options[4] == 0;
         context.settings.debug_mode = self.state.selected_options[5] == 1;
+        context.settings.session = match self.state.selected_options[6] {
+            1 => SessionMode::Record,
+            2 => SessionMode::Replay,
+            _ => SessionMode::Live,
+        };
 
         // Save settings to file
         if let Err(e) = context.settings.save() {
@@ -265,7 +296,7 @@ impl SettingsMenu {
                             % 4
                 }
             }
-            (2, change) => {
+            (2 | 6, change) => {
                 if self.state.selected_options[current_setting] == 0 {
                     self.state.selected_options[current_setting] = (3 + change) as usize % 3;
                 } else {
@@ -274,6 +305,7 @@ impl SettingsMenu {
                             % 3
                 }
             }
+            (1, _) | (ENDPOINTS_OFFSET.., _) => {}
             (_current, _change) => {
                 self.state.selected_options[current_setting] =
                     1 - self.state.selected_options[current_setting];
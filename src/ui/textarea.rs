@@ -36,6 +36,11 @@ pub enum Warning {
     AudioInputDisabled,
     FailedNewTranscription,
     InputTooShort,
+    TurnInProgress,
+    BudgetAlmostReached,
+    BudgetReached,
+    TurnFailed,
+    LineCrossed,
 }
 impl Warning {
     fn color(&self) -> Color {
@@ -43,6 +48,11 @@ impl Warning {
             Warning::AudioInputDisabled => Color::Yellow,
             Warning::FailedNewTranscription => Color::Red,
             Warning::InputTooShort => Color::Yellow,
+            Warning::TurnInProgress => Color::Yellow,
+            Warning::BudgetAlmostReached => Color::Yellow,
+            Warning::BudgetReached => Color::Red,
+            Warning::TurnFailed => Color::Red,
+            Warning::LineCrossed => Color::Yellow,
         }
     }
     fn text(&self) -> String {
@@ -52,6 +62,15 @@ impl Warning {
             }
             Warning::FailedNewTranscription => " Failed to create a new Transcription. ",
             Warning::InputTooShort => " Input too Short. Write something before validation. ",
+            Warning::TurnInProgress => {
+                " The Game Master is still answering. Ctrl+c to cancel the turn. "
+            }
+            Warning::BudgetAlmostReached => {
+                " Most of the campaign budget is spent. B for details. "
+            }
+            Warning::BudgetReached => " The campaign budget is spent. B to raise it. ",
+            Warning::TurnFailed => " The Game Master could not answer. U to take the turn back. ",
+            Warning::LineCrossed => " A reply crossed a line of the campaign and was taken back. ",
         };
         text.to_string()
     }
@@ -60,6 +79,11 @@ impl Warning {
             Warning::AudioInputDisabled => "alert",
             Warning::FailedNewTranscription => "oops",
             Warning::InputTooShort => "alert",
+            Warning::TurnInProgress => "alert",
+            Warning::BudgetAlmostReached => "alert",
+            Warning::BudgetReached => "oops",
+            Warning::TurnFailed => "oops",
+            Warning::LineCrossed => "alert",
         }
     }
 }
@@ -154,6 +178,12 @@ impl fmt::Display for Mode {
 pub enum Transition {
     Nop,
     Validation,
+    Cancel,
+    Reroll,
+    Undo,
+    ToolLog,
+    Usage,
+    XCard,
     EndRecording,
     Detail(SectionMove),
     Exit,
@@ -601,6 +631,31 @@ impl Vim {
             Input {
                 key: Key::Enter, ..
             } if self.mode == Mode::Normal => Some(Transition::Validation),
+            Input {
+                key: Key::Char('c'),
+                ctrl: true,
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::Cancel),
+            Input {
+                key: Key::Char('R'),
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::Reroll),
+            Input {
+                key: Key::Char('U'),
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::Undo),
+            Input {
+                key: Key::Char('L'),
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::ToolLog),
+            Input {
+                key: Key::Char('B'),
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::Usage),
+            Input {
+                key: Key::Char('X'),
+                ..
+            } if self.mode == Mode::Normal => Some(Transition::XCard),
             Input {
                 key: Key::Char('v'),
                 ctrl: true,
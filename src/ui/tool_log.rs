@@ -0,0 +1,202 @@
+// ui/tool_log.rs
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::*,
+};
+use serde_json::Value;
+
+use super::draw::center_rect;
+use crate::{
+    game_state::GameState,
+    tool_log::{ToolCallEntry, load_tool_calls},
+};
+
+// Read-only overlay over the game listing the tool calls of the save, newest selected.
+#[derive(Debug)]
+pub struct ToolLogView {
+    entries: Vec<ToolCallEntry>,
+    state: ListState,
+    detail_scroll: u16,
+}
+
+impl ToolLogView {
+    pub fn new(game_state: &GameState) -> Self {
+        let entries = load_tool_calls(game_state).unwrap_or_else(|e| {
+            log::error!("Failed to load the tool log: {e:#?}");
+            Vec::new()
+        });
+        let mut state = ListState::default();
+        state.select(entries.len().checked_sub(1));
+        Self {
+            entries,
+            state,
+            detail_scroll: 0,
+        }
+    }
+
+    // Returns false when the view should close.
+    pub fn on_key(&mut self, key: KeyEvent) -> bool {
+        let last = self.entries.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or_default();
+        let next = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => return false,
+            KeyCode::Down | KeyCode::Char('j') => (selected + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+            KeyCode::Char('g') => 0,
+            KeyCode::Char('G') => last,
+            KeyCode::PageDown | KeyCode::Char(']') => {
+                self.detail_scroll = self.detail_scroll.saturating_add(5);
+                return true;
+            }
+            KeyCode::PageUp | KeyCode::Char('[') => {
+                self.detail_scroll = self.detail_scroll.saturating_sub(5);
+                return true;
+            }
+            _ => return true,
+        };
+        if !self.entries.is_empty() && next != selected {
+            self.state.select(Some(next));
+            self.detail_scroll = 0;
+        }
+        true
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let area = center_rect(area, Constraint::Percentage(90), Constraint::Percentage(90));
+        Clear.render(area, buffer);
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(" Tool Calls ")
+            .title_bottom(" ↓↑ or jk: select, [ ]: scroll details, Esc: close ");
+        let inner = block.inner(area);
+        block.render(area, buffer);
+
+        if self.entries.is_empty() {
+            Paragraph::new("No tool call was logged for this save yet.")
+                .style(Style::default().fg(Color::Yellow))
+                .render(inner, buffer);
+            return;
+        }
+
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(inner);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let time = entry.timestamp.get(11..19).unwrap_or(&entry.timestamp);
+                let mut spans = vec![
+                    Span::styled(format!("{time} "), Style::default().fg(Color::DarkGray)),
+                    Span::raw(entry.name.clone()),
+                ];
+                if !entry.changes.is_empty() {
+                    spans.push(Span::styled(
+                        format!(" ({})", entry.changes.len()),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::RIGHT))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        StatefulWidget::render(list, list_area, buffer, &mut self.state);
+
+        let Some(entry) = self.state.selected().and_then(|i| self.entries.get(i)) else {
+            return;
+        };
+        Paragraph::new(detail_lines(entry))
+            .wrap(Wrap { trim: false })
+            .scroll((self.detail_scroll, 0))
+            .block(Block::default().padding(Padding::horizontal(1)))
+            .render(detail_area, buffer);
+    }
+}
+
+fn detail_lines(entry: &ToolCallEntry) -> Vec<Line<'static>> {
+    let heading = |text: &str| {
+        Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(Color::Yellow),
+        ))
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                entry.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}", entry.timestamp),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::default(),
+        heading("Changes:"),
+    ];
+    if entry.changes.is_empty() {
+        lines.push(Line::from("  None"));
+    }
+    for change in &entry.changes {
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {} {}: ", change.character, change.field)),
+            Span::styled(compact(&change.before), Style::default().fg(Color::Red)),
+            Span::raw(" → "),
+            Span::styled(compact(&change.after), Style::default().fg(Color::Green)),
+        ]));
+    }
+
+    // Dice rolls break their pools down, the defender one too for opposed tests.
+    let output = serde_json::from_str::<Value>(&entry.output).unwrap_or_default();
+    let pools: Vec<&str> = [
+        &output["dice_pool"]["summary"],
+        &output["opposed"]["defender_dice_pool"]["summary"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_str)
+    .collect();
+    if !pools.is_empty() {
+        lines.push(Line::default());
+        lines.push(heading("Dice Pools:"));
+        lines.extend(pools.iter().map(|pool| Line::from(format!("  {pool}"))));
+    }
+
+    lines.push(Line::default());
+    lines.push(heading("Arguments:"));
+    let arguments = serde_json::from_str::<Value>(&entry.arguments)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| entry.arguments.clone());
+    lines.extend(arguments.lines().map(|line| Line::from(line.to_string())));
+
+    lines.push(Line::default());
+    lines.push(heading("Output:"));
+    lines.extend(
+        entry
+            .output
+            .lines()
+            .map(|line| Line::from(line.to_string())),
+    );
+    lines
+}
+
+fn compact(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "-".to_string(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,208 @@
+// ui/usage.rs
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::*,
+};
+
+use super::draw::center_rect;
+use crate::{
+    settings::Pricing,
+    usage::{BudgetStatus, Usage, UsageLedger},
+};
+
+const BUDGET_STEP: f64 = 1.0;
+
+// Overlay over the game with the usage of the campaign turn by turn and its budget.
+#[derive(Debug, Default)]
+pub struct UsageView {
+    scroll: usize,
+}
+
+// What a key did in the usage view, only a new budget needs saving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKey {
+    Close,
+    BudgetChanged,
+    Nothing,
+}
+
+impl UsageView {
+    // The budget keys change the ledger in place.
+    pub fn on_key(&mut self, key: KeyEvent, ledger: &mut UsageLedger) -> UsageKey {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => return UsageKey::Close,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll = (self.scroll + 1).min(ledger.turns.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('+') => {
+                ledger.adjust_budget(BUDGET_STEP);
+                return UsageKey::BudgetChanged;
+            }
+            KeyCode::Char('-') => {
+                ledger.adjust_budget(-BUDGET_STEP);
+                return UsageKey::BudgetChanged;
+            }
+            KeyCode::Char('x') => {
+                ledger.budget = None;
+                return UsageKey::BudgetChanged;
+            }
+            _ => {}
+        }
+        UsageKey::Nothing
+    }
+
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buffer: &mut Buffer,
+        ledger: &UsageLedger,
+        pricing: &Pricing,
+    ) {
+        let area = center_rect(area, Constraint::Percentage(80), Constraint::Percentage(80));
+        Clear.render(area, buffer);
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(" Campaign Usage ")
+            .title_bottom(" ↓↑ or jk: scroll, +/-: budget by $1, x: no budget, Esc: close ");
+        let inner = block.inner(area);
+        block.render(area, buffer);
+
+        let [summary_area, table_area] =
+            Layout::vertical([Constraint::Length(4), Constraint::Min(0)]).areas(inner);
+        Paragraph::new(summary_lines(ledger, pricing))
+            .block(Block::default().padding(Padding::horizontal(1)))
+            .render(summary_area, buffer);
+
+        let header = Row::new(vec![
+            "Turn",
+            "Started",
+            "Prompt",
+            "Completion",
+            "TTS chars",
+            "Dictation",
+            "Images",
+            "Cost",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows = ledger
+            .turns
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .map(|(index, turn)| {
+                let started = turn
+                    .started
+                    .get(..16)
+                    .unwrap_or(&turn.started)
+                    .replace('T', " ");
+                let mut cells = vec![(index + 1).to_string(), started];
+                cells.extend(usage_cells(&turn.usage, pricing));
+                Row::new(cells)
+            });
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(17),
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(9),
+        ];
+        Widget::render(
+            Table::new(rows, widths)
+                .header(header)
+                .block(Block::default().borders(Borders::TOP)),
+            table_area,
+            buffer,
+        );
+    }
+}
+
+// One line for the game status, like "12.3k tokens · $0.42 / $5.00".
+pub fn status_line(ledger: &UsageLedger, pricing: &Pricing) -> Line<'static> {
+    let total = ledger.total();
+    let mut text = format!(
+        " {} tokens · {}",
+        short_count(total.tokens()),
+        dollars(total.cost(pricing))
+    );
+    if let Some(budget) = ledger.budget {
+        text.push_str(&format!(" / {}", dollars(budget)));
+    }
+    text.push(' ');
+    Line::from(Span::styled(
+        text,
+        budget_style(ledger.budget_status(pricing)),
+    ))
+}
+
+fn summary_lines(ledger: &UsageLedger, pricing: &Pricing) -> Vec<Line<'static>> {
+    let total = ledger.total();
+    let budget = match ledger.budget {
+        Some(budget) => format!(
+            "{} of {} ({:.0}%)",
+            dollars(total.cost(pricing)),
+            dollars(budget),
+            total.cost(pricing) / budget * 100.0
+        ),
+        None => format!("{}, no budget", dollars(total.cost(pricing))),
+    };
+    vec![
+        Line::from(vec![
+            Span::raw("Spent: "),
+            Span::styled(budget, budget_style(ledger.budget_status(pricing))),
+        ]),
+        Line::from(format!(
+            "Tokens: {} prompt, {} completion",
+            total.prompt_tokens, total.completion_tokens
+        )),
+        Line::from(format!(
+            "Narration: {} characters, dictation: {:.0}s, images: {}",
+            total.tts_characters, total.transcription_seconds, total.images
+        )),
+    ]
+}
+
+fn usage_cells(usage: &Usage, pricing: &Pricing) -> Vec<String> {
+    vec![
+        usage.prompt_tokens.to_string(),
+        usage.completion_tokens.to_string(),
+        usage.tts_characters.to_string(),
+        format!("{:.0}s", usage.transcription_seconds),
+        usage.images.to_string(),
+        dollars(usage.cost(pricing)),
+    ]
+}
+
+fn budget_style(status: BudgetStatus) -> Style {
+    Style::default().fg(match status {
+        BudgetStatus::Unlimited | BudgetStatus::Within => Color::DarkGray,
+        BudgetStatus::Warning => Color::Yellow,
+        BudgetStatus::Reached => Color::Red,
+    })
+}
+
+fn dollars(amount: f64) -> String {
+    format!("${amount:.2}")
+}
+
+fn short_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
@@ -0,0 +1,160 @@
+// /usage.rs
+use async_openai::types::{CompletionUsage, RunCompletionUsage};
+use serde::{Deserialize, Serialize};
+use std::{mem, ops::AddAssign};
+
+use crate::settings::Pricing;
+
+// Past this share of the budget the player is warned at each turn.
+pub const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+// What the AI services were asked for. Tokens come from the GM and the Archivist.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub tts_characters: u64,
+    pub transcription_seconds: f64,
+    pub images: u64,
+}
+
+impl Usage {
+    pub fn tts(text: &str) -> Self {
+        Self {
+            tts_characters: text.chars().count() as u64,
+            ..Self::default()
+        }
+    }
+
+    pub fn transcription(seconds: f64) -> Self {
+        Self {
+            transcription_seconds: seconds,
+            ..Self::default()
+        }
+    }
+
+    pub fn image() -> Self {
+        Self {
+            images: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    // In dollars.
+    pub fn cost(&self, pricing: &Pricing) -> f64 {
+        let micro_usd = self.prompt_tokens as f64 * pricing.prompt_tokens as f64 / 1e6
+            + self.completion_tokens as f64 * pricing.completion_tokens as f64 / 1e6
+            + self.tts_characters as f64 * pricing.tts_characters as f64 / 1e6
+            + self.transcription_seconds * pricing.transcription_minute as f64 / 60.0
+            + self.images as f64 * pricing.image as f64;
+        micro_usd / 1e6
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.tts_characters += other.tts_characters;
+        self.transcription_seconds += other.transcription_seconds;
+        self.images += other.images;
+    }
+}
+
+impl From<CompletionUsage> for Usage {
+    fn from(usage: CompletionUsage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens.into(),
+            completion_tokens: usage.completion_tokens.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<RunCompletionUsage> for Usage {
+    fn from(usage: RunCompletionUsage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens.into(),
+            completion_tokens: usage.completion_tokens.into(),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TurnUsage {
+    pub started: String,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetStatus {
+    Unlimited,
+    Within,
+    Warning,
+    Reached,
+}
+
+// The usage of a campaign, turn by turn, and the budget the player set for it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct UsageLedger {
+    pub turns: Vec<TurnUsage>,
+    // Usage before the first turn and dictation of the next input, counted with the next turn.
+    pub pending: Usage,
+    // In dollars.
+    pub budget: Option<f64>,
+}
+
+impl UsageLedger {
+    pub fn start_turn(&mut self) {
+        self.turns.push(TurnUsage {
+            started: chrono::Local::now().to_rfc3339(),
+            usage: mem::take(&mut self.pending),
+        });
+    }
+
+    // Adds to the current turn. The player dictates the next input once the reply is in,
+    // so transcriptions wait for the turn they are for.
+    pub fn record(&mut self, mut usage: Usage) {
+        self.pending.transcription_seconds += mem::take(&mut usage.transcription_seconds);
+        match self.turns.last_mut() {
+            Some(turn) => turn.usage += usage,
+            None => self.pending += usage,
+        }
+    }
+
+    pub fn total(&self) -> Usage {
+        let mut total = self.pending;
+        for turn in &self.turns {
+            total += turn.usage;
+        }
+        total
+    }
+
+    pub fn budget_status(&self, pricing: &Pricing) -> BudgetStatus {
+        let Some(budget) = self.budget else {
+            return BudgetStatus::Unlimited;
+        };
+        let cost = self.total().cost(pricing);
+        if cost >= budget {
+            BudgetStatus::Reached
+        } else if cost >= budget * BUDGET_WARNING_RATIO {
+            BudgetStatus::Warning
+        } else {
+            BudgetStatus::Within
+        }
+    }
+
+    // Raises or lowers the budget, dropping it when it would go below zero.
+    pub fn adjust_budget(&mut self, delta: f64) {
+        let budget = self.budget.unwrap_or_default() + delta;
+        self.budget = (budget > 0.0).then_some(budget);
+    }
+}
@@ -287,3 +287,1873 @@ fn test_settings_env_var_precedence() {
         env::remove_var("OPENAI_API_KEY");
     }
 }
+
+#[test]
+fn test_legacy_save_defaults_to_assistants_backend() {
+    let legacy_save = r#"{
+        "assistant_id": "asst_123",
+        "thread_id": "thread_456",
+        "save_name": "legacy",
+        "characters": [],
+        "save_path": null,
+        "main_character_sheet": null,
+        "image_path": null
+    }"#;
+
+    let game_state: GameState = serde_json::from_str(legacy_save).expect("Failed to parse save");
+    assert_eq!(game_state.backend, provider::Backend::Assistants);
+    assert!(game_state.history.is_empty());
+    assert!(game_state.memory.is_empty());
+    // It still owns its assistant until it is moved to the shared one.
+    assert_eq!(game_state.assistant_version, None);
+
+    let new_state = GameState::new(provider::Backend::ChatCompletions, "new".to_string());
+    let serialized = serde_json::to_string(&new_state).expect("Failed to serialize save");
+    let reloaded: GameState = serde_json::from_str(&serialized).expect("Failed to reload save");
+    assert_eq!(reloaded.backend, provider::Backend::ChatCompletions);
+}
+
+#[test]
+fn test_endpoint_fallbacks() {
+    use sharad_ratatui::settings::{Capability, Endpoint};
+
+    let mut settings = Settings {
+        openai_api_key: Some("main_key".to_string()),
+        ..Settings::default()
+    };
+    assert!(settings.is_configured(Capability::Speech));
+    assert_eq!(settings.model_for(Capability::Narration), settings.model);
+    assert_eq!(settings.model_for(Capability::Transcription), "whisper-1");
+
+    settings.openai_api_key = None;
+    *settings.endpoint_mut(Capability::Transcription) = Endpoint {
+        base_url: Some("http://localhost:8080/v1".to_string()),
+        api_key: None,
+        model: Some("ggml-base.en".to_string()),
+    };
+    assert!(settings.is_configured(Capability::Transcription));
+    assert!(!settings.is_configured(Capability::Images));
+    assert_eq!(
+        settings.model_client(Capability::Transcription).model,
+        "ggml-base.en"
+    );
+}
+
+#[tokio::test]
+async fn test_replay_session() {
+    use sharad_ratatui::provider::{Backend, SessionEntry, record_entry};
+    use sharad_ratatui::settings::SessionMode;
+    use tokio::sync::mpsc;
+
+    let dir = tempdir().expect("Failed to create temp dir");
+    let mut game_state = GameState::new(Backend::ChatCompletions, "replay".to_string());
+    game_state.save_path = Some(dir.path().join("replay.json"));
+
+    let response = r#"{
+        "crunch": "Body raised to 5.",
+        "fluff": { "speakers": [], "dialogue": [] },
+        "character_sheet": null
+    }"#;
+    let request = serde_json::to_string(&UserMessage::new(
+        "Answer in valid json".to_string(),
+        "I hit the gym".to_string(),
+    ))
+    .expect("Failed to serialize request");
+    let entries = [
+        SessionEntry::Messages {
+            messages: vec![Message::new(MessageType::Game, "Welcome".to_string())],
+        },
+        SessionEntry::Request {
+            content: request.clone(),
+        },
+        SessionEntry::ToolCall {
+            name: "update_basic_attributes".to_string(),
+            arguments: r#"{"character_name": "Raven", "updates": {"body": 5}}"#.to_string(),
+            output: "Updated basic attributes for character: Raven".to_string(),
+        },
+        SessionEntry::Response {
+            content: response.to_string(),
+        },
+    ];
+    for entry in &entries {
+        record_entry(&game_state, entry).expect("Failed to record entry");
+    }
+
+    let settings = Settings {
+        session: SessionMode::Replay,
+        ..Settings::default()
+    };
+    let (ai_sender, mut ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&settings, ai_sender);
+
+    let messages = game_ai
+        .fetch_all_messages(&game_state)
+        .await
+        .expect("Failed to replay messages");
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].content, "Welcome");
+
+    let completion = UserCompletionRequest {
+        language: "English".to_string(),
+        message: UserMessage::new(
+            "Answer in valid json".to_string(),
+            "I hit the gym".to_string(),
+        ),
+        state: game_state,
+    };
+    game_ai
+        .send_message(completion)
+        .await
+        .expect("Failed to replay turn");
+
+    assert!(matches!(
+        ai_receiver.recv().await,
+        Some(AIMessage::RequestCharacterUpdate(_, name)) if name == "Raven"
+    ));
+    assert!(matches!(
+        ai_receiver.recv().await,
+        Some(AIMessage::UpdateHistory(history)) if history.len() == 2
+    ));
+    assert!(matches!(
+        ai_receiver.recv().await,
+        Some(AIMessage::Response(message)) if message.crunch == "Body raised to 5."
+    ));
+}
+
+#[test]
+fn test_reroll_truncates_the_last_reply() {
+    use async_openai::types::{
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+        ChatCompletionRequestToolMessage, ChatCompletionRequestUserMessage,
+    };
+    use sharad_ratatui::provider::truncate_to_last_user_message;
+
+    let reply = |content: &str| -> ChatCompletionRequestMessage {
+        ChatCompletionRequestAssistantMessageArgs::default()
+            .content(content)
+            .build()
+            .expect("Failed to build reply")
+            .into()
+    };
+    let mut history: Vec<ChatCompletionRequestMessage> = vec![
+        ChatCompletionRequestUserMessage::from("Start").into(),
+        reply("Welcome"),
+        ChatCompletionRequestUserMessage::from("I hit the gym").into(),
+        ChatCompletionRequestToolMessage {
+            content: "Updated basic attributes for character: Raven".into(),
+            tool_call_id: "call_1".to_string(),
+        }
+        .into(),
+        reply("Body raised to 5."),
+    ];
+
+    truncate_to_last_user_message(&mut history).expect("Failed to truncate");
+    assert_eq!(history.len(), 3);
+    assert!(matches!(
+        history.last(),
+        Some(ChatCompletionRequestMessage::User(_))
+    ));
+
+    // Rerolling twice replays the same player turn.
+    truncate_to_last_user_message(&mut history).expect("Failed to truncate");
+    assert_eq!(history.len(), 3);
+
+    let mut empty = Vec::new();
+    assert!(truncate_to_last_user_message(&mut empty).is_err());
+}
+
+#[test]
+fn test_invalid_tool_arguments_go_back_to_the_model() {
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    let (ai_sender, mut ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "tools".to_string());
+
+    let output = game_ai
+        .handle_tool_call(
+            "update_inventory",
+            r#"{"character_name": "Raven", "operation": "Steal", "item": {"name": "Ares Predator"}}"#,
+            &mut game_state,
+        )
+        .expect("Invalid arguments should not fail the turn");
+    let output: serde_json::Value = serde_json::from_str(&output).expect("Expected json output");
+    assert_eq!(output["error"], "Invalid arguments for update_inventory");
+    let details = output["details"].as_array().expect("Expected details");
+    assert!(details.iter().any(|detail| {
+        detail
+            .as_str()
+            .is_some_and(|detail| detail.starts_with("$.operation"))
+    }));
+    assert!(details.iter().any(|detail| {
+        detail
+            .as_str()
+            .is_some_and(|detail| detail.contains("'quantity'"))
+    }));
+    // Nothing reaches the sheet, the rejected call is only logged.
+    assert!(matches!(
+        ai_receiver.try_recv(),
+        Ok(AIMessage::ToolCall(entry)) if entry.name == "update_inventory"
+    ));
+    assert!(ai_receiver.try_recv().is_err());
+
+    let output = game_ai
+        .handle_tool_call("hack_the_gibson", "{}", &mut game_state)
+        .expect("Unknown functions should not fail the turn");
+    assert!(output.contains("Unknown function: hack_the_gibson"));
+}
+
+#[test]
+fn test_typed_tool_arguments() {
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    let (ai_sender, mut ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "tools".to_string());
+
+    game_ai
+        .handle_tool_call(
+            "update_skills",
+            r#"{"character_name": "Raven", "updates": {"skills": {"combat": {"Pistols": 5}}, "knowledge_skills": {"Street Gangs": 3}}}"#,
+            &mut game_state,
+        )
+        .expect("Failed to update skills");
+
+    let Ok(AIMessage::RequestCharacterUpdate(
+        CharacterSheetUpdate::Attribute {
+            attribute,
+            operation: UpdateOperation::Modify(CharacterValue::Skills(skills)),
+        },
+        name,
+    )) = ai_receiver.try_recv()
+    else {
+        panic!("Expected a skills update");
+    };
+    assert_eq!(attribute, "skills");
+    assert_eq!(name, "Raven");
+    assert_eq!(skills.combat.get("Pistols"), Some(&5));
+    assert!(skills.social.is_empty());
+    assert!(matches!(
+        ai_receiver.try_recv(),
+        Ok(AIMessage::RequestCharacterUpdate(CharacterSheetUpdate::Attribute { attribute, .. }, _)) if attribute == "knowledge_skills"
+    ));
+    assert!(matches!(ai_receiver.try_recv(), Ok(AIMessage::ToolCall(_))));
+
+    // Ratings are u8 on the sheet, the model hears about it instead of a silent truncation.
+    let output = game_ai
+        .handle_tool_call(
+            "update_basic_attributes",
+            r#"{"character_name": "Raven", "updates": {"body": 300}}"#,
+            &mut game_state,
+        )
+        .expect("Invalid arguments should not fail the turn");
+    assert!(output.contains("Invalid arguments for update_basic_attributes"));
+    assert!(matches!(ai_receiver.try_recv(), Ok(AIMessage::ToolCall(_))));
+    assert!(ai_receiver.try_recv().is_err());
+}
+
+#[test]
+fn test_tool_log_records_sheet_changes() {
+    use sharad_ratatui::provider::Backend;
+    use sharad_ratatui::tool_log::{ToolCallEntry, append_tool_call, load_tool_calls, sheet_diff};
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let before = create_character_from_args(&json_value["function"]["arguments"]);
+    let mut after = before.clone();
+    after
+        .apply_update(&CharacterSheetUpdate::Attribute {
+            attribute: "nuyen".to_string(),
+            operation: UpdateOperation::Modify(CharacterValue::Nuyen(before.nuyen - 5000)),
+        })
+        .expect("Failed to apply the update");
+
+    let changes = sheet_diff(Some(&before), &after);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].character, before.name);
+    assert_eq!(changes[0].field, "nuyen");
+    assert_eq!(changes[0].before, before.nuyen);
+    assert_eq!(changes[0].after, before.nuyen - 5000);
+
+    // A new character lists every field it was created with.
+    assert!(
+        sheet_diff(None, &after)
+            .iter()
+            .any(|change| change.field == "name" && change.before.is_null())
+    );
+
+    let dir = tempdir().expect("Failed to create temp dir");
+    let mut game_state = GameState::new(Backend::ChatCompletions, "audit".to_string());
+    game_state.save_path = Some(dir.path().join("audit.json"));
+    assert!(
+        load_tool_calls(&game_state)
+            .expect("Failed to load")
+            .is_empty()
+    );
+
+    let mut entry = ToolCallEntry::new(
+        "update_basic_attributes",
+        r#"{"character_name": "Raven", "updates": {"nuyen": 1000}}"#,
+        "Updated basic attributes for character: Raven",
+    );
+    entry.changes = changes;
+    append_tool_call(&game_state, &entry).expect("Failed to log");
+    append_tool_call(&game_state, &entry).expect("Failed to log");
+
+    let entries = load_tool_calls(&game_state).expect("Failed to load");
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].changes, entry.changes);
+}
+
+#[test]
+fn test_partial_fluff_from_streamed_response() {
+    let response = r#"{"crunch": "Roll Stealth.", "fluff": {"speakers": [{"index": 0, "name": "Narrator", "gender": "NonBinary", "voice": null}, {"index": 1, "name": "Fixer", "gender": "Female", "voice": null}], "dialogue": [{"speaker_index": 0, "text": "Rain hits the neon {signs}.", "audio": null}, {"speaker_index": 1, "text": "You're late, \"chummer\".", "audio": null}]}, "character_sheet": null}"#;
+
+    // Nothing to show until the text of the first dialogue line is complete.
+    let first_line_end = response
+        .find("\"audio\": null}")
+        .expect("Expected a dialogue line")
+        + 14;
+    let before_dialogue = response.find("\"dialogue\"").expect("Expected a dialogue");
+    let fluff =
+        Fluff::from_partial_response(&response[..before_dialogue]).expect("Expected the speakers");
+    assert_eq!(fluff.speakers.len(), 2);
+    assert!(fluff.dialogue.is_empty());
+
+    let mid_text = response.find("neon").expect("Expected a text");
+    let fluff = Fluff::from_partial_response(&response[..mid_text]).expect("Expected the speakers");
+    assert!(fluff.dialogue.is_empty());
+
+    let fluff = Fluff::from_partial_response(&response[..first_line_end]).expect("Expected a line");
+    assert_eq!(fluff.dialogue.len(), 1);
+    assert_eq!(fluff.dialogue[0].text, "Rain hits the neon {signs}.");
+
+    let fluff = Fluff::from_partial_response(response).expect("Expected the whole fluff");
+    assert_eq!(fluff.dialogue.len(), 2);
+    assert_eq!(fluff.dialogue[1].text, "You're late, \"chummer\".");
+}
+
+#[test]
+fn test_campaign_memory_context() {
+    use sharad_ratatui::archivist::CampaignMemory;
+
+    let memory: CampaignMemory = serde_json::from_str(
+        r#"{
+            "summary": "The runners stole a prototype from Renraku.",
+            "npcs": [{"name": "Mr. Johnson", "motivation": "Wants the prototype", "notes": "Plans to sell out the team"}],
+            "plot_threads": ["Who tipped off Lone Star?"],
+            "facts": []
+        }"#,
+    )
+    .expect("Failed to parse the campaign memory");
+    assert!(!memory.is_empty());
+    assert_eq!(memory.npcs[0].name, "Mr. Johnson");
+
+    let context = memory.to_context();
+    assert!(context.contains("The runners stole a prototype from Renraku."));
+    assert!(context.contains("- Mr. Johnson: Wants the prototype Plans to sell out the team"));
+    assert!(context.contains("Open plot threads:\n- Who tipped off Lone Star?"));
+    assert!(!context.contains("Facts:"));
+}
+
+#[test]
+fn test_usage_ledger_and_budget() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use sharad_ratatui::{
+        settings::Pricing,
+        ui::usage::{UsageKey, UsageView},
+        usage::{BudgetStatus, Usage, UsageLedger},
+    };
+
+    let pricing = Pricing::default();
+    let mut ledger = UsageLedger::default();
+    assert_eq!(ledger.budget_status(&pricing), BudgetStatus::Unlimited);
+
+    ledger.record(Usage::transcription(30.0));
+    ledger.start_turn();
+    ledger.record(Usage {
+        prompt_tokens: 1_000_000,
+        completion_tokens: 100_000,
+        ..Usage::default()
+    });
+    // Dictating the next input is counted with the next turn.
+    ledger.record(Usage::transcription(12.0));
+    ledger.record(Usage::image());
+    assert_eq!(ledger.turns.len(), 1);
+    assert_eq!(ledger.turns[0].usage.transcription_seconds, 30.0);
+    assert_eq!(ledger.turns[0].usage.images, 1);
+    assert_eq!(ledger.pending.transcription_seconds, 12.0);
+
+    ledger.start_turn();
+    assert_eq!(ledger.turns[1].usage.transcription_seconds, 12.0);
+    let total = ledger.total();
+    assert_eq!(total.tokens(), 1_100_000);
+    // 0.15 + 0.06 of tokens, 0.0042 of dictation and 0.04 for the image.
+    assert!((total.cost(&pricing) - 0.2542).abs() < 1e-9);
+
+    ledger.budget = Some(0.3);
+    assert_eq!(ledger.budget_status(&pricing), BudgetStatus::Warning);
+    ledger.budget = Some(0.25);
+    assert_eq!(ledger.budget_status(&pricing), BudgetStatus::Reached);
+    ledger.adjust_budget(1.0);
+    assert_eq!(ledger.budget_status(&pricing), BudgetStatus::Within);
+    ledger.adjust_budget(-2.0);
+    assert_eq!(ledger.budget, None);
+
+    // Only the budget keys of the usage view change what is saved.
+    let mut view = UsageView::default();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        view.on_key(key(KeyCode::Char('j')), &mut ledger),
+        UsageKey::Nothing
+    );
+    assert_eq!(
+        view.on_key(key(KeyCode::Char('+')), &mut ledger),
+        UsageKey::BudgetChanged
+    );
+    assert_eq!(ledger.budget, Some(1.0));
+    assert_eq!(view.on_key(key(KeyCode::Esc), &mut ledger), UsageKey::Close);
+
+    // Saves from before the ledger load without usage.
+    let state: game_state::GameState = serde_json::from_str(
+        r#"{"assistant_id": "", "thread_id": "", "save_name": "old", "characters": [],
+            "save_path": null, "main_character_sheet": null, "image_path": null}"#,
+    )
+    .expect("Failed to load an old save");
+    assert_eq!(state.usage, UsageLedger::default());
+}
+
+#[tokio::test]
+async fn test_retry_policy() {
+    use async_openai::error::{ApiError, OpenAIError};
+    use sharad_ratatui::{
+        error::{AIError, Error},
+        retry::{Retry, retry_after, with_retry},
+        settings::RetryPolicy,
+    };
+    use std::{cell::Cell, time::Duration};
+
+    let api_error = |kind: Option<&str>, message: &str| {
+        Error::AI(AIError::OpenAI(OpenAIError::ApiError(ApiError {
+            message: message.to_string(),
+            r#type: kind.map(str::to_string),
+            param: None,
+            code: None,
+        })))
+    };
+    let rate_limited = api_error(
+        Some("rate_limit_exceeded"),
+        "Rate limit reached for gpt-4o-mini. Please try again in 1m3.5s. Visit our docs.",
+    );
+    assert_eq!(
+        Retry::from_error(&rate_limited),
+        Retry::After(Some(Duration::from_millis(63_500)))
+    );
+    assert_eq!(
+        Retry::from_error(&api_error(None, "Bad gateway")),
+        Retry::After(None)
+    );
+    assert_eq!(
+        Retry::from_error(&api_error(Some("insufficient_quota"), "Check your plan")),
+        Retry::Never
+    );
+    assert_eq!(
+        Retry::from_error(&api_error(Some("invalid_request_error"), "Unknown model")),
+        Retry::Never
+    );
+    let refused_stream = Error::AI(AIError::OpenAI(OpenAIError::StreamError(
+        "Invalid status code: 503 Service Unavailable".to_string(),
+    )));
+    assert_eq!(Retry::from_error(&refused_stream), Retry::After(None));
+    assert_eq!(
+        retry_after("Please try again in 20ms."),
+        Some(Duration::from_millis(20))
+    );
+    assert_eq!(retry_after("Please try again later."), None);
+
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 0,
+        max_delay_ms: 0,
+    };
+    let delay = RetryPolicy::default().delay(3, None);
+    assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+    assert_eq!(
+        RetryPolicy::default().delay(1, Some(Duration::from_secs(10))),
+        Duration::from_secs(10)
+    );
+
+    let calls = Cell::new(0);
+    let retries = Cell::new(Vec::new());
+    let result: error::Result<()> = with_retry(
+        &policy,
+        |attempt, max| {
+            let mut seen = retries.take();
+            seen.push((attempt, max));
+            retries.set(seen);
+        },
+        || {
+            calls.set(calls.get() + 1);
+            async { Err(api_error(None, "Internal server error")) }
+        },
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 3);
+    assert_eq!(retries.take(), vec![(2, 3), (3, 3)]);
+
+    calls.set(0);
+    let result: error::Result<()> = with_retry(
+        &policy,
+        |_, _| {},
+        || {
+            calls.set(calls.get() + 1);
+            async { Err(api_error(Some("invalid_request_error"), "Unknown model")) }
+        },
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 1);
+}
+
+#[tokio::test]
+async fn test_assistant_version() {
+    use sharad_ratatui::{ai::GameAI, assistant::assistant_version};
+    use tokio::sync::mpsc;
+
+    let version = assistant_version("gpt-4o-mini").expect("Failed to hash the assets");
+    let parts: Vec<&str> = version.split(':').collect();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "gpt-4o-mini");
+    assert!(parts[1..].iter().all(|hash| hash.len() == 16));
+    assert_eq!(assistant_version("gpt-4o-mini").unwrap(), version);
+    let other = assistant_version("gpt-4o").unwrap();
+    assert_ne!(other, version);
+    assert_eq!(
+        other.split_once(':').unwrap().1,
+        version.split_once(':').unwrap().1
+    );
+
+    // Chat Completions saves have no assistant to move.
+    let (ai_sender, _ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(provider::Backend::ChatCompletions, "chat".to_string());
+    assert!(!game_ai.migrate_assistant(&mut game_state).await.unwrap());
+    assert_eq!(game_state.assistant_version, None);
+}
+
+#[test]
+fn test_orphaned_assistants() {
+    use async_openai::types::AssistantObject;
+    use sharad_ratatui::{
+        assistant::{is_sharad_assistant, orphaned_assistants},
+        save::{SaveManager, ThreadRegistry},
+    };
+    use std::collections::HashSet;
+
+    let assistant = |id: &str, metadata: serde_json::Value, tools: serde_json::Value| {
+        serde_json::from_value::<AssistantObject>(serde_json::json!({
+            "id": id,
+            "object": "assistant",
+            "created_at": 1700000000,
+            "name": id,
+            "model": "gpt-4o-mini",
+            "tools": tools,
+            "metadata": metadata,
+        }))
+        .expect("Failed to build the assistant")
+    };
+    let game_tools = serde_json::json!([{
+        "type": "function",
+        "function": { "name": "create_character_sheet", "parameters": {} }
+    }]);
+    let shared = assistant(
+        "asst_shared",
+        serde_json::json!({ "sharad_model": "gpt-4o-mini" }),
+        serde_json::json!([]),
+    );
+    let legacy = assistant("asst_legacy", serde_json::json!({}), game_tools.clone());
+    let kept = assistant("asst_kept", serde_json::json!({}), game_tools);
+    let foreign = assistant("asst_foreign", serde_json::json!({}), serde_json::json!([]));
+    assert!(is_sharad_assistant(&shared));
+    assert!(is_sharad_assistant(&legacy));
+    assert!(!is_sharad_assistant(&foreign));
+
+    // A save that no longer loads still holds on to its assistant.
+    let dir = tempdir().unwrap();
+    let save_path = dir.path().join("kept.json");
+    fs::write(
+        &save_path,
+        r#"{"assistant_id": "asst_kept", "thread_id": "thread_1", "future_field": 3}"#,
+    )
+    .unwrap();
+    let broken_path = dir.path().join("broken.json");
+    fs::write(&broken_path, "{ not json").unwrap();
+    let referenced = SaveManager::referenced_assistants(&[save_path.clone(), broken_path.clone()]);
+    assert_eq!(referenced, HashSet::from(["asst_kept".to_string()]));
+
+    let orphans = orphaned_assistants(vec![shared, legacy, kept, foreign], &referenced);
+    let ids: Vec<&str> = orphans.iter().map(|orphan| orphan.id.as_str()).collect();
+    assert_eq!(ids, ["asst_shared", "asst_legacy"]);
+
+    // Threads can't be listed, those of deleted saves are found in the registry.
+    let threads = SaveManager::referenced_threads(&[save_path, broken_path]);
+    assert_eq!(threads, HashSet::from(["thread_1".to_string()]));
+    let registry = ThreadRegistry::at(dir.path().join("threads.json"));
+    assert!(registry.threads().is_empty());
+    registry
+        .add(["thread_1".to_string(), "thread_deleted".to_string()])
+        .unwrap();
+    registry.add(threads.iter().cloned()).unwrap();
+    assert_eq!(registry.orphans(&threads), ["thread_deleted"]);
+    registry.remove(&["thread_deleted".to_string()]).unwrap();
+    assert!(registry.orphans(&threads).is_empty());
+    assert_eq!(registry.threads().len(), 1);
+}
+
+#[test]
+fn test_campaign_config_layers_instructions() {
+    use sharad_ratatui::{
+        assistant::load_instructions,
+        campaign::{CampaignConfig, Difficulty, Tone},
+        settings::Language,
+    };
+
+    // Saves from before campaign settings play as they did.
+    let legacy: CampaignConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(legacy, CampaignConfig::default());
+    assert_eq!(legacy.instructions(), None);
+    assert_eq!(legacy.system_instructions(), load_instructions());
+    assert_eq!(legacy.turn_instructions(), None);
+    assert_eq!(legacy.model("gpt-4o-mini"), "gpt-4o-mini");
+    assert_eq!(legacy.language(&Language::French), "French");
+
+    let dir = tempdir().unwrap();
+    let custom_path = dir.path().join("heist.md");
+    fs::write(&custom_path, "  The runner owes a debt to the Yakuza.\n").unwrap();
+    let campaign = CampaignConfig {
+        tone: Tone::Horror,
+        difficulty: Difficulty::Lethal,
+        instructions_file: Some(custom_path),
+        model: Some("gpt-4o".to_string()),
+        language: Some(Language::Japanese),
+        ..CampaignConfig::default()
+    };
+    let layer = campaign.instructions().unwrap();
+    assert!(layer.contains("Tone: horror"));
+    assert!(layer.contains("Difficulty: lethal"));
+    assert!(layer.ends_with("The runner owes a debt to the Yakuza."));
+    let system = campaign.system_instructions();
+    assert!(system.starts_with(load_instructions()));
+    assert!(system.ends_with(&layer));
+    assert_eq!(campaign.model("gpt-4o-mini"), "gpt-4o");
+    assert_eq!(campaign.language(&Language::English), "Japanese");
+
+    let message = create_user_message(&campaign, "Japanese", "Open the door");
+    assert!(message.instructions.contains("'fluff': Japanese."));
+    assert!(
+        message
+            .instructions
+            .ends_with("Keep the horror tone. Keep the lethal difficulty.")
+    );
+
+    // A missing instruction file is skipped rather than failing the turn.
+    let missing = CampaignConfig {
+        instructions_file: Some(dir.path().join("gone.md")),
+        ..CampaignConfig::default()
+    };
+    assert_eq!(missing.instructions(), None);
+}
+
+#[test]
+fn test_lines_and_veils() {
+    use sharad_ratatui::{campaign::CampaignConfig, safety::Boundaries};
+
+    let boundaries = Boundaries {
+        lines: Boundaries::parse_topics("Spider, harm to children\n\n  "),
+        veils: Boundaries::parse_topics("torture"),
+    };
+    assert_eq!(boundaries.lines, ["Spider", "harm to children"]);
+    assert_eq!(boundaries.veils, ["torture"]);
+
+    // Whole words and their plurals, whatever the case.
+    assert_eq!(
+        boundaries.crossed_line("Giant SPIDERS crawl out of the vent."),
+        Some("Spider")
+    );
+    assert_eq!(
+        boundaries.crossed_line("A spiderweb of cracks runs across the windshield."),
+        None
+    );
+    assert_eq!(
+        boundaries.crossed_line("The ganger threatens HARM TO CHILDREN."),
+        Some("harm to children")
+    );
+    assert_eq!(
+        boundaries.crossed_line("The torture happened off screen."),
+        None
+    );
+
+    // The boundaries go with every turn.
+    let campaign = CampaignConfig {
+        boundaries,
+        ..CampaignConfig::default()
+    };
+    let reminder = campaign.turn_instructions().unwrap();
+    assert!(reminder.contains("Spider; harm to children"));
+    assert!(reminder.contains("fade to black"));
+    assert!(campaign.instructions().unwrap().contains("torture"));
+    assert_eq!(Boundaries::default().instructions(), None);
+}
+
+#[test]
+fn test_condition_monitor_damage() {
+    use sharad_ratatui::character::DamageType;
+    use sharad_ratatui::dice::soak_damage;
+    use sharad_ratatui::tools::{ApplyDamageArgs, parse_arguments};
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    let physical = character.derived_attributes.monitors.physical;
+    let stun = character.derived_attributes.monitors.stun;
+    let skill = character
+        .get_all_active_skills()
+        .into_keys()
+        .next()
+        .expect("Expected a skill");
+    let healthy_pool = character.get_dice_pool(&skill);
+
+    // One die off per 3 boxes of each track.
+    character
+        .apply_update(&CharacterSheetUpdate::Damage {
+            damage_type: DamageType::Physical,
+            boxes: 4,
+        })
+        .expect("Failed to apply the damage");
+    character.take_damage(DamageType::Stun, 3);
+    assert_eq!(character.wound_modifier(), 2);
+    assert_eq!(
+        character.get_dice_pool(&skill),
+        healthy_pool.saturating_sub(2)
+    );
+
+    // Stun past the track carries into the physical one, one box per two.
+    character.take_damage(DamageType::Stun, stun - 3 + 4);
+    assert_eq!(character.derived_attributes.monitors.stun_damage, stun);
+    assert_eq!(character.derived_attributes.monitors.physical_damage, 6);
+    assert!(character.is_unconscious());
+
+    // Physical past the track fills the overflow, and healing closes it first.
+    character.take_damage(DamageType::Physical, physical - 6 + 2);
+    assert_eq!(character.derived_attributes.monitors.overflow, 2);
+    assert!(!character.is_dead());
+    character.heal(DamageType::Physical, 3);
+    assert_eq!(character.derived_attributes.monitors.overflow, 0);
+    assert_eq!(
+        character.derived_attributes.monitors.physical_damage,
+        physical - 1
+    );
+    character.take_damage(DamageType::Physical, character.attributes.body + 2);
+    assert!(character.is_dead());
+
+    // Physical damage below the armor becomes stun, unsoaked damage is marked as it is.
+    let mut armored = create_character_from_args(&json_value["function"]["arguments"]);
+    armored.derived_attributes.armor = 12;
+    let response = soak_damage(armored.clone(), DamageType::Physical, 5, -2, false);
+    assert_eq!(response.damage_type, DamageType::Stun);
+    assert_eq!(response.boxes, 5);
+    assert!(response.dice_results.is_empty());
+    assert_eq!(response.condition.wound_modifier, -1);
+    let response = soak_damage(armored, DamageType::Physical, 12, -2, true);
+    assert_eq!(response.damage_type, DamageType::Physical);
+    assert_eq!(response.boxes, 12 - response.soak_hits.min(12));
+
+    let args: ApplyDamageArgs = parse_arguments(
+        "apply_damage",
+        r#"{"character_name": "Raven", "damage_type": "physical", "damage_value": 6}"#,
+    )
+    .expect("Valid arguments");
+    assert!(args.soak);
+    assert_eq!(args.armor_penetration, 0);
+
+    // Sheets saved before damage tracking start unharmed.
+    let mut saved = serde_json::to_value(&character).expect("Failed to serialize");
+    let monitors = saved["derived_attributes"]["monitors"]
+        .as_object_mut()
+        .expect("Expected monitors");
+    monitors.remove("physical_damage");
+    monitors.remove("stun_damage");
+    monitors.remove("overflow");
+    let legacy: CharacterSheet = serde_json::from_value(saved).expect("Failed to deserialize");
+    assert_eq!(legacy.wound_modifier(), 0);
+    assert!(!legacy.is_unconscious());
+}
+
+#[test]
+fn test_combat_initiative_passes() {
+    use sharad_ratatui::combat::{Combat, StatusEffect};
+    use sharad_ratatui::tools::{UpdateCombatArgs, parse_arguments};
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut runner = create_character_from_args(&json_value["function"]["arguments"]);
+    runner.main = true;
+    // Without initiative dice the scores are known in advance.
+    runner.derived_attributes.initiative = (15, 0);
+    let mut ganger = runner.clone();
+    ganger.name = "Ganger".to_string();
+    ganger.main = false;
+    ganger.derived_attributes.initiative = (8, 0);
+    let characters = vec![ganger.clone(), runner.clone()];
+
+    let mut combat = Combat::start(&[&ganger, &runner]);
+    assert_eq!(combat.combatants[0].name, runner.name);
+    assert_eq!(combat.current.as_deref(), Some(runner.name.as_str()));
+    combat
+        .add_status_effect(
+            "Ganger",
+            StatusEffect {
+                name: "stunned".to_string(),
+                rounds: Some(1),
+            },
+        )
+        .expect("The ganger fights");
+    combat
+        .add_status_effect(
+            &runner.name,
+            StatusEffect {
+                name: "prone".to_string(),
+                rounds: None,
+            },
+        )
+        .expect("The runner fights");
+
+    combat.next_turn(&characters);
+    assert_eq!(combat.current.as_deref(), Some("Ganger"));
+
+    // Only the runner has initiative left for a second pass.
+    combat.next_turn(&characters);
+    assert_eq!((combat.round, combat.pass), (1, 2));
+    assert_eq!(combat.current.as_deref(), Some(runner.name.as_str()));
+    assert_eq!(combat.combatants[0].initiative, 5);
+    assert!(combat.interrupt(&runner.name, 10).is_err());
+    combat
+        .interrupt(&runner.name, 5)
+        .expect("Enough initiative to dodge");
+    assert!(combat.interrupt("Nobody", 5).is_err());
+
+    // Nobody can act anymore: a new round, initiative rolled again and effects counted down.
+    combat.next_turn(&characters);
+    assert_eq!((combat.round, combat.pass), (2, 1));
+    assert_eq!(combat.combatants[0].initiative, 15);
+    assert_eq!(combat.combatants[1].initiative, 8);
+    assert!(combat.combatants[1].status_effects.is_empty());
+    assert_eq!(combat.combatants[0].status_effects.len(), 1);
+    assert_eq!(combat.summary()["acting"], runner.name.as_str());
+
+    assert!(matches!(
+        parse_arguments("update_combat", r#"{"action": "next_turn"}"#),
+        Ok(UpdateCombatArgs::NextTurn)
+    ));
+    assert!(matches!(
+        parse_arguments(
+            "update_combat",
+            r#"{"action": "interrupt", "character_name": "Ganger", "initiative_cost": 5}"#
+        ),
+        Ok(UpdateCombatArgs::Interrupt {
+            initiative_cost: 5,
+            ..
+        })
+    ));
+    assert!(
+        parse_arguments::<UpdateCombatArgs>("update_combat", r#"{"action": "interrupt"}"#).is_err()
+    );
+}
+
+#[test]
+fn test_edge_actions() {
+    use sharad_ratatui::dice::{EdgeAction, dice_roll};
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    for _ in 0..200 {
+        // Sixes only explode when the limit is pushed.
+        let roll = dice_roll(6, Some(2), None, None, 4);
+        assert_eq!(roll.dice_results.len(), 6);
+        assert!(roll.hits <= 2);
+        assert!(!roll.edge_spent);
+        let roll = dice_roll(6, Some(2), None, Some(EdgeAction::PushTheLimit), 4);
+        assert!(roll.dice_results.len() >= 10);
+        assert_eq!(
+            roll.hits as usize,
+            roll.dice_results.iter().filter(|&&die| die >= 5).count()
+        );
+        assert!(roll.edge_spent);
+
+        // Second Chance and Close Call are only paid for when the roll needs them.
+        assert!(!dice_roll(3, None, Some(0), Some(EdgeAction::SecondChance), 4).edge_spent);
+        assert!(dice_roll(0, None, Some(1), Some(EdgeAction::SecondChance), 4).edge_spent);
+        let roll = dice_roll(1, None, None, Some(EdgeAction::CloseCall), 4);
+        assert!(!roll.critical_glitch);
+        assert_eq!(roll.edge_spent, roll.dice_results == [1]);
+    }
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    let edge = character.attributes.edge;
+    assert_eq!(character.derived_attributes.edge_points, edge);
+    character.refresh_edge(Some(3));
+    assert_eq!(character.derived_attributes.edge_points, edge);
+
+    // Every Edge use of a tool call takes a point off the sheet of the turn.
+    let (ai_sender, mut ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "edge".to_string());
+    let name = character.name.clone();
+    game_state.characters.push(character);
+    let roll = format!(
+        r#"{{"character_name": "{name}", "attribute": "agility", "skill": "Pistols", "limit_type": "physical", "edge_action": "PushTheLimit"}}"#
+    );
+    for _ in 0..edge {
+        let output = game_ai
+            .handle_tool_call("perform_dice_roll", &roll, &mut game_state)
+            .expect("Failed to roll");
+        assert!(output.contains(r#""edge_spent":true"#));
+        assert!(matches!(
+            ai_receiver.try_recv(),
+            Ok(AIMessage::RequestCharacterUpdate(
+                CharacterSheetUpdate::SpendEdge,
+                _
+            ))
+        ));
+        assert!(matches!(ai_receiver.try_recv(), Ok(AIMessage::ToolCall(_))));
+    }
+    assert_eq!(game_state.characters[0].derived_attributes.edge_points, 0);
+    let output = game_ai
+        .handle_tool_call("perform_dice_roll", &roll, &mut game_state)
+        .expect("Failed to roll");
+    assert!(output.contains("no Edge points left"));
+    let output = game_ai
+        .handle_tool_call(
+            "dead_mans_trigger",
+            &format!(r#"{{"character_name": "{name}"}}"#),
+            &mut game_state,
+        )
+        .expect("Invalid arguments should not fail the turn");
+    assert!(output.contains("no Edge points left"));
+
+    game_ai
+        .handle_tool_call(
+            "refresh_edge",
+            &format!(r#"{{"character_name": "{name}", "points": 1}}"#),
+            &mut game_state,
+        )
+        .expect("Failed to refresh");
+    assert_eq!(game_state.characters[0].derived_attributes.edge_points, 1);
+    let mut character = game_state.characters[0].clone();
+    character
+        .apply_update(&CharacterSheetUpdate::SpendEdge)
+        .expect("One point left");
+    assert!(character.spend_edge().is_err());
+    character.refresh_edge(None);
+    assert_eq!(character.derived_attributes.edge_points, edge);
+}
+
+#[test]
+fn test_opposed_and_extended_tests() {
+    use sharad_ratatui::dice::{
+        ExtendedTestRequest, OpposedTestRequest, perform_extended_test, perform_opposed_test,
+    };
+    use sharad_ratatui::provider::Backend;
+    use sharad_ratatui::tools::parse_arguments;
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let runner = create_character_from_args(&json_value["function"]["arguments"]);
+    let mut guard = runner.clone();
+    guard.name = "Guard".to_string();
+    let mut game_state = GameState::new(Backend::ChatCompletions, "tests".to_string());
+    game_state.characters = vec![runner.clone(), guard];
+
+    let opposed = format!(
+        r#"{{"attacker": {{"character_name": "{}", "attribute": "agility", "skill": "Stealth", "limit_type": "physical"}},
+            "defender": {{"character_name": "Guard", "attribute": "intuition", "skill": "Perception", "limit_type": "mental"}}}}"#,
+        runner.name
+    );
+    for _ in 0..100 {
+        let request: OpposedTestRequest =
+            parse_arguments("perform_opposed_test", &opposed).expect("Valid arguments");
+        let response = perform_opposed_test(request, &game_state).expect("Failed to roll");
+        let defender = response.opposed.expect("Expected the defender roll");
+        assert_eq!(defender.defender, "Guard");
+        assert_eq!(
+            defender.net_hits,
+            i16::from(response.hits) - i16::from(defender.defender_hits)
+        );
+        // Ties go to the defender.
+        assert_eq!(response.success, defender.net_hits > 0);
+    }
+    let request: OpposedTestRequest =
+        serde_json::from_str(&opposed.replace("Guard", "Nobody")).expect("Valid arguments");
+    assert!(perform_opposed_test(request, &game_state).is_err());
+    // Both sides would check their Edge against the same sheet.
+    let request: OpposedTestRequest =
+        serde_json::from_str(&opposed.replace("Guard", &runner.name)).expect("Valid arguments");
+    assert!(perform_opposed_test(request, &game_state).is_err());
+    // Without a threshold, Second Chance could not tell that the attacker lost.
+    let request: OpposedTestRequest = serde_json::from_str(&opposed.replace(
+        r#""limit_type": "mental""#,
+        r#""limit_type": "mental", "edge_action": "SecondChance""#,
+    ))
+    .expect("Valid arguments");
+    assert!(perform_opposed_test(request, &game_state).is_err());
+
+    let extended = format!(
+        r#"{{"character_name": "{}", "attribute": "logic", "skill": "Hacking", "limit_type": "mental", "threshold": 12, "interval": "1 hour", "max_rolls": 4}}"#,
+        runner.name
+    );
+    for _ in 0..100 {
+        let request: ExtendedTestRequest =
+            parse_arguments("perform_extended_test", &extended).expect("Valid arguments");
+        let response = perform_extended_test(request, &game_state).expect("Failed to roll");
+        let extended = response.extended.expect("Expected the rolls");
+        assert!(!extended.rolls.is_empty() && extended.rolls.len() <= 4);
+        assert_eq!(
+            extended.time_taken,
+            format!("{} x 1 hour", extended.rolls.len())
+        );
+        // The pool shrinks by a die at each roll and the hits add up, less the glitches.
+        for (i, roll) in extended.rolls.iter().enumerate() {
+            assert_eq!(roll.dice_pool, extended.rolls[0].dice_pool - i as u8);
+            let before = i.checked_sub(1).map_or(0, |i| extended.rolls[i].total_hits);
+            assert_eq!(roll.total_hits, before + roll.hits - roll.hits_lost);
+        }
+        let last = extended.rolls.last().expect("Expected a roll");
+        assert_eq!(response.hits, last.total_hits);
+        assert_eq!(
+            response.success,
+            !response.critical_glitch && response.hits >= 12
+        );
+        if response.success || response.critical_glitch {
+            assert!(extended.rolls.len() <= 4);
+        } else {
+            assert!(extended.rolls.len() == 4 || last.dice_pool == 1);
+        }
+    }
+}
+
+#[test]
+fn test_dice_pool_modifiers() {
+    use sharad_ratatui::augmentation::Augmentation;
+    use sharad_ratatui::dice::{DiceRollRequest, perform_dice_roll};
+    use sharad_ratatui::dice_pool::{DicePool, PoolModifier};
+    use sharad_ratatui::provider::Backend;
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    let agility = character.attributes.agility;
+
+    // Attribute and skill, whatever the case of the skill.
+    let pool = DicePool::new(&character, Some("agility"), "pistols", None, &[])
+        .expect("Expected a dice pool");
+    assert_eq!(pool.skill_rating, 4);
+    assert!(pool.modifiers.is_empty());
+    assert_eq!(pool.total, agility + 4);
+    assert_eq!(
+        pool.summary,
+        format!("Agility {agility} + pistols 4 = {}", agility + 4)
+    );
+
+    // Defaulting on a missing skill, attribute only tests do not.
+    let pool = DicePool::new(&character, Some("agility"), "Archery", None, &[])
+        .expect("Expected a dice pool");
+    assert_eq!(
+        pool.modifiers,
+        vec![PoolModifier {
+            name: "Defaulting".to_string(),
+            value: -1
+        }]
+    );
+    assert_eq!(pool.total, agility - 1);
+    assert_eq!(
+        DicePool::new(&character, Some("agility"), "none", None, &[])
+            .expect("Expected a dice pool")
+            .total,
+        agility
+    );
+
+    // Specializations only count when the character has them.
+    character
+        .apply_update(&CharacterSheetUpdate::Attribute {
+            attribute: "specializations".to_string(),
+            operation: UpdateOperation::Modify(CharacterValue::HashMapStringString(HashMap::from(
+                [("Pistols".to_string(), "Semi-Automatics".to_string())],
+            ))),
+        })
+        .expect("Failed to add the specialization");
+    let specialized = DicePool::new(
+        &character,
+        Some("agility"),
+        "Pistols",
+        Some("semi-automatics"),
+        &[],
+    )
+    .expect("Expected a dice pool");
+    assert_eq!(specialized.total, agility + 4 + 2);
+    assert_eq!(
+        DicePool::new(&character, Some("agility"), "Pistols", Some("Tasers"), &[])
+            .expect("Expected a dice pool")
+            .total,
+        agility + 4
+    );
+
+    // Augmentations raise the attribute by 4 at most, the smartlink helps with firearms.
+    character.cyberware = vec![
+        Augmentation::from("Smartlink".to_string()),
+        Augmentation::from("Muscle Replacement 3".to_string()),
+    ];
+    character.bioware = vec![Augmentation::from("Muscle Toner 2".to_string())];
+    let pool = DicePool::new(&character, Some("agility"), "Pistols", None, &[])
+        .expect("Expected a dice pool");
+    assert_eq!(pool.total, agility + 4 + 2 + 4);
+    assert_eq!(
+        DicePool::new(&character, Some("agility"), "Athletics", None, &[])
+            .expect("Expected a dice pool")
+            .total,
+        agility + 5 + 4
+    );
+
+    // Wounds and situational modifiers, the pool never goes below zero.
+    character.take_damage(DamageType::Physical, 3);
+    let cover = PoolModifier {
+        name: "Partial cover".to_string(),
+        value: -2,
+    };
+    let pool = DicePool::new(
+        &character,
+        Some("agility"),
+        "Pistols",
+        None,
+        std::slice::from_ref(&cover),
+    )
+    .expect("Expected a dice pool");
+    assert_eq!(pool.total, agility + 4 + 2 + 4 - 1 - 2);
+    assert!(
+        pool.summary
+            .ends_with(&format!("Wounds -1 + Partial cover -2 = {}", pool.total))
+    );
+    let blind = PoolModifier {
+        name: "Blind fire".to_string(),
+        value: -100,
+    };
+    assert_eq!(
+        DicePool::new(&character, Some("agility"), "Pistols", None, &[blind])
+            .expect("Expected a dice pool")
+            .total,
+        0
+    );
+
+    // The roll reports its breakdown.
+    let mut game_state = GameState::new(Backend::ChatCompletions, "tests".to_string());
+    game_state.characters = vec![character.clone()];
+    let request: DiceRollRequest = serde_json::from_value(serde_json::json!({
+        "character_name": character.name,
+        "attribute": "agility",
+        "skill": "Pistols",
+        "limit_type": "physical",
+        "specialization": "Semi-Automatics",
+        "modifiers": [cover],
+    }))
+    .expect("Valid arguments");
+    let response = perform_dice_roll(request, &game_state).expect("Failed to roll");
+    assert_eq!(response.dice_pool.total, agility + 4 + 2 + 2 + 4 - 1 - 2);
+    assert_eq!(
+        response.dice_results.len(),
+        usize::from(response.dice_pool.total)
+    );
+}
+
+#[test]
+fn test_skill_catalog_and_groups() {
+    use sharad_ratatui::dice_pool::DicePool;
+    use sharad_ratatui::skills::{KnowledgeCategory, find_skill, group_skills};
+
+    let pistols = find_skill("pistols").expect("Expected Pistols in the catalog");
+    assert_eq!(pistols.attribute, "agility");
+    assert_eq!(pistols.group, Some("Firearms"));
+    let firearms: Vec<&str> = group_skills("firearms").map(|skill| skill.name).collect();
+    assert_eq!(firearms, ["Automatics", "Longarms", "Pistols"]);
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    let attributes = character.attributes.clone();
+
+    // Skills roll their linked attribute, missing ones by defaulting unless they cannot be.
+    assert_eq!(character.get_dice_pool("Hacking"), attributes.logic + 4);
+    assert_eq!(
+        character.get_dice_pool("Negotiation"),
+        attributes.charisma + 3
+    );
+    assert_eq!(
+        character.get_dice_pool("Perception"),
+        attributes.intuition - 1
+    );
+    assert_eq!(character.get_dice_pool("Medicine"), 0);
+    assert_eq!(character.get_dice_pool("Basket Weaving"), 0);
+    assert!(DicePool::new(&character, None, "Basket Weaving", None, &[]).is_err());
+    assert!(DicePool::new(&character, None, "none", None, &[]).is_err());
+
+    // Group ratings count for every skill of the group, unless a skill is rated higher.
+    character.skill_groups = HashMap::from([("Influence".to_string(), 4)]);
+    assert_eq!(character.get_skill_rating("Etiquette"), 4);
+    assert_eq!(character.get_skill_rating("Negotiation"), 4);
+    character.skill_groups = HashMap::from([("Influence".to_string(), 2)]);
+    assert_eq!(character.get_skill_rating("Negotiation"), 3);
+
+    // Knowledge skills roll by category, street knowledge without one, languages intuition.
+    assert_eq!(
+        character.linked_attribute("Street Knowledge"),
+        Some("intuition")
+    );
+    character
+        .apply_update(&CharacterSheetUpdate::Attribute {
+            attribute: "knowledge_categories".to_string(),
+            operation: UpdateOperation::Modify(CharacterValue::HashMapStringKnowledgeCategory(
+                HashMap::from([(
+                    "Security Systems".to_string(),
+                    KnowledgeCategory::Professional,
+                )]),
+            )),
+        })
+        .expect("Failed to set the category");
+    assert_eq!(
+        character.get_dice_pool("Security Systems"),
+        attributes.logic + 4
+    );
+    character.languages = HashMap::from([("Japanese".to_string(), 2)]);
+    character.native_language = Some("English".to_string());
+    assert_eq!(
+        character.get_dice_pool("japanese"),
+        attributes.intuition + 2
+    );
+    assert_eq!(character.get_dice_pool("English"), attributes.intuition);
+
+    // Saves from before the skill model load with empty groups and languages.
+    let mut save = serde_json::to_value(&character).expect("Failed to serialize");
+    for field in [
+        "specializations",
+        "skill_groups",
+        "knowledge_categories",
+        "languages",
+        "native_language",
+    ] {
+        save.as_object_mut()
+            .expect("Expected an object")
+            .remove(field);
+    }
+    let loaded: CharacterSheet = serde_json::from_value(save).expect("Failed to load");
+    assert!(loaded.skill_groups.is_empty() && loaded.languages.is_empty());
+    assert_eq!(loaded.native_language, None);
+    assert_eq!(loaded.get_dice_pool("Hacking"), attributes.logic + 4);
+}
+
+#[test]
+fn test_augmentations() {
+    use sharad_ratatui::augmentation::{Augmentation, AugmentationType, Grade};
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    character.magic.magic = Some(5);
+    let reaction = character.attributes.reaction;
+
+    // The grade lowers the essence cost, each point of essence lost costs a point of Magic.
+    let mut wired_reflexes = Augmentation::from("Wired Reflexes 2".to_string());
+    wired_reflexes.grade = Grade::Alphaware;
+    assert!((wired_reflexes.essence() - 2.4).abs() < 0.001);
+    character
+        .apply_update(&CharacterSheetUpdate::InstallAugmentation {
+            augmentation_type: AugmentationType::Cyberware,
+            augmentation: wired_reflexes,
+        })
+        .expect("Failed to install");
+    assert!((character.derived_attributes.essence.current - 3.6).abs() < 0.001);
+    assert_eq!(character.magic.magic, Some(2));
+    assert_eq!(character.augmented_attribute("reaction"), reaction + 2);
+    assert_eq!(character.derived_attributes.initiative.1, 3);
+    assert!(
+        character
+            .install_augmentation(
+                AugmentationType::Cyberware,
+                Augmentation::from("Wired Reflexes 1".to_string())
+            )
+            .is_ok()
+    );
+    assert_eq!(character.derived_attributes.initiative.1, 4);
+
+    // Bonuses to an attribute stop at 4, essence cannot run out, nothing is installed twice.
+    character
+        .install_augmentation(
+            AugmentationType::Bioware,
+            Augmentation::from("Reaction Enhancers 3".to_string()),
+        )
+        .expect("Failed to install");
+    assert_eq!(character.augmented_attribute("reaction"), reaction + 4);
+    assert!(
+        character
+            .install_augmentation(
+                AugmentationType::Cyberware,
+                Augmentation::from("Muscle Replacement 4".to_string()),
+            )
+            .is_err()
+    );
+    assert!(
+        character
+            .install_augmentation(
+                AugmentationType::Bioware,
+                Augmentation::from("Reaction Enhancers 3".to_string()),
+            )
+            .is_err()
+    );
+
+    // Removing an augmentation leaves the essence hole.
+    let essence = character.derived_attributes.essence.current;
+    character
+        .apply_update(&CharacterSheetUpdate::RemoveAugmentation {
+            augmentation_type: AugmentationType::Cyberware,
+            name: "wired reflexes 2".to_string(),
+        })
+        .expect("Failed to remove");
+    assert_eq!(character.derived_attributes.essence.current, essence);
+    assert_eq!(character.derived_attributes.initiative.1, 2);
+    assert!(
+        character
+            .remove_augmentation(AugmentationType::Cyberware, "Smartlink")
+            .is_err()
+    );
+
+    // Saves from before structured augmentations only have names.
+    let mut save = serde_json::to_value(&character).expect("Failed to serialize");
+    save["cyberware"] = serde_json::json!(["Smartlink", "Muscle Replacement 2"]);
+    let loaded: CharacterSheet = serde_json::from_value(save).expect("Failed to load");
+    assert_eq!(loaded.cyberware[1].rating, Some(2));
+    assert_eq!(loaded.cyberware[1].attribute_bonus("Strength"), 2);
+    assert_eq!(loaded.cyberware[0].skill_bonus("pistols"), 2);
+}
+
+#[test]
+fn test_lifestyle_upkeep() {
+    use sharad_ratatui::lifestyle::{Lifestyle, LifestyleOption, LifestyleTier};
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    assert_eq!(character.lifestyle, Lifestyle::new(LifestyleTier::Street));
+    assert_eq!(character.lifestyle.monthly_cost(), 0);
+
+    // Options add to the rent, months paid in advance cost nuyen right away.
+    character.nuyen = 12_000;
+    character
+        .apply_update(&CharacterSheetUpdate::ChangeLifestyle {
+            tier: Some(LifestyleTier::Low),
+            options: Some(vec![LifestyleOption {
+                name: "Special Work Area".to_string(),
+                monthly_cost: 1_000,
+                description: String::new(),
+            }]),
+            prepay_months: 1,
+        })
+        .expect("Failed to change the lifestyle");
+    assert_eq!(character.lifestyle.monthly_cost(), 3_000);
+    assert_eq!(character.nuyen, 9_000);
+    assert_eq!(character.lifestyle.prepaid_months, 1);
+    assert!(character.change_lifestyle(None, None, 4).is_err());
+    assert_eq!(character.nuyen, 9_000);
+
+    // Rent comes from the prepaid months, then the nuyen, until the character falls behind.
+    let upkeep = character.pay_upkeep(2);
+    assert_eq!((upkeep.prepaid_months_used, upkeep.nuyen_paid), (1, 3_000));
+    assert_eq!(upkeep.warning, None);
+    let upkeep = character.pay_upkeep(3);
+    assert_eq!((upkeep.nuyen_paid, upkeep.months_behind), (6_000, 1));
+    assert_eq!(character.nuyen, 0);
+    assert!(
+        upkeep
+            .warning
+            .is_some_and(|warning| warning.contains("1 month behind"))
+    );
+
+    // Paying in advance settles the months behind first.
+    character.nuyen = 6_000;
+    character
+        .change_lifestyle(None, None, 2)
+        .expect("Failed to pay the rent");
+    assert_eq!(character.lifestyle.months_behind, 0);
+    assert_eq!(character.lifestyle.prepaid_months, 1);
+
+    // Time goes by through the tool, the rent is due at the end of each month.
+    let (ai_sender, _ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "lifestyle".to_string());
+    game_state.characters.push(character);
+    let output = game_ai
+        .handle_tool_call("pass_time", r#"{"days": 20}"#, &mut game_state)
+        .expect("Failed to pass time");
+    assert!(output.contains(r#""upkeep":[]"#));
+    game_ai
+        .handle_tool_call("pass_time", r#"{"days": 45}"#, &mut game_state)
+        .expect("Failed to pass time");
+    assert_eq!(game_state.days, 65);
+    let character = &game_state.characters[0];
+    assert_eq!(character.lifestyle.prepaid_months, 0);
+    assert_eq!(character.lifestyle.months_behind, 1);
+    let output = game_ai
+        .handle_tool_call(
+            "update_lifestyle",
+            &format!(
+                r#"{{"character_name": "{}", "tier": "Luxury", "prepay_months": 1}}"#,
+                character.name
+            ),
+            &mut game_state,
+        )
+        .expect("Invalid arguments should not fail the turn");
+    assert!(output.contains("not enough"));
+
+    // Saves from before structured lifestyles only have a name.
+    let mut save = serde_json::to_value(&game_state.characters[0]).expect("Failed to serialize");
+    save["lifestyle"] = serde_json::json!("Middle");
+    let loaded: CharacterSheet = serde_json::from_value(save).expect("Failed to load");
+    assert_eq!(loaded.lifestyle, Lifestyle::new(LifestyleTier::Middle));
+}
+
+#[test]
+fn test_weapons_and_armor() {
+    use sharad_ratatui::gear::{FiringMode, ItemCategory, Weapon};
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut character = create_character_from_args(&json_value["function"]["arguments"]);
+    let name = character.name.clone();
+    let pistol: Item = serde_json::from_value(serde_json::json!({
+        "name": "Ares Predator V",
+        "quantity": 1,
+        "description": "Heavy pistol",
+        "category": "Weapon",
+        "weapon": {
+            "skill": "Pistols",
+            "damage_value": 8,
+            "damage_type": "physical",
+            "armor_penetration": -1,
+            "accuracy": 5,
+            "modes": ["SA"],
+            "ammo": 2,
+            "clip": 15
+        },
+        "armor_rating": null
+    }))
+    .expect("Valid item");
+    let jacket = Item {
+        name: "Armor Jacket".to_string(),
+        quantity: 1,
+        description: String::new(),
+        category: ItemCategory::Armor,
+        weapon: None,
+        armor_rating: Some(12),
+        equipped: false,
+    };
+    character
+        .apply_update(&CharacterSheetUpdate::Attribute {
+            attribute: "inventory".to_string(),
+            operation: UpdateOperation::Add(CharacterValue::HashMapStringItem(HashMap::from([
+                (pistol.name.clone(), pistol),
+                (jacket.name.clone(), jacket),
+            ]))),
+        })
+        .expect("Failed to add the gear");
+    assert_eq!(character.derived_attributes.armor, 0);
+    assert!(character.equipped_weapon(None).is_err());
+
+    // Wearing armor sets the armor of the character, only weapons and armor can be equipped.
+    let (ai_sender, _ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "gear".to_string());
+    game_state.characters.push(character.clone());
+    for item in ["armor jacket", "Ares Predator V"] {
+        game_ai
+            .handle_tool_call(
+                "update_gear",
+                &format!(
+                    r#"{{"character_name": "{name}", "item_name": "{item}", "action": "equip"}}"#
+                ),
+                &mut game_state,
+            )
+            .expect("Failed to equip");
+    }
+    assert_eq!(game_state.characters[0].derived_attributes.armor, 12);
+    assert!(character.equip_item("Commlink", true).is_err());
+
+    // Firing takes the rounds out of the clip and rolls the skill of the weapon.
+    let fire = format!(r#"{{"character_name": "{name}", "mode": "SA"}}"#);
+    let output = game_ai
+        .handle_tool_call("fire_weapon", &fire, &mut game_state)
+        .expect("Failed to fire");
+    let response: serde_json::Value = serde_json::from_str(&output).expect("Expected json output");
+    assert_eq!(response["attack"]["ammo_left"], 1);
+    assert_eq!(response["attack"]["damage_value"], 8);
+    assert_eq!(response["dice_pool"]["skill"], "Pistols");
+    assert!(response["hits"].as_u64().is_some_and(|hits| hits <= 5));
+    let output = game_ai
+        .handle_tool_call(
+            "fire_weapon",
+            &format!(r#"{{"character_name": "{name}", "mode": "BF"}}"#),
+            &mut game_state,
+        )
+        .expect("Invalid modes should not fail the turn");
+    assert!(output.contains("cannot fire in BF mode"));
+    game_ai
+        .handle_tool_call("fire_weapon", &fire, &mut game_state)
+        .expect("Failed to fire");
+    let output = game_ai
+        .handle_tool_call("fire_weapon", &fire, &mut game_state)
+        .expect("An empty clip should not fail the turn");
+    assert!(output.contains("reload it first"));
+
+    game_ai
+        .handle_tool_call(
+            "update_gear",
+            &format!(
+                r#"{{"character_name": "{name}", "item_name": "Ares Predator V", "action": "reload"}}"#
+            ),
+            &mut game_state,
+        )
+        .expect("Failed to reload");
+    let (_, weapon) = game_state.characters[0]
+        .equipped_weapon(Some("ares predator v"))
+        .expect("Expected the pistol");
+    assert_eq!(weapon.ammo, 15);
+
+    // Full auto fires more rounds than the character can compensate.
+    let rifle = Weapon {
+        skill: "Automatics".to_string(),
+        damage_value: 10,
+        damage_type: DamageType::Physical,
+        armor_penetration: -2,
+        accuracy: None,
+        modes: vec![FiringMode::FA],
+        recoil_compensation: 1,
+        ammo: 30,
+        clip: 30,
+    };
+    assert_eq!(rifle.stats(), "10P, AP -2, FA, RC 1, 30/30");
+    let mut character = game_state.characters[0].clone();
+    character.inventory.insert(
+        "AK-97".to_string(),
+        Item {
+            name: "AK-97".to_string(),
+            quantity: 1,
+            description: String::new(),
+            category: ItemCategory::Weapon,
+            weapon: Some(rifle),
+            armor_rating: None,
+            equipped: true,
+        },
+    );
+    game_state.characters = vec![character];
+    let output = game_ai
+        .handle_tool_call(
+            "fire_weapon",
+            &format!(r#"{{"character_name": "{name}", "weapon": "AK-97", "mode": "FA"}}"#),
+            &mut game_state,
+        )
+        .expect("Failed to fire");
+    let response: serde_json::Value = serde_json::from_str(&output).expect("Expected json output");
+    let strength = game_state.characters[0].attributes.strength;
+    let recoil = 6 - (2 + strength.div_ceil(3));
+    assert!(
+        response["dice_pool"]["summary"]
+            .as_str()
+            .is_some_and(|summary| summary.contains(&format!("Recoil -{recoil}")))
+    );
+    assert_eq!(response["attack"]["defense_modifier"], -5);
+}
+
+#[test]
+fn test_reroll_restores_the_game_before_the_turn() {
+    use sharad_ratatui::lifestyle::LifestyleTier;
+    use sharad_ratatui::provider::Backend;
+    use tokio::sync::mpsc;
+
+    let (ai_sender, _ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender);
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let mut game_state = GameState::new(Backend::ChatCompletions, "x".to_string());
+    game_state.characters = vec![create_character_from_args(
+        &json_value["function"]["arguments"],
+    )];
+    let snapshot = game_state.clone();
+
+    // The discarded reply started a combat.
+    game_state.usage.start_turn();
+    game_ai
+        .handle_tool_call("start_combat", "{}", &mut game_state)
+        .expect("Failed to start the combat");
+    assert!(game_state.combat.is_some());
+
+    game_state.rewind_for_reroll(snapshot.clone());
+    assert!(game_state.combat.is_none());
+    assert_eq!(game_state.usage.turns.len(), 1);
+
+    // A rerolled month of downtime is paid once, and the clock does not drift.
+    game_state.characters[0]
+        .change_lifestyle(Some(LifestyleTier::Low), None, 0)
+        .expect("Failed to change the lifestyle");
+    let snapshot = game_state.clone();
+    for _ in 0..2 {
+        game_state.rewind_for_reroll(snapshot.clone());
+        game_ai
+            .handle_tool_call("pass_time", r#"{"days": 30}"#, &mut game_state)
+            .expect("Failed to pass time");
+        assert_eq!(game_state.days, 30);
+        assert_eq!(
+            game_state.characters[0].nuyen,
+            snapshot.characters[0].nuyen - 2_000
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_turn_drops_what_it_sent() {
+    use sharad_ratatui::provider::Backend;
+    use sharad_ratatui::ui::game::Turn;
+    use sharad_ratatui::usage::Usage;
+    use tokio::sync::mpsc;
+
+    let (ai_sender, mut ai_receiver) = mpsc::unbounded_channel();
+    let game_ai = GameAI::new(&Settings::default(), ai_sender).for_turn(1);
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let character = create_character_from_args(&json_value["function"]["arguments"]);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "cancel".to_string());
+    game_state.characters = vec![character.clone()];
+    let mut content = vec![Message::new(MessageType::Game, "Welcome".to_string())];
+    let mut turn = Turn {
+        id: 1,
+        handle: Some(tokio::spawn(std::future::pending())),
+        input: "I draw my gun".to_string(),
+        reroll: false,
+        state: game_state.clone(),
+        content_len: content.len(),
+        user_message_id: None,
+        assistant_message_ids: Vec::new(),
+        x_card: false,
+    };
+    content.push(Message::new(MessageType::User, turn.input.clone()));
+
+    // The GM is still answering. The App already applied the fight it started, the nuyen it
+    // took is still queued.
+    let mut turn_state = game_state.clone();
+    game_ai
+        .handle_tool_call("start_combat", "{}", &mut turn_state)
+        .expect("Failed to start the combat");
+    game_state.combat = turn_state.combat.clone();
+    game_ai
+        .handle_tool_call(
+            "update_basic_attributes",
+            &format!(
+                r#"{{"character_name": "{}", "updates": {{"nuyen": 1000}}}}"#,
+                character.name
+            ),
+            &mut turn_state,
+        )
+        .expect("Failed to update the nuyen");
+    game_state.usage.start_turn();
+
+    turn.abort();
+    assert!(turn.handle.is_none());
+    turn.restore(&mut game_state, &mut content);
+    assert!(game_state.combat.is_none());
+    assert_eq!(game_state.characters[0].nuyen, character.nuyen);
+    assert_eq!(game_state.usage.turns.len(), 1);
+    assert_eq!(content.len(), 1);
+
+    // What the cancelled turn queued says which turn it is from, so the App drops it instead of
+    // applying it on top of the game put back. The usage stays counted.
+    game_ai.report_retry(2, 5);
+    game_ai.report_usage(Usage::image());
+    let mut queued = 0;
+    while let Ok(message) = ai_receiver.try_recv() {
+        match message {
+            AIMessage::Turn(1, _) => queued += 1,
+            AIMessage::Usage(_) => {}
+            message => panic!("Untagged turn message: {message:?}"),
+        }
+    }
+    assert!(queued >= 3);
+}
+
+#[test]
+fn test_undo_restores_the_game_and_the_transcript() {
+    use sharad_ratatui::combat::Combat;
+    use sharad_ratatui::provider::Backend;
+    use sharad_ratatui::ui::game::Turn;
+
+    let json_str = fs::read_to_string("tests/dummy_create_character_sheet.json")
+        .expect("Failed to read dummy create character JSON file");
+    let json_value: serde_json::Value =
+        serde_json::from_str(&json_str).expect("Failed to parse JSON");
+    let character = create_character_from_args(&json_value["function"]["arguments"]);
+    let mut game_state = GameState::new(Backend::ChatCompletions, "undo".to_string());
+    game_state.characters = vec![character.clone()];
+    let mut content = vec![Message::new(MessageType::Game, "Welcome".to_string())];
+    let turn = Turn {
+        id: 1,
+        handle: None,
+        input: "I rob the stuffer shack".to_string(),
+        reroll: false,
+        state: game_state.clone(),
+        content_len: content.len(),
+        user_message_id: None,
+        assistant_message_ids: Vec::new(),
+        x_card: false,
+    };
+
+    // The reply arrived and everything it changed was applied.
+    content.push(Message::new(MessageType::User, turn.input.clone()));
+    content.push(Message::new(
+        MessageType::Game,
+        "The clerk pulls a shotgun".to_string(),
+    ));
+    game_state.usage.start_turn();
+    game_state.characters[0].nuyen += 500;
+    game_state.main_character_sheet = Some(game_state.characters[0].clone());
+    game_state
+        .memory
+        .plot_threads
+        .push("The clerk called Lone Star".to_string());
+    game_state.combat = Some(Combat::start(&[&character]));
+    game_state.days = 1;
+
+    turn.restore(&mut game_state, &mut content);
+    assert_eq!(content.len(), 1);
+    assert_eq!(content[0].content, "Welcome");
+    assert_eq!(game_state.characters[0].nuyen, character.nuyen);
+    assert!(game_state.main_character_sheet.is_none());
+    assert!(game_state.memory.plot_threads.is_empty());
+    assert!(game_state.combat.is_none());
+    assert_eq!(game_state.days, 0);
+    // What the turn cost stays counted.
+    assert_eq!(game_state.usage.turns.len(), 1);
+}
+
+#[test]
+fn test_cancelled_reroll_drops_the_reply_from_the_history() {
+    use async_openai::types::{
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+        ChatCompletionRequestToolMessage, ChatCompletionRequestUserMessage,
+    };
+    use sharad_ratatui::provider::Backend;
+
+    let reply = |content: &str| -> ChatCompletionRequestMessage {
+        ChatCompletionRequestAssistantMessageArgs::default()
+            .content(content)
+            .build()
+            .expect("Failed to build reply")
+            .into()
+    };
+    let mut game_state = GameState::new(Backend::ChatCompletions, "reroll".to_string());
+    game_state.history = vec![
+        ChatCompletionRequestUserMessage::from("Start").into(),
+        reply("Welcome"),
+    ];
+    let snapshot = game_state.clone();
+    game_state.history.extend([
+        ChatCompletionRequestUserMessage::from("I hit the gym").into(),
+        ChatCompletionRequestToolMessage {
+            content: "Updated basic attributes for character: Raven".into(),
+            tool_call_id: "call_1".to_string(),
+        }
+        .into(),
+        reply("Body raised to 5."),
+    ]);
+
+    // The reroll drops the reply, and cancelling it puts the game back once more.
+    game_state.rewind_for_reroll(snapshot.clone());
+    game_state.rewind_for_reroll(snapshot);
+    assert_eq!(game_state.history.len(), 3);
+    assert!(matches!(
+        game_state.history.last(),
+        Some(ChatCompletionRequestMessage::User(_))
+    ));
+}
+
+#[test]
+fn test_archive_queue_runs_one_turn_at_a_time() {
+    use sharad_ratatui::archivist::{ArchiveQueue, ArchiveRequest};
+
+    let request = |turn_id| ArchiveRequest {
+        turn_id,
+        player_action: "I hit the gym".to_string(),
+        reply: "Body raised to 5.".to_string(),
+    };
+    let mut queue = ArchiveQueue::default();
+    queue.push(request(None));
+    queue.push(request(Some(1)));
+    queue.push(request(Some(2)));
+    queue.push(request(Some(3)));
+
+    // The opening is archived first, the next turn waits for its memory.
+    let stands = |id| id != 2;
+    assert_eq!(
+        queue.next(stands).map(|request| request.turn_id),
+        Some(None)
+    );
+    assert!(queue.next(stands).is_none());
+    queue.done();
+    assert_eq!(
+        queue.next(stands).map(|request| request.turn_id),
+        Some(Some(1))
+    );
+    queue.done();
+    // The second turn was taken back meanwhile.
+    assert_eq!(
+        queue.next(stands).map(|request| request.turn_id),
+        Some(Some(3))
+    );
+    queue.done();
+    assert!(queue.next(stands).is_none());
+}